@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 
@@ -30,7 +31,29 @@ pub trait AgentBehavior: Send + Sync {
     /// Process received messages
     async fn process_message(&mut self, message: Message) -> Result<Option<Message>>;
 
-    /// Execute the Agent's main loop (if any)
+    /// Topic patterns the Agent is subscribed to on the message bus when it is spawned
+    fn topic_subscriptions(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Interval between calls to `tick`, or `None` if the Agent has no background work
+    fn tick_interval(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Do one round of background work
+    ///
+    /// Ticks run to completion between messages and are never cancelled, so they should be
+    /// short; an error stops the Agent.
+    async fn tick(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Former main loop of the Agent
+    ///
+    /// No longer called: `AgentManager` used to race it against message handling, which
+    /// cancelled it at any await. Move periodic work to `tick_interval` and `tick`.
+    #[deprecated(note = "not called by AgentManager; implement `tick_interval` and `tick`")]
     async fn run(&mut self) -> Result<()> {
         Ok(())
     }
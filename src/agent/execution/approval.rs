@@ -0,0 +1,247 @@
+use std::{fmt, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Answers accepted when asking for approval
+pub const APPROVE: &str = "approve";
+pub const EDIT: &str = "edit";
+pub const SKIP: &str = "skip";
+
+type Predicate = Arc<dyn Fn(&str, &Value) -> bool + Send + Sync>;
+
+/// What makes a tool call require approval
+#[derive(Clone)]
+enum ApprovalRule {
+    /// The tool with this name
+    Tool(String),
+    /// Any tool served by this MCP server
+    McpServer(String),
+    /// Calls for which the predicate on tool name and arguments holds
+    Predicate { name: String, predicate: Predicate },
+}
+
+impl ApprovalRule {
+    fn matches(&self, tool: &str, mcp_server: &str, arguments: &Value) -> bool {
+        match self {
+            Self::Tool(name) => name == tool,
+            Self::McpServer(server) => server == mcp_server,
+            Self::Predicate { predicate, .. } => predicate(tool, arguments),
+        }
+    }
+}
+
+impl fmt::Display for ApprovalRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tool(name) => write!(f, "tool:{name}"),
+            Self::McpServer(server) => write!(f, "mcp_server:{server}"),
+            Self::Predicate { name, .. } => write!(f, "predicate:{name}"),
+        }
+    }
+}
+
+/// Which tool calls have to be approved by a human before they run
+#[derive(Clone, Default)]
+pub struct ApprovalPolicy {
+    rules: Vec<ApprovalRule>,
+}
+
+impl ApprovalPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require approval for every call of `tool`
+    pub fn require_tool(mut self, tool: impl Into<String>) -> Self {
+        self.rules.push(ApprovalRule::Tool(tool.into()));
+        self
+    }
+
+    /// Require approval for every tool of an MCP server
+    pub fn require_mcp_server(mut self, server: impl Into<String>) -> Self {
+        self.rules.push(ApprovalRule::McpServer(server.into()));
+        self
+    }
+
+    /// Require approval for the calls `predicate` holds for, given tool name and arguments
+    ///
+    /// `name` identifies the rule in the recorded decisions.
+    pub fn require_when(
+        mut self,
+        name: impl Into<String>,
+        predicate: impl Fn(&str, &Value) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.rules.push(ApprovalRule::Predicate {
+            name: name.into(),
+            predicate: Arc::new(predicate),
+        });
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// The first rule requiring approval for this call, if any
+    pub fn required_by(&self, tool: &str, mcp_server: &str, arguments: &Value) -> Option<String> {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(tool, mcp_server, arguments))
+            .map(ToString::to_string)
+    }
+}
+
+impl fmt::Debug for ApprovalPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rules: Vec<String> = self.rules.iter().map(ToString::to_string).collect();
+        f.debug_struct("ApprovalPolicy").field("rules", &rules).finish()
+    }
+}
+
+/// How a human answered an approval request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "decision", rename_all = "snake_case")]
+pub enum ApprovalDecision {
+    Approved,
+    /// Approved with replaced arguments
+    Edited { arguments: Value },
+    /// The call was not made and the step was skipped
+    Skipped,
+    /// The replacement arguments were not valid JSON, so the call was not made
+    InvalidEdit { answer: String },
+}
+
+/// A recorded approval decision, kept in the trace of the step that asked for it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApprovalRecord {
+    pub tool: String,
+    /// Arguments presented for approval
+    pub arguments: Value,
+    /// The rule that required approval
+    pub rule: String,
+    pub decision: ApprovalDecision,
+    pub decided_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use serde_json::json;
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+    use crate::{
+        agent::{
+            Executor, context::AgentContext, human::ChannelHumanInterface, memory::Memory,
+            planning::AgentStep, state::AgentState, types::StepResult,
+        },
+        error::Result,
+        tools::native::{Tool, register_native_tool},
+    };
+
+    struct CountTool;
+
+    #[async_trait]
+    impl Tool for CountTool {
+        fn name(&self) -> &str {
+            "approval_test_count"
+        }
+
+        fn description(&self) -> &str {
+            "Echo the count"
+        }
+
+        fn input_schema(&self) -> Value {
+            json!({"type": "object", "required": ["n"]})
+        }
+
+        async fn call(&self, arguments: Value) -> Result<Value> {
+            Ok(json!({ "result": arguments["n"].to_string() }))
+        }
+    }
+
+    fn step(n: u64) -> AgentStep {
+        AgentStep {
+            step_id: 1,
+            description: "count".to_string(),
+            action: "call_tool".to_string(),
+            tool: Some("approval_test_count".to_string()),
+            parameters: Some(json!({ "n": n })),
+            ..Default::default()
+        }
+    }
+
+    async fn execute(executor: &Executor, step: &AgentStep) -> StepResult {
+        executor
+            .execute(
+                step,
+                &AgentContext::default(),
+                &Memory::default(),
+                &AgentState::default(),
+                &CancellationToken::new(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_tool_calls_wait_for_approval() {
+        register_native_tool(Arc::new(CountTool));
+        let (human, mut questions) = ChannelHumanInterface::new();
+        let policy = ApprovalPolicy::new()
+            .require_when("large count", |_, arguments| arguments["n"].as_u64() > Some(10));
+        let executor = Executor::new(Arc::new(human)).with_approval_policy(policy);
+
+        // Small counts need no approval
+        let result = execute(&executor, &step(1)).await;
+        assert_eq!(result.output, json!({"result": "1"}).to_string());
+        assert!(result.trace.is_empty());
+
+        tokio::spawn(async move {
+            let rounds = [
+                vec!["approve"],
+                vec!["edit", r#"{"n": 12}"#],
+                vec!["3"],
+                vec!["edit", "n = 12"],
+            ];
+            for answers in rounds {
+                for answer in answers {
+                    questions.recv().await.unwrap().answer(answer);
+                }
+            }
+        });
+
+        let approved = execute(&executor, &step(11)).await;
+        assert_eq!(approved.output, json!({"result": "11"}).to_string());
+        assert_eq!(approved.trace[0].decision, ApprovalDecision::Approved);
+        assert_eq!(approved.trace[0].rule, "predicate:large count");
+
+        let edited = execute(&executor, &step(20)).await;
+        assert_eq!(edited.output, json!({"result": "12"}).to_string());
+        assert_eq!(edited.trace[0].arguments, json!({"n": 20}));
+        assert_eq!(
+            edited.trace[0].decision,
+            ApprovalDecision::Edited {
+                arguments: json!({"n": 12})
+            }
+        );
+
+        // A skipped call is not a successful step
+        let skipped = execute(&executor, &step(30)).await;
+        assert!(!skipped.success);
+        assert!(skipped.output.contains("\"skipped\":true"));
+        assert_eq!(skipped.trace[0].decision, ApprovalDecision::Skipped);
+
+        // An edit that is not JSON fails the step but is still recorded
+        let invalid = execute(&executor, &step(40)).await;
+        assert!(!invalid.success);
+        assert_eq!(
+            invalid.trace[0].decision,
+            ApprovalDecision::InvalidEdit {
+                answer: "n = 12".to_string()
+            }
+        );
+    }
+}
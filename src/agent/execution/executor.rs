@@ -1,37 +1,183 @@
-use std::io::{self, Write};
+use std::{fmt, sync::Arc};
 
-use mcp_client::registry::get_mcp_registry;
+use chrono::Utc;
 use serde_json::json;
+use tokio_util::sync::CancellationToken;
 
 use crate::{
-    agent::{context::AgentContext, memory::Memory, planning::AgentStep, types::StepResult},
-    error::agent_error::AgentError,
-    tools::model::TOOL_REGISTRY,
+    agent::{
+        context::AgentContext,
+        execution::{
+            approval::{APPROVE, ApprovalDecision, ApprovalPolicy, ApprovalRecord, EDIT, SKIP},
+            reference::resolve_references,
+        },
+        human::{HumanInterface, HumanQuestion, TerminalHumanInterface},
+        memory::Memory,
+        planning::AgentStep,
+        state::AgentState,
+        types::StepResult,
+    },
+    error::{Error, agent_error::AgentError},
+    tools::{model::TOOL_REGISTRY, native::resolve_tool, schema},
 };
 
-#[derive(Debug, Default, Clone)]
-pub struct Executor;
+#[derive(Clone)]
+pub struct Executor {
+    human: Arc<dyn HumanInterface>,
+    approval: ApprovalPolicy,
+}
+
+impl Default for Executor {
+    fn default() -> Self {
+        Self::new(Arc::new(TerminalHumanInterface))
+    }
+}
+
+impl fmt::Debug for Executor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Executor")
+            .field("approval", &self.approval)
+            .finish_non_exhaustive()
+    }
+}
 
 impl Executor {
-    async fn call_mcp_tool(
+    /// Create an executor that asks `human` for the answers to `ask_user` steps
+    pub fn new(human: Arc<dyn HumanInterface>) -> Self {
+        Self {
+            human,
+            approval: ApprovalPolicy::default(),
+        }
+    }
+
+    /// Set where `ask_user` steps and approval requests get their answers from
+    pub fn with_human_interface(mut self, human: Arc<dyn HumanInterface>) -> Self {
+        self.human = human;
+        self
+    }
+
+    /// Set which tool calls need a human's approval before they run
+    pub fn with_approval_policy(mut self, approval: ApprovalPolicy) -> Self {
+        self.approval = approval;
+        self
+    }
+
+    /// Answer an `ask_user` step
+    async fn ask_user(
+        &self,
+        step: &AgentStep,
+        cancel: &CancellationToken,
+    ) -> Result<String, AgentError> {
+        let question = match &step.input {
+            Some(input) if input.get("question").is_some() => {
+                serde_json::from_value::<HumanQuestion>(input.clone()).map_err(|e| {
+                    AgentError::ParseError(format!("ask_user 的 input 格式不正确: {e}"))
+                })?
+            }
+            _ => HumanQuestion::new(step.description.clone()),
+        };
+        println!("🧑 等待用户回答: {}", question.question);
+        self.ask(&question, cancel).await
+    }
+
+    /// Ask for approval of a tool call when the policy requires it
+    ///
+    /// Returns the arguments to call the tool with, or `None` when the call is skipped. The
+    /// decision is appended to `trace`, also when an edit is rejected with an error.
+    async fn review_tool_call(
+        &self,
+        tool_name: &str,
+        arguments: serde_json::Value,
+        cancel: &CancellationToken,
+        trace: &mut Vec<ApprovalRecord>,
+    ) -> Result<Option<serde_json::Value>, AgentError> {
+        if self.approval.is_empty() {
+            return Ok(Some(arguments));
+        }
+        let mcp_server = TOOL_REGISTRY
+            .read()
+            .unwrap()
+            .get(tool_name)
+            .map(|tool_info| tool_info.mcp_server.clone())
+            .unwrap_or_default();
+        let Some(rule) = self.approval.required_by(tool_name, &mcp_server, &arguments) else {
+            return Ok(Some(arguments));
+        };
+
+        println!("🔐 工具 {tool_name} 需要审批 ({rule})");
+        let question = HumanQuestion::new(format!(
+            "是否允许调用工具 {tool_name}？参数: {arguments}"
+        ))
+        .with_choices(vec![APPROVE.into(), EDIT.into(), SKIP.into()]);
+
+        let (decision, approved) = match self.ask(&question, cancel).await?.as_str() {
+            EDIT => {
+                let question = HumanQuestion::new(format!("请输入工具 {tool_name} 的新参数（JSON）"));
+                let answer = self.ask(&question, cancel).await?;
+                match serde_json::from_str::<serde_json::Value>(&answer) {
+                    Ok(edited) => {
+                        let decision = ApprovalDecision::Edited {
+                            arguments: edited.clone(),
+                        };
+                        (decision, Ok(Some(edited)))
+                    }
+                    Err(e) => (
+                        ApprovalDecision::InvalidEdit { answer },
+                        Err(AgentError::ParseError(format!("新参数不是合法的 JSON: {e}"))),
+                    ),
+                }
+            }
+            SKIP => (ApprovalDecision::Skipped, Ok(None)),
+            _ => (ApprovalDecision::Approved, Ok(Some(arguments.clone()))),
+        };
+        println!("🔐 审批结果: {decision:?}");
+
+        trace.push(ApprovalRecord {
+            tool: tool_name.to_string(),
+            arguments,
+            rule,
+            decision,
+            decided_at: Utc::now(),
+        });
+        approved
+    }
+
+    /// Ask the user through the human interface, giving up when `cancel` is cancelled
+    async fn ask(
+        &self,
+        question: &HumanQuestion,
+        cancel: &CancellationToken,
+    ) -> Result<String, AgentError> {
+        let answer = tokio::select! {
+            biased;
+            _ = cancel.cancelled() => return Err(AgentError::Cancelled),
+            answer = self.human.ask(question) => answer,
+        };
+
+        let answer = answer.map_err(|e| match e {
+            Error::AgentError(e) => e,
+            other => AgentError::ExecutionError(format!("读取用户回答失败: {other}")),
+        })?;
+        question.resolve_answer(&answer).ok_or_else(|| {
+            AgentError::ExecutionError(format!(
+                "回答 {answer:?} 不在可选项中: {}",
+                question.choices.join(", ")
+            ))
+        })
+    }
+
+    /// Validate arguments and dispatch to a native or MCP tool
+    async fn call_tool(
         &self,
         tool_name: &str,
-        parameters: &Option<serde_json::Value>,
+        arguments: serde_json::Value,
+        cancel: &CancellationToken,
     ) -> Result<serde_json::Value, AgentError> {
-        println!("🛠️ 调用 MCP 工具: {tool_name}");
-        
-        // 1. 从 TOOL_REGISTRY 获取工具信息和对应的 MCP 服务器
-        let mcp_server_name = {
+        // 1. 从 TOOL_REGISTRY 获取工具的输入 schema
+        let params_schema = {
             let tool_registry = TOOL_REGISTRY.read().unwrap();
             match tool_registry.get(tool_name) {
-                Some(tool_info) => {
-                    if tool_info.mcp_server.is_empty() {
-                        return Err(AgentError::ExecutionError(
-                            format!("工具 '{tool_name}' 不是 MCP 工具")
-                        ));
-                    }
-                    tool_info.mcp_server.clone()
-                },
+                Some(tool_info) => tool_info.params_schema.clone(),
                 None => {
                     return Err(AgentError::ExecutionError(
                         format!("未找到工具: {tool_name}")
@@ -40,84 +186,135 @@ impl Executor {
             }
         };
 
-        // 2. 通过 MCP 服务器名称获取对应的客户端
-        let registry = get_mcp_registry();
-        let client = registry.get(&mcp_server_name).map_err(|e| {
-            AgentError::ExecutionError(format!(
-                "无法获取 MCP 服务器 '{mcp_server_name}' 的客户端: {e}"
-            ))
+        // 2. 按工具的输入 schema 校验参数，不合法时不发起调用
+        if let Err(errors) = schema::validate(&params_schema, &arguments) {
+            return Err(AgentError::InvalidToolParameters {
+                tool: tool_name.to_string(),
+                errors,
+            });
+        }
+
+        // 3. 解析为原生工具或 MCP 工具后统一调用
+        let tool = resolve_tool(tool_name).ok_or_else(|| {
+            AgentError::ExecutionError(format!("工具 '{tool_name}' 没有可用的实现"))
         })?;
 
-        // 3. 构造工具调用参数，按照你提供的格式
-        let tool_call_params = match parameters {
-            Some(params) => {
-                // 如果 parameters 已经包含了 name 和 arguments，直接使用
-                if params.get("name").is_some() && params.get("arguments").is_some() {
-                    params.clone()
-                } else {
-                    // 否则包装成标准格式
-                    json!({
-                        "name": tool_name,
-                        "arguments": params
-                    })
-                }
-            }
-            None => {
-                json!({
-                    "name": tool_name,
-                    "arguments": {}
-                })
-            }
+        // 4. 取消时放弃尚未完成的调用
+        let result = tokio::select! {
+            biased;
+            _ = cancel.cancelled() => return Err(AgentError::Cancelled),
+            result = tool.call(arguments) => result,
         };
 
-        println!("🔧 调用 MCP 服务器 '{mcp_server_name}' 的工具 '{tool_name}', 参数: {tool_call_params:?}");
+        result.map_err(|e| match e {
+            Error::AgentError(e) => e,
+            other => AgentError::ExecutionError(format!("工具 '{tool_name}' 调用失败: {other}")),
+        })
+    }
 
-        // 4. 调用 MCP 工具
-        match client.call_tool(tool_call_params).await {
-            Ok(response) => {
-                println!("✅ MCP工具调用成功: {response:?}");
-                // 尝试解析响应为JSON
-                match response {
-                    mcp_client::core::protocol::message::JsonRpcMessage::Response(resp) => {
-                        Ok(resp.result.unwrap_or(json!({"success": true})))
-                    }
-                    _ => Ok(json!({"result": "工具执行完成"})),
-                }
-            }
-            Err(e) => Err(AgentError::ExecutionError(format!(
-                "MCP工具 '{tool_name}' 调用失败: {e}"
-            ))),
+    /// Replace `{{steps.N.output...}}` and `{{memory...}}` references in the step's
+    /// `parameters` and `input` with values from earlier results
+    pub fn resolve_step(
+        &self,
+        step: &AgentStep,
+        state: &AgentState,
+        memory: &Memory,
+    ) -> Result<AgentStep, AgentError> {
+        let mut resolved = step.clone();
+        if let Some(parameters) = &step.parameters {
+            resolved.parameters = Some(resolve_references(parameters, state, memory)?);
+        }
+        if let Some(input) = &step.input {
+            resolved.input = Some(resolve_references(input, state, memory)?);
         }
+        Ok(resolved)
     }
 
+    /// Execute one step; a cancelled `cancel` token aborts it with `AgentError::Cancelled`
     pub async fn execute(
         &self,
         step: &AgentStep,
-        // TODO: 添加 AgentContext 和 Memory 参数
         _context: &AgentContext,
-        _memory: &Memory,
+        memory: &Memory,
+        state: &AgentState,
+        cancel: &CancellationToken,
     ) -> Result<StepResult, AgentError> {
+        if cancel.is_cancelled() {
+            return Err(AgentError::Cancelled);
+        }
+        let step = &self.resolve_step(step, state, memory)?;
+
         match step.action.as_str() {
             "call_tool" => {
                 if let Some(tool_name) = &step.tool {
                     println!("🛠️ 调用工具 [{tool_name}]，参数: {:?}", step.parameters);
 
-                    // 实际调用 MCP 工具
-                    match self.call_mcp_tool(tool_name, &step.parameters).await {
+                    // 需要审批的调用先交给用户决定
+                    let mut trace = Vec::new();
+                    let reviewed = self
+                        .review_tool_call(tool_name, step.tool_arguments(), cancel, &mut trace)
+                        .await;
+                    let arguments = match reviewed {
+                        Ok(arguments) => arguments,
+                        Err(AgentError::Cancelled) => return Err(AgentError::Cancelled),
+                        // 审批未通过时保留已记录的决定
+                        Err(e) => {
+                            return Ok(StepResult {
+                                output: json!({ "error": e.to_string() }).to_string(),
+                                success: false,
+                                trace,
+                            });
+                        }
+                    };
+                    // 被跳过的敏感操作不算完成
+                    let Some(arguments) = arguments else {
+                        let skipped = json!({
+                            "result": format!("用户跳过了工具 {tool_name} 的调用"),
+                            "skipped": true,
+                        });
+                        return Ok(StepResult {
+                            output: skipped.to_string(),
+                            success: false,
+                            trace,
+                        });
+                    };
+
+                    // 实际调用工具（原生或 MCP）
+                    match self.call_tool(tool_name, arguments, cancel).await {
                         Ok(result) => Ok(StepResult {
                             output: result.to_string(),
                             success: true,
+                            trace,
                         }),
+                        Err(AgentError::Cancelled) => {
+                            println!("🛑 工具调用已取消: {tool_name}");
+                            Err(AgentError::Cancelled)
+                        }
+                        Err(AgentError::InvalidToolParameters { tool, errors }) => {
+                            let summary = schema::format_schema_errors(&errors);
+                            println!("❌ 工具 {tool} 参数校验失败: {summary}");
+                            // 工具并未被调用；保留带路径的校验错误，供修复与校验环节使用
+                            let rejected = json!({
+                                "error": format!("工具 '{tool}' 参数校验失败: {summary}"),
+                                "tool": tool,
+                                "schema_errors": errors,
+                            });
+                            Ok(StepResult {
+                                output: rejected.to_string(),
+                                success: false,
+                                trace,
+                            })
+                        }
                         Err(e) => {
-                            println!("❌ MCP工具调用失败: {e:?}");
-                            // 降级为模拟输出
-                            let simulated_output = json!({
-                                "result": format!("{tool_name} 工具执行失败，使用模拟结果"),
+                            println!("❌ 工具调用失败: {e:?}");
+                            let failed = json!({
                                 "error": e.to_string(),
+                                "tool": tool_name,
                             });
                             Ok(StepResult {
-                                output: simulated_output.to_string(),
+                                output: failed.to_string(),
                                 success: false,
+                                trace,
                             })
                         }
                     }
@@ -127,29 +324,15 @@ impl Executor {
             }
 
             "ask_user" => {
-                println!("🧑 等待用户回答: {:?}", step.input);
-
-                // 提示问题
-                if let Some(input) = &step.input
-                    && let Some(question) = input.get("question").and_then(|v| v.as_str()) {
-                        println!("❓ {question}");
-                }
-
-                print!("👉 请输入你的回答：");
-                io::stdout().flush().unwrap(); // 确保立即输出提示
-
-                let mut user_input = String::new();
-                io::stdin()
-                    .read_line(&mut user_input)
-                    .map_err(|e| AgentError::ExecutionError(format!("读取用户输入失败: {e}")))?;
-                let user_input = user_input.trim(); // 去除换行符
+                let answer = self.ask_user(step, cancel).await?;
 
                 // 构造返回结果
-                let answer_json = json!({ "answer": user_input });
+                let answer_json = json!({ "answer": answer });
 
                 Ok(StepResult {
                     output: answer_json.to_string(),
                     success: true,
+                    ..Default::default()
                 })
             }
 
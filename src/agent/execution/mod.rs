@@ -1,3 +1,6 @@
+pub mod approval;
 pub mod executor;
+pub mod reference;
 
+pub use approval::{ApprovalDecision, ApprovalPolicy, ApprovalRecord};
 pub use executor::Executor;
\ No newline at end of file
@@ -0,0 +1,252 @@
+use serde_json::Value;
+
+use crate::{
+    agent::{memory::Memory, state::AgentState},
+    error::agent_error::AgentError,
+};
+
+/// Resolve `{{...}}` references inside a JSON value.
+///
+/// Supported references:
+/// - `{{steps.<step_id>.output}}` / `{{steps.<step_id>.output.<path>}}`: output of an earlier
+///   step (parsed as JSON when possible)
+/// - `{{steps.<step_id>.success}}`: whether that step succeeded
+/// - `{{memory.<key>}}` / `{{memory.<key>.<path>}}`: a variable stored in `Memory`
+///
+/// A string consisting of a single reference is replaced by the referenced value with its
+/// JSON type preserved; references embedded in longer strings are interpolated as text.
+pub fn resolve_references(
+    value: &Value,
+    state: &AgentState,
+    memory: &Memory,
+) -> Result<Value, AgentError> {
+    match value {
+        Value::String(text) => resolve_string(text, state, memory),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| resolve_references(item, state, memory))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        Value::Object(map) => {
+            let mut resolved = serde_json::Map::with_capacity(map.len());
+            for (key, item) in map {
+                resolved.insert(key.clone(), resolve_references(item, state, memory)?);
+            }
+            Ok(Value::Object(resolved))
+        }
+        other => Ok(other.clone()),
+    }
+}
+
+/// Whether a JSON value contains any `{{...}}` reference
+pub fn contains_references(value: &Value) -> bool {
+    match value {
+        Value::String(text) => text.contains("{{"),
+        Value::Array(items) => items.iter().any(contains_references),
+        Value::Object(map) => map.values().any(contains_references),
+        _ => false,
+    }
+}
+
+/// Ids of the steps referenced through `{{steps.<step_id>...}}` inside a JSON value
+pub fn referenced_steps(value: &Value) -> Vec<usize> {
+    let mut ids = Vec::new();
+    collect_referenced_steps(value, &mut ids);
+    ids
+}
+
+fn collect_referenced_steps(value: &Value, ids: &mut Vec<usize>) {
+    match value {
+        Value::String(text) => {
+            let mut rest = text.as_str();
+            while let Some(start) = rest.find("{{") {
+                rest = &rest[start + 2 ..];
+                let Some(end) = rest.find("}}") else {
+                    break;
+                };
+                let mut segments = rest[.. end].trim().split('.');
+                if segments.next() == Some("steps")
+                    && let Some(Ok(id)) = segments.next().map(str::parse::<usize>)
+                    && !ids.contains(&id)
+                {
+                    ids.push(id);
+                }
+                rest = &rest[end + 2 ..];
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| collect_referenced_steps(item, ids)),
+        Value::Object(map) => map.values().for_each(|item| collect_referenced_steps(item, ids)),
+        _ => {}
+    }
+}
+
+fn resolve_string(text: &str, state: &AgentState, memory: &Memory) -> Result<Value, AgentError> {
+    if !text.contains("{{") {
+        return Ok(Value::String(text.to_string()));
+    }
+
+    // A lone reference keeps the referenced value's type
+    let trimmed = text.trim();
+    if let Some(inner) = trimmed.strip_prefix("{{").and_then(|t| t.strip_suffix("}}"))
+        && !inner.contains("{{")
+        && !inner.contains("}}")
+    {
+        return lookup(inner.trim(), state, memory);
+    }
+
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[.. start]);
+        let after = &rest[start + 2 ..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| AgentError::UnresolvedReference(format!("未闭合的引用: {text}")))?;
+
+        match lookup(after[.. end].trim(), state, memory)? {
+            Value::String(s) => output.push_str(&s),
+            other => output.push_str(&other.to_string()),
+        }
+        rest = &after[end + 2 ..];
+    }
+    output.push_str(rest);
+
+    Ok(Value::String(output))
+}
+
+fn lookup(reference: &str, state: &AgentState, memory: &Memory) -> Result<Value, AgentError> {
+    let mut segments = reference.split('.');
+
+    match segments.next() {
+        Some("steps") => {
+            let step_id = segments.next().ok_or_else(|| {
+                AgentError::UnresolvedReference(format!("缺少步骤 ID: {reference}"))
+            })?;
+            let result = state.step_results.get(step_id).ok_or_else(|| {
+                AgentError::UnresolvedReference(format!(
+                    "{reference}: 步骤 {step_id} 尚无执行结果"
+                ))
+            })?;
+
+            match segments.next() {
+                Some("output") => {
+                    let output = serde_json::from_str(&result.output)
+                        .unwrap_or_else(|_| Value::String(result.output.clone()));
+                    walk(output, segments, reference)
+                }
+                Some("success") => Ok(Value::Bool(result.success)),
+                Some(other) => Err(AgentError::UnresolvedReference(format!(
+                    "{reference}: 未知字段 {other}"
+                ))),
+                None => Err(AgentError::UnresolvedReference(format!(
+                    "{reference}: 缺少 output 或 success 字段"
+                ))),
+            }
+        }
+        Some("memory") => {
+            let key = segments.next().ok_or_else(|| {
+                AgentError::UnresolvedReference(format!("缺少变量名: {reference}"))
+            })?;
+            let value = memory.get_variable(key).cloned().ok_or_else(|| {
+                AgentError::UnresolvedReference(format!("{reference}: 变量 {key} 不存在"))
+            })?;
+            walk(value, segments, reference)
+        }
+        _ => Err(AgentError::UnresolvedReference(format!(
+            "{reference}: 引用必须以 steps. 或 memory. 开头"
+        ))),
+    }
+}
+
+fn walk<'a>(
+    mut value: Value,
+    segments: impl Iterator<Item = &'a str>,
+    reference: &str,
+) -> Result<Value, AgentError> {
+    for segment in segments {
+        let next = match &value {
+            Value::Object(map) => map.get(segment).cloned(),
+            Value::Array(items) => segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| items.get(i).cloned()),
+            _ => None,
+        };
+        value = next.ok_or_else(|| {
+            AgentError::UnresolvedReference(format!("{reference}: 路径 {segment} 不存在"))
+        })?;
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::agent::types::StepResult;
+
+    fn state() -> AgentState {
+        let mut state = AgentState::default();
+        state.append_result(
+            1,
+            StepResult {
+                output: json!({"result": "ok", "items": [{"id": 7}]}).to_string(),
+                success: true,
+                ..Default::default()
+            },
+        );
+        state.append_result(
+            2,
+            StepResult {
+                output: json!({"answer": "Beijing"}).to_string(),
+                success: true,
+                ..Default::default()
+            },
+        );
+        state
+    }
+
+    #[test]
+    fn test_resolve_typed_and_interpolated_references() {
+        let mut memory = Memory::default();
+        memory.set_variable("lang".to_string(), json!("zh"));
+
+        let params = json!({
+            "id": "{{steps.1.output.items.0.id}}",
+            "query": "weather in {{steps.2.output.answer}} ({{memory.lang}})",
+            "ok": "{{ steps.1.success }}",
+            "plain": 3
+        });
+
+        let resolved = resolve_references(&params, &state(), &memory).unwrap();
+        assert_eq!(
+            resolved,
+            json!({
+                "id": 7,
+                "query": "weather in Beijing (zh)",
+                "ok": true,
+                "plain": 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_missing_references_are_errors() {
+        let memory = Memory::default();
+        for reference in [
+            "{{steps.9.output}}",
+            "{{steps.1.output.missing}}",
+            "{{memory.unknown}}",
+            "{{other.1}}",
+            "broken {{steps.1.output",
+        ] {
+            let result = resolve_references(&json!(reference), &state(), &memory);
+            assert!(
+                matches!(result, Err(AgentError::UnresolvedReference(_))),
+                "{reference} should fail"
+            );
+        }
+    }
+}
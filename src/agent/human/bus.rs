@@ -0,0 +1,68 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+
+use crate::{
+    agent::human::{HumanInterface, HumanQuestion},
+    error::{Result, agent_error::AgentError},
+    multi_agent::communication::{Message, MessageBus, MessageType},
+};
+
+/// Message type of the questions sent over the bus
+pub const USER_QUESTION: &str = "UserQuestion";
+
+/// How long to wait for questions without their own timeout
+const DEFAULT_ANSWER_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Sends each question as a `Custom("UserQuestion")` message and waits for the reply
+///
+/// The question is the message payload. Whoever talks to the user answers with a message
+/// whose `correlation_id` is the question's id and whose payload holds an `answer` string.
+pub struct BusHumanInterface {
+    bus: Arc<MessageBus>,
+    sender_id: String,
+    receiver_id: String,
+    default_timeout: Duration,
+}
+
+impl BusHumanInterface {
+    /// Ask on behalf of `sender_id` and send the questions to `receiver_id`
+    pub fn new(bus: Arc<MessageBus>, sender_id: String, receiver_id: String) -> Self {
+        Self {
+            bus,
+            sender_id,
+            receiver_id,
+            default_timeout: DEFAULT_ANSWER_TIMEOUT,
+        }
+    }
+
+    /// Set how long to wait for questions without their own timeout
+    pub fn with_default_timeout(mut self, timeout: Duration) -> Self {
+        self.default_timeout = timeout;
+        self
+    }
+}
+
+#[async_trait]
+impl HumanInterface for BusHumanInterface {
+    async fn ask(&self, question: &HumanQuestion) -> Result<String> {
+        let message = Message::new(
+            self.sender_id.clone(),
+            Some(self.receiver_id.clone()),
+            MessageType::Custom(USER_QUESTION.to_string()),
+            serde_json::to_value(question)?,
+        );
+
+        let timeout = question.timeout().unwrap_or(self.default_timeout);
+        let reply = self.bus.request(message, timeout).await?;
+
+        match reply.payload.get("answer").and_then(|v| v.as_str()) {
+            Some(answer) => Ok(answer.to_string()),
+            None => Err(AgentError::ParseError(format!(
+                "{} 的回答中缺少 answer 字段",
+                reply.sender_id
+            ))
+            .into()),
+        }
+    }
+}
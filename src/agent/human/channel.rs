@@ -0,0 +1,55 @@
+use async_trait::async_trait;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::{
+    agent::human::{HumanInterface, HumanQuestion, await_answer},
+    error::{Result, agent_error::AgentError},
+};
+
+/// A question waiting for its answer
+#[derive(Debug)]
+pub struct PendingQuestion {
+    pub question: HumanQuestion,
+    reply: oneshot::Sender<String>,
+}
+
+impl PendingQuestion {
+    /// Answer the question; does nothing if the asker stopped waiting
+    pub fn answer(self, answer: impl Into<String>) {
+        let _ = self.reply.send(answer.into());
+    }
+}
+
+/// Hands questions over a channel, for tests and applications that collect answers themselves
+#[derive(Debug, Clone)]
+pub struct ChannelHumanInterface {
+    questions: mpsc::UnboundedSender<PendingQuestion>,
+}
+
+impl ChannelHumanInterface {
+    /// Create the interface and the receiver on which its questions arrive
+    pub fn new() -> (Self, mpsc::UnboundedReceiver<PendingQuestion>) {
+        let (questions, receiver) = mpsc::unbounded_channel();
+        (Self { questions }, receiver)
+    }
+}
+
+#[async_trait]
+impl HumanInterface for ChannelHumanInterface {
+    async fn ask(&self, question: &HumanQuestion) -> Result<String> {
+        let (reply, answer) = oneshot::channel();
+        self.questions
+            .send(PendingQuestion {
+                question: question.clone(),
+                reply,
+            })
+            .map_err(|_| AgentError::MessageDeliveryError("问题接收方已关闭".into()))?;
+
+        await_answer(question, async {
+            answer.await.map_err(|_| {
+                AgentError::MessageDeliveryError("问题未被回答就被丢弃".into()).into()
+            })
+        })
+        .await
+    }
+}
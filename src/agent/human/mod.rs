@@ -0,0 +1,193 @@
+pub mod bus;
+pub mod channel;
+pub mod terminal;
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+pub use bus::BusHumanInterface;
+pub use channel::{ChannelHumanInterface, PendingQuestion};
+pub use terminal::TerminalHumanInterface;
+
+use crate::error::{Result, agent_error::AgentError};
+
+/// A question put to the user by an `ask_user` step
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HumanQuestion {
+    pub question: String,
+
+    /// Answers the user has to pick from; any answer is accepted when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub choices: Vec<String>,
+
+    /// How long to wait for the answer; waits indefinitely when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+impl HumanQuestion {
+    pub fn new(question: impl Into<String>) -> Self {
+        Self {
+            question: question.into(),
+            choices: Vec::new(),
+            timeout_secs: None,
+        }
+    }
+
+    pub fn with_choices(mut self, choices: Vec<String>) -> Self {
+        self.choices = choices;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout_secs = Some(timeout.as_secs());
+        self
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout_secs.map(Duration::from_secs)
+    }
+
+    /// Match an answer against the choices, by text or by 1-based number
+    ///
+    /// Returns the chosen text, or `None` when the answer is not one of the choices.
+    pub fn resolve_answer(&self, answer: &str) -> Option<String> {
+        let answer = answer.trim();
+        if self.choices.is_empty() {
+            return Some(answer.to_string());
+        }
+        if let Some(choice) = self.choices.iter().find(|c| c.eq_ignore_ascii_case(answer)) {
+            return Some(choice.clone());
+        }
+        answer
+            .parse::<usize>()
+            .ok()
+            .and_then(|n| n.checked_sub(1))
+            .and_then(|i| self.choices.get(i))
+            .cloned()
+    }
+}
+
+/// Where `ask_user` steps get their answers from
+#[async_trait]
+pub trait HumanInterface: Send + Sync {
+    /// Ask the user a question and wait for the raw answer
+    ///
+    /// Fails when no answer arrives within the question's timeout.
+    async fn ask(&self, question: &HumanQuestion) -> Result<String>;
+}
+
+/// Wait for `answer`, failing with `AgentError::AnswerTimeout` once the question's timeout passes
+async fn await_answer(
+    question: &HumanQuestion,
+    answer: impl Future<Output = Result<String>>,
+) -> Result<String> {
+    match question.timeout() {
+        Some(timeout) => tokio::time::timeout(timeout, answer)
+            .await
+            .unwrap_or_else(|_| Err(AgentError::AnswerTimeout(question.question.clone()).into())),
+        None => answer.await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::json;
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+    use crate::{
+        agent::{
+            Executor, context::AgentContext, memory::Memory, planning::AgentStep,
+            state::AgentState,
+        },
+        multi_agent::communication::{Message, MessageBus, MessageBusConfig, MessageType},
+    };
+
+    fn ask_step(input: serde_json::Value) -> AgentStep {
+        AgentStep {
+            step_id: 1,
+            description: "ask".to_string(),
+            action: "ask_user".to_string(),
+            input: Some(input),
+            ..Default::default()
+        }
+    }
+
+    async fn execute(
+        executor: &Executor,
+        step: &AgentStep,
+    ) -> std::result::Result<String, AgentError> {
+        executor
+            .execute(
+                step,
+                &AgentContext::default(),
+                &Memory::default(),
+                &AgentState::default(),
+                &CancellationToken::new(),
+            )
+            .await
+            .map(|result| result.output)
+    }
+
+    #[test]
+    fn test_resolve_answer() {
+        let open = HumanQuestion::new("name?");
+        assert_eq!(open.resolve_answer(" Alice\n"), Some("Alice".to_string()));
+
+        let choice = HumanQuestion::new("continue?").with_choices(vec!["yes".into(), "no".into()]);
+        assert_eq!(choice.resolve_answer("YES"), Some("yes".to_string()));
+        assert_eq!(choice.resolve_answer("2"), Some("no".to_string()));
+        assert_eq!(choice.resolve_answer("0"), None);
+        assert_eq!(choice.resolve_answer("maybe"), None);
+    }
+
+    #[tokio::test]
+    async fn test_ask_user_through_channel() {
+        let (human, mut questions) = ChannelHumanInterface::new();
+        let executor = Executor::new(Arc::new(human));
+
+        let answering = tokio::spawn(async move {
+            let pending = questions.recv().await.unwrap();
+            assert_eq!(pending.question.choices, vec!["yes", "no"]);
+            pending.answer("2");
+            questions
+        });
+        let step = ask_step(json!({"question": "continue?", "choices": ["yes", "no"]}));
+        assert_eq!(execute(&executor, &step).await.unwrap(), json!({"answer": "no"}).to_string());
+
+        // Nobody answers a question that gives up immediately
+        let _questions = answering.await.unwrap();
+        let step = ask_step(json!({"question": "still there?", "timeout_secs": 0}));
+        assert!(matches!(execute(&executor, &step).await, Err(AgentError::AnswerTimeout(_))));
+    }
+
+    #[tokio::test]
+    async fn test_ask_over_bus() {
+        let bus = Arc::new(MessageBus::new(MessageBusConfig::default()));
+        let mut ui = bus.register_agent("ui".to_string()).await.unwrap();
+        let human = BusHumanInterface::new(bus.clone(), "executor".to_string(), "ui".to_string());
+
+        let ui_bus = bus.clone();
+        tokio::spawn(async move {
+            let question = ui.recv().await.unwrap();
+            assert_eq!(question.message_type, MessageType::Custom(bus::USER_QUESTION.into()));
+            assert_eq!(question.payload["question"], "city?");
+            let answer = Message::response(
+                "ui".to_string(),
+                question.sender_id.clone(),
+                MessageType::Custom("UserAnswer".to_string()),
+                json!({"answer": "Beijing"}),
+                question.id.clone(),
+            );
+            ui_bus.send(answer).await.unwrap();
+        });
+
+        let question = HumanQuestion::new("city?").with_timeout(Duration::from_secs(5));
+        assert_eq!(human.ask(&question).await.unwrap(), "Beijing");
+    }
+}
@@ -0,0 +1,39 @@
+use std::io::Write;
+
+use async_trait::async_trait;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+use crate::{
+    agent::human::{HumanInterface, HumanQuestion, await_answer},
+    error::{Result, agent_error::AgentError},
+};
+
+/// Asks on the terminal and reads the answer from stdin without blocking the runtime
+#[derive(Debug, Default, Clone)]
+pub struct TerminalHumanInterface;
+
+#[async_trait]
+impl HumanInterface for TerminalHumanInterface {
+    async fn ask(&self, question: &HumanQuestion) -> Result<String> {
+        let read = async {
+            let mut lines = BufReader::new(tokio::io::stdin()).lines();
+            loop {
+                println!("❓ {}", question.question);
+                for (number, choice) in question.choices.iter().enumerate() {
+                    println!("   {}. {choice}", number + 1);
+                }
+                print!("👉 请输入你的回答：");
+                std::io::stdout().flush()?; // 确保立即输出提示
+
+                let Some(line) = lines.next_line().await? else {
+                    return Err(AgentError::ExecutionError("标准输入已关闭".into()).into());
+                };
+                if question.resolve_answer(&line).is_some() {
+                    return Ok(line);
+                }
+                println!("⚠️ 请从给出的选项中选择");
+            }
+        };
+        await_answer(question, read).await
+    }
+}
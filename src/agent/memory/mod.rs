@@ -1,11 +1,15 @@
 use std::collections::HashMap;
 
+use serde_json::Value;
+
 use crate::agent::planning::AgentStep;
 
 #[derive(Debug, Default, Clone)]
 pub struct Memory {
     pub history: Vec<String>,
     pub step_cache: HashMap<String, AgentStep>,
+    /// Named values that plan steps can reference as `{{memory.<key>}}`
+    pub variables: HashMap<String, Value>,
 }
 
 impl Memory {
@@ -16,4 +20,12 @@ impl Memory {
     pub fn log(&mut self, message: String) {
         self.history.push(message);
     }
+
+    pub fn set_variable(&mut self, key: String, value: Value) {
+        self.variables.insert(key, value);
+    }
+
+    pub fn get_variable(&self, key: &str) -> Option<&Value> {
+        self.variables.get(key)
+    }
 }
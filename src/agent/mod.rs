@@ -1,8 +1,10 @@
 pub mod context;
 pub mod core;
 pub mod execution;
+pub mod human;
 pub mod memory;
 pub mod planning;
+pub mod runner;
 pub mod state;
 pub mod types;
 pub mod verification;
@@ -10,7 +12,9 @@ pub mod verification;
 
 pub use context::AgentContext;
 pub use execution::Executor;
+pub use human::{HumanInterface, HumanQuestion};
 pub use memory::Memory;
 pub use planning::{AgentPlan, AgentStep, Planner};
+pub use runner::{AgentRunner, RunReport};
 pub use state::AgentState;
 pub use verification::verifier::Verifier;
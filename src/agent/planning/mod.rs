@@ -1,11 +1,21 @@
 pub mod planner;
 mod step;
 
+use std::collections::HashSet;
+
 pub use planner::Planner;
 use serde::{Deserialize, Serialize};
 pub use step::AgentStep;
 
-use crate::agent::{state::AgentState, types::StepStatus};
+use crate::{
+    agent::{execution::reference::contains_references, state::AgentState, types::StepStatus},
+    error::agent_error::AgentError,
+    tools::{model::TOOL_REGISTRY, schema::format_schema_errors},
+    utils::{dag::DependencyGraph, string_util::StripCodeBlock},
+};
+
+/// Actions the executor knows how to run
+pub const KNOWN_ACTIONS: &[&str] = &["call_tool", "ask_user"];
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentPlan {
@@ -38,12 +48,309 @@ impl AgentPlan {
     //     }
     // }
 
-    pub fn next_pending_step(&self, state: &AgentState) -> Option<&AgentStep> {
-        self.steps.iter().find(|step| {
-            match state.get_step_status(step.step_id) {
-                Some(StepStatus::Pending) | None => true, // 默认视为 Pending
-                _ => false,
+    /// Parse a plan from raw LLM text (optionally wrapped in a code block) and validate it
+    pub fn parse(text: &str) -> Result<Self, AgentError> {
+        let plan: AgentPlan = serde_json::from_str(text.strip_code_block())
+            .map_err(|e| AgentError::ParseError(format!("计划 JSON 解析失败: {e}")))?;
+        plan.validate()?;
+        Ok(plan)
+    }
+
+    /// Check that step ids are unique, actions are known, referenced tools exist and the
+    /// step dependencies form no cycle
+    pub fn validate(&self) -> Result<(), AgentError> {
+        if self.steps.is_empty() {
+            return Err(AgentError::InvalidPlan("计划中没有任何步骤".into()));
+        }
+
+        let mut seen = HashSet::new();
+        let tool_registry = TOOL_REGISTRY.read().unwrap();
+
+        for step in &self.steps {
+            if !seen.insert(step.step_id) {
+                return Err(AgentError::InvalidPlan(format!(
+                    "步骤 ID 重复: {}",
+                    step.step_id
+                )));
+            }
+
+            if !KNOWN_ACTIONS.contains(&step.action.as_str()) {
+                return Err(AgentError::InvalidPlan(format!(
+                    "步骤 {} 使用了未知动作: {}",
+                    step.step_id, step.action
+                )));
+            }
+
+            if step.action == "call_tool" {
+                match &step.tool {
+                    Some(tool) => {
+                        let Some(tool_info) = tool_registry.get(tool) else {
+                            return Err(AgentError::InvalidPlan(format!(
+                                "步骤 {} 引用了不存在的工具: {tool}",
+                                step.step_id
+                            )));
+                        };
+                        // Arguments with references can only be checked once resolved
+                        let arguments = step.tool_arguments();
+                        if !contains_references(&arguments)
+                            && let Err(errors) = tool_info.validate_arguments(&arguments)
+                        {
+                            return Err(AgentError::InvalidPlan(format!(
+                                "步骤 {} 的参数不符合工具 {tool} 的 schema: {}",
+                                step.step_id,
+                                format_schema_errors(&errors)
+                            )));
+                        }
+                    }
+                    None => {
+                        return Err(AgentError::InvalidPlan(format!(
+                            "步骤 {} 缺少 tool 字段",
+                            step.step_id
+                        )));
+                    }
+                }
+            }
+        }
+
+        self.dependency_graph().validate()?;
+        Ok(())
+    }
+
+    /// Ids of the steps `step` waits for
+    ///
+    /// These are its declared `depends_on` (the previous step when it declares none) plus
+    /// every step whose result it references.
+    pub fn dependencies_of(&self, step: &AgentStep) -> Vec<usize> {
+        let mut dependencies = match &step.depends_on {
+            Some(declared) => declared.clone(),
+            None => self
+                .steps
+                .iter()
+                .take_while(|s| s.step_id != step.step_id)
+                .last()
+                .map(|previous| vec![previous.step_id])
+                .unwrap_or_default(),
+        };
+        for id in step.referenced_steps() {
+            if !dependencies.contains(&id) {
+                dependencies.push(id);
+            }
+        }
+        dependencies
+    }
+
+    /// Dependency graph of the steps, keyed by step id
+    pub fn dependency_graph(&self) -> DependencyGraph<usize> {
+        let mut graph = DependencyGraph::new();
+        for step in &self.steps {
+            graph.add_node(step.step_id, self.dependencies_of(step));
+        }
+        graph
+    }
+
+    /// Declare the dependencies of steps that have none where it is safe to relax them
+    ///
+    /// A step calling a read-only tool only waits for the last step with side effects before
+    /// it and the steps whose results it references, so consecutive reads run in parallel.
+    /// A step with side effects following such reads waits for all of them. Every other step
+    /// keeps waiting for the step before it, since not referencing a result does not make
+    /// two actions independent.
+    pub fn infer_dependencies(&mut self) {
+        let tool_registry = TOOL_REGISTRY.read().unwrap();
+        let mut last_effect: Option<usize> = None;
+        let mut reads_since: Vec<usize> = Vec::new();
+
+        for step in &mut self.steps {
+            let read_only = step.action == "call_tool"
+                && step
+                    .tool
+                    .as_ref()
+                    .and_then(|tool| tool_registry.get(tool))
+                    .is_some_and(|info| info.read_only);
+
+            if step.depends_on.is_none() {
+                if read_only {
+                    let mut dependencies: Vec<usize> = last_effect.into_iter().collect();
+                    for id in step.referenced_steps() {
+                        if !dependencies.contains(&id) {
+                            dependencies.push(id);
+                        }
+                    }
+                    step.depends_on = Some(dependencies);
+                } else if !reads_since.is_empty() {
+                    let mut dependencies: Vec<usize> = last_effect.into_iter().collect();
+                    dependencies.extend(&reads_since);
+                    step.depends_on = Some(dependencies);
+                }
+            }
+
+            if read_only {
+                reads_since.push(step.step_id);
+            } else {
+                last_effect = Some(step.step_id);
+                reads_since.clear();
             }
-        })
+        }
+    }
+
+    /// Pending steps whose dependencies are all done, in plan order
+    pub fn ready_steps(&self, state: &AgentState) -> Vec<&AgentStep> {
+        self.steps
+            .iter()
+            .filter(|step| is_pending(state, step.step_id))
+            .filter(|step| {
+                self.dependencies_of(step)
+                    .iter()
+                    .all(|id| state.get_step_status(*id) == Some(&StepStatus::Done))
+            })
+            .collect()
+    }
+
+    /// First step that is ready to run
+    pub fn next_pending_step(&self, state: &AgentState) -> Option<&AgentStep> {
+        self.ready_steps(state).into_iter().next()
+    }
+
+    /// First step that has not run yet, whether or not it is ready
+    pub fn first_unstarted_step(&self, state: &AgentState) -> Option<&AgentStep> {
+        self.steps.iter().find(|step| is_pending(state, step.step_id))
+    }
+}
+
+fn is_pending(state: &AgentState, step_id: usize) -> bool {
+    match state.get_step_status(step_id) {
+        Some(StepStatus::Pending) | None => true, // 默认视为 Pending
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{tools::ToolInfo, utils::dag::DagError};
+
+    fn register_test_tool(name: &str) {
+        TOOL_REGISTRY.write().unwrap().insert(
+            name.to_string(),
+            ToolInfo::new(
+                name.to_string(),
+                "test tool".to_string(),
+                json!({"type": "object"}),
+                "test".to_string(),
+            ),
+        );
+    }
+
+    #[test]
+    fn test_parse_plan_from_code_block() {
+        register_test_tool("plan_test_search");
+
+        let text = r#"```json
+{
+  "plan_id": "p1",
+  "steps": [
+    {"step_id": 1, "description": "search", "action": "call_tool", "tool": "plan_test_search", "parameters": {"q": "rust"}},
+    {"step_id": 2, "description": "ask", "action": "ask_user", "input": {"question": "ok?"}}
+  ]
+}
+```"#;
+
+        let plan = AgentPlan::parse(text).unwrap();
+        assert_eq!(plan.plan_id, "p1");
+        assert_eq!(plan.steps.len(), 2);
+    }
+
+    #[test]
+    fn test_validate_rejects_invalid_plans() {
+        let duplicate = r#"{"plan_id": "p", "steps": [
+            {"step_id": 1, "description": "a", "action": "ask_user"},
+            {"step_id": 1, "description": "b", "action": "ask_user"}
+        ]}"#;
+        assert!(matches!(AgentPlan::parse(duplicate), Err(AgentError::InvalidPlan(_))));
+
+        let unknown_action = r#"{"plan_id": "p", "steps": [
+            {"step_id": 1, "description": "a", "action": "dance"}
+        ]}"#;
+        assert!(matches!(AgentPlan::parse(unknown_action), Err(AgentError::InvalidPlan(_))));
+
+        let unknown_tool = r#"{"plan_id": "p", "steps": [
+            {"step_id": 1, "description": "a", "action": "call_tool", "tool": "no_such_tool"}
+        ]}"#;
+        assert!(matches!(AgentPlan::parse(unknown_tool), Err(AgentError::InvalidPlan(_))));
+
+        assert!(matches!(AgentPlan::parse("not json"), Err(AgentError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_step_dependencies() {
+        let cyclic = r#"{"plan_id": "p", "steps": [
+            {"step_id": 1, "description": "a", "action": "ask_user", "depends_on": [2]},
+            {"step_id": 2, "description": "b", "action": "ask_user", "depends_on": [1]}
+        ]}"#;
+        assert!(matches!(
+            AgentPlan::parse(cyclic),
+            Err(AgentError::InvalidDependency(DagError::Cycle(_)))
+        ));
+
+        let unknown = r#"{"plan_id": "p", "steps": [
+            {"step_id": 1, "description": "a", "action": "ask_user", "depends_on": [9]}
+        ]}"#;
+        assert!(matches!(AgentPlan::parse(unknown), Err(AgentError::InvalidDependency(_))));
+
+        // Undeclared dependencies default to the previous step
+        let mut plan = AgentPlan::parse(
+            r#"{"plan_id": "p", "steps": [
+            {"step_id": 1, "description": "a", "action": "ask_user"},
+            {"step_id": 2, "description": "b", "action": "ask_user"},
+            {"step_id": 3, "description": "c", "action": "ask_user",
+             "input": {"question": "{{steps.1.output.answer}}?"}}
+        ]}"#,
+        )
+        .unwrap();
+        let ready = |plan: &AgentPlan| -> Vec<usize> {
+            plan.ready_steps(&AgentState::default())
+                .iter()
+                .map(|step| step.step_id)
+                .collect()
+        };
+        assert_eq!(plan.dependencies_of(&plan.steps[2]), vec![2, 1]);
+        assert_eq!(ready(&plan), vec![1]);
+
+        // Steps with side effects keep their order
+        plan.infer_dependencies();
+        assert_eq!(plan.steps[2].depends_on, None);
+        assert_eq!(ready(&plan), vec![1]);
+
+        // Reads run in parallel, while the steps around them still wait
+        register_test_tool("plan_test_write");
+        TOOL_REGISTRY.write().unwrap().insert(
+            "plan_test_read".to_string(),
+            ToolInfo::new(
+                "plan_test_read".to_string(),
+                "read-only test tool".to_string(),
+                json!({"type": "object"}),
+                "test".to_string(),
+            )
+            .with_read_only(true),
+        );
+        let mut plan = AgentPlan::parse(
+            r#"{"plan_id": "p", "steps": [
+            {"step_id": 1, "description": "a", "action": "call_tool", "tool": "plan_test_write"},
+            {"step_id": 2, "description": "b", "action": "call_tool", "tool": "plan_test_read"},
+            {"step_id": 3, "description": "c", "action": "call_tool", "tool": "plan_test_read"},
+            {"step_id": 4, "description": "d", "action": "ask_user"},
+            {"step_id": 5, "description": "e", "action": "call_tool", "tool": "plan_test_read",
+             "parameters": {"q": "{{steps.2.output}}"}}
+        ]}"#,
+        )
+        .unwrap();
+        plan.infer_dependencies();
+        let declared: Vec<_> = plan.steps.iter().map(|step| step.depends_on.clone()).collect();
+        assert_eq!(
+            declared,
+            vec![None, Some(vec![1]), Some(vec![1]), Some(vec![1, 2, 3]), Some(vec![4, 2])]
+        );
     }
 }
@@ -1,19 +1,26 @@
 use model_gateway_rs::{
     clients::llm::LlmClient,
-    model::llm::{LlmInput, LlmOutput},
+    model::llm::{ChatMessage, LlmInput, LlmOutput},
     sdk::{ModelSDK, openai::OpenAiSdk},
     traits::ModelClient,
 };
 
 use crate::{
-    error::Result, input::model::UserTaskInput, message::planner::generate_planner_message,
+    agent::{planning::AgentPlan, state::AgentState},
+    error::{Error, Result, agent_error::AgentError},
+    input::model::UserTaskInput,
+    message::planner::{generate_planner_message, generate_replanner_message},
 };
 
+/// Default number of times the LLM is asked to repair an invalid plan
+const DEFAULT_MAX_REPAIRS: usize = 2;
+
 pub struct Planner<T>
 where
     T: ModelSDK<Input = LlmInput, Output = LlmOutput> + Sync + Send,
 {
     llm_client: LlmClient<T>,
+    max_repairs: usize,
 }
 
 impl<T> Planner<T>
@@ -21,28 +28,79 @@ where
     T: ModelSDK<Input = LlmInput, Output = LlmOutput> + Sync + Send,
 {
     pub fn new(llm_client: LlmClient<T>) -> Self {
-        Self { llm_client }
+        Self {
+            llm_client,
+            max_repairs: DEFAULT_MAX_REPAIRS,
+        }
+    }
+
+    /// Set how many repair prompts are sent after an invalid plan
+    pub fn with_max_repairs(mut self, max_repairs: usize) -> Self {
+        self.max_repairs = max_repairs;
+        self
     }
 
-    pub async fn generate_plan(&self, input: &UserTaskInput) -> Result<LlmOutput> {
-        let i = generate_planner_message(input);
-        println!("📜 生成计划消息: {i:?}");
-        let input = LlmInput {
-            messages: i,
-            max_tokens: Some(4096),
-        };
+    pub async fn generate_plan(&self, input: &UserTaskInput) -> Result<AgentPlan> {
+        let messages = generate_planner_message(input);
+        println!("📜 生成计划消息: {messages:?}");
+        self.plan_with_repairs(messages).await
+    }
 
-        let r: LlmOutput = self.llm_client.infer(input).await?;
-        Ok(r)
+    /// Ask for a revised plan after `plan` failed, given the partial execution trace
+    pub async fn replan(
+        &self,
+        input: &UserTaskInput,
+        plan: &AgentPlan,
+        state: &AgentState,
+        reason: &str,
+    ) -> Result<AgentPlan> {
+        let messages = generate_replanner_message(input, plan, state, reason);
+        println!("🔁 重新规划计划 {}: {reason}", plan.plan_id);
+        self.plan_with_repairs(messages).await
+    }
+
+    /// Ask the LLM for a plan, feeding parse/validation errors back until it is valid
+    pub(crate) async fn plan_with_repairs(
+        &self,
+        mut messages: Vec<ChatMessage>,
+    ) -> Result<AgentPlan> {
+        let mut last_error = None;
+
+        for attempt in 0 ..= self.max_repairs {
+            let input = LlmInput {
+                messages: messages.clone(),
+                max_tokens: Some(4096),
+            };
+
+            let output: LlmOutput = self.llm_client.infer(input).await?;
+            let content = output.get_content().to_string();
+
+            match AgentPlan::parse(&content) {
+                Ok(plan) => return Ok(plan),
+                Err(e) => {
+                    println!("⚠️ 第 {} 次计划无效: {e}", attempt + 1);
+                    messages.push(ChatMessage::assistant(content.as_str()));
+                    messages.push(ChatMessage::user(
+                        format!(
+                            "The previous plan is invalid: {e}\nFix the problem and output only the corrected JSON plan."
+                        )
+                        .as_str(),
+                    ));
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(Error::AgentError(last_error.unwrap_or_else(|| {
+            AgentError::InvalidPlan("未能生成有效计划".into())
+        })))
     }
 }
 
 impl Default for Planner<OpenAiSdk> {
     fn default() -> Self {
-        Self {
-            llm_client: LlmClient::new(
-                OpenAiSdk::new("", "http://192.168.1.64:11434/v1", "llama4:scout").unwrap(),
-            ),
-        }
+        Self::new(LlmClient::new(
+            OpenAiSdk::new("", "http://192.168.1.64:11434/v1", "llama4:scout").unwrap(),
+        ))
     }
 }
@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::agent::types::StepStatus;
+use crate::agent::{
+    execution::{ApprovalRecord, reference::referenced_steps},
+    types::StepStatus,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AgentStep {
@@ -23,6 +26,14 @@ pub struct AgentStep {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub input: Option<Value>,
 
+    /// Ids of the steps that must be done before this one
+    ///
+    /// `None`, when the plan omits the field, means the step waits for the step listed before
+    /// it, while `Some(vec![])` means it waits for nothing. A plain `Vec` could not tell the
+    /// two apart.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depends_on: Option<Vec<usize>>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output: Option<Value>,
 
@@ -34,5 +45,35 @@ pub struct AgentStep {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error_reason: Option<String>,
+
+    /// Approval decisions made while the step ran
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trace: Vec<ApprovalRecord>,
 }
 
+impl AgentStep {
+    /// Arguments passed to the tool, accepting both bare parameters and the
+    /// `{"name": .., "arguments": ..}` call form
+    pub fn tool_arguments(&self) -> Value {
+        match &self.parameters {
+            Some(params) if params.get("name").is_some() && params.get("arguments").is_some() => {
+                params["arguments"].clone()
+            }
+            Some(params) => params.clone(),
+            None => Value::Object(Default::default()),
+        }
+    }
+
+    /// Ids of the steps whose results `parameters` or `input` reference
+    pub fn referenced_steps(&self) -> Vec<usize> {
+        let mut ids = Vec::new();
+        for value in [&self.parameters, &self.input].into_iter().flatten() {
+            for id in referenced_steps(value) {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+        ids
+    }
+}
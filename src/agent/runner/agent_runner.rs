@@ -0,0 +1,468 @@
+use model_gateway_rs::{
+    model::llm::{LlmInput, LlmOutput},
+    sdk::ModelSDK,
+};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::json;
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    agent::{
+        context::AgentContext,
+        execution::{ApprovalPolicy, Executor},
+        human::HumanInterface,
+        memory::Memory,
+        planning::{AgentPlan, AgentStep, Planner},
+        runner::scheduler::{self, StepHandler},
+        state::AgentState,
+        types::StepResult,
+        verification::Verifier,
+    },
+    error::Result,
+    input::model::UserTaskInput,
+    shared::global_context::GlobalConfig,
+};
+
+/// Default number of revised plans requested after a failure
+pub const DEFAULT_MAX_REPLANS: usize = 2;
+
+/// Outcome of a full plan → execute → verify → replan run
+#[derive(Debug, Clone)]
+pub struct RunReport {
+    /// The last plan that was executed; `is_succeeded` / `error_step_id` describe its outcome
+    pub plan: AgentPlan,
+    /// Step statuses and results of the last plan
+    pub state: AgentState,
+    /// Number of revised plans that were requested
+    pub replans: usize,
+}
+
+impl RunReport {
+    pub fn is_succeeded(&self) -> bool {
+        self.plan.is_succeeded
+    }
+}
+
+/// Why a plan stopped before all steps were done
+#[derive(Debug, Clone)]
+pub struct StepFailure {
+    pub step_id: usize,
+    pub reason: String,
+}
+
+/// Single-agent core loop driving planner, executor and verifier together
+pub struct AgentRunner<T>
+where
+    T: ModelSDK<Input = LlmInput, Output = LlmOutput> + Sync + Send,
+{
+    planner: Planner<T>,
+    executor: Executor,
+    verifier: Verifier,
+    context: AgentContext,
+    memory: Memory,
+    max_replans: usize,
+    max_concurrency: usize,
+    cancel: CancellationToken,
+}
+
+impl<T> AgentRunner<T>
+where
+    T: ModelSDK<Input = LlmInput, Output = LlmOutput> + Sync + Send,
+{
+    pub fn new(planner: Planner<T>) -> Self {
+        Self {
+            planner,
+            executor: Executor::default(),
+            verifier: Verifier,
+            context: AgentContext::default(),
+            memory: Memory::default(),
+            max_replans: DEFAULT_MAX_REPLANS,
+            max_concurrency: GlobalConfig::default().max_concurrent_tasks,
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    /// Set how many revised plans may be requested after failures
+    pub fn with_max_replans(mut self, max_replans: usize) -> Self {
+        self.max_replans = max_replans;
+        self
+    }
+
+    /// Set how many independent steps may run at the same time
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    /// Set where `ask_user` steps get their answers from
+    pub fn with_human_interface(mut self, human: Arc<dyn HumanInterface>) -> Self {
+        self.executor = self.executor.with_human_interface(human);
+        self
+    }
+
+    /// Set which tool calls need a human's approval before they run
+    pub fn with_approval_policy(mut self, approval: ApprovalPolicy) -> Self {
+        self.executor = self.executor.with_approval_policy(approval);
+        self
+    }
+
+    /// Set the agent context passed to executor and verifier
+    pub fn with_context(mut self, context: AgentContext) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Stop the run with `AgentError::Cancelled` once `cancel` is cancelled
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    pub fn memory(&self) -> &Memory {
+        &self.memory
+    }
+
+    pub fn memory_mut(&mut self) -> &mut Memory {
+        &mut self.memory
+    }
+
+    /// Plan the task, execute and verify every step, and replan on failure
+    pub async fn run(&mut self, input: &UserTaskInput) -> Result<RunReport> {
+        self.context.set_user_prompt(input.goal.clone());
+
+        let plan = self.planner.generate_plan(input).await?;
+        self.run_plan(input, plan).await
+    }
+
+    /// Execute an existing plan, replanning on failure
+    pub async fn run_plan(&mut self, input: &UserTaskInput, plan: AgentPlan) -> Result<RunReport> {
+        let (max_replans, max_concurrency) = (self.max_replans, self.max_concurrency);
+        scheduler::run_plan(self, input, plan, max_replans, max_concurrency).await
+    }
+
+    /// Run the pending steps of `plan`, recording progress in `state`.
+    ///
+    /// Steps are scheduled by [`scheduler::execute_plan`], running independent ones up to
+    /// `max_concurrency` at a time. The steps that ran are recorded in memory afterwards.
+    pub async fn execute_plan(
+        &mut self,
+        plan: &mut AgentPlan,
+        state: &mut AgentState,
+    ) -> std::result::Result<(), StepFailure> {
+        let outcome = scheduler::execute_plan(&*self, plan, state, self.max_concurrency).await;
+        self.plan_finished(plan, state, &outcome);
+        outcome
+    }
+}
+
+#[async_trait]
+impl<T> StepHandler for AgentRunner<T>
+where
+    T: ModelSDK<Input = LlmInput, Output = LlmOutput> + Sync + Send,
+{
+    /// Execute one step, turning executor errors into a failed result
+    async fn execute_step(
+        &self,
+        _plan_id: &str,
+        step: &AgentStep,
+        state: &AgentState,
+    ) -> StepResult {
+        match self
+            .executor
+            .execute(step, &self.context, &self.memory, state, &self.cancel)
+            .await
+        {
+            Ok(result) => result,
+            Err(e) => StepResult {
+                output: json!({ "error": e.to_string() }).to_string(),
+                success: false,
+                ..Default::default()
+            },
+        }
+    }
+
+    async fn verify_step(
+        &self,
+        _plan_id: &str,
+        step: &AgentStep,
+        result: &StepResult,
+    ) -> std::result::Result<(), String> {
+        self.verifier
+            .verify(step, result, &self.context, &self.memory)
+            .map_err(|e| e.to_string())
+    }
+
+    async fn replan(
+        &self,
+        input: &UserTaskInput,
+        plan: &AgentPlan,
+        state: &AgentState,
+        reason: &str,
+    ) -> Result<AgentPlan> {
+        self.planner.replan(input, plan, state, reason).await
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// Record the steps that ran and the failure, if any, in memory
+    fn plan_finished(
+        &mut self,
+        plan: &AgentPlan,
+        state: &AgentState,
+        outcome: &std::result::Result<(), StepFailure>,
+    ) {
+        for step in &plan.steps {
+            if state.get_step_status(step.step_id).is_some() {
+                self.memory.record_step(&step.step_id.to_string(), step);
+            }
+        }
+        if let Err(failure) = outcome {
+            self.memory.log(format!(
+                "plan {} failed at step {}: {}",
+                plan.plan_id, failure.step_id, failure.reason
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use async_trait::async_trait;
+    use serde_json::Value;
+
+    use super::*;
+    use crate::{
+        agent::types::StepStatus,
+        tools::native::{Tool, register_native_tool},
+    };
+
+    struct EchoTool;
+
+    #[async_trait]
+    impl Tool for EchoTool {
+        fn name(&self) -> &str {
+            "runner_test_echo"
+        }
+
+        fn description(&self) -> &str {
+            "Echo the text argument"
+        }
+
+        fn input_schema(&self) -> Value {
+            json!({"type": "object", "required": ["text"]})
+        }
+
+        async fn call(&self, arguments: Value) -> Result<Value> {
+            Ok(json!({ "result": arguments["text"] }))
+        }
+    }
+
+    /// Records how many calls were running at the same time
+    struct ConcurrencyProbe {
+        running: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Tool for ConcurrencyProbe {
+        fn name(&self) -> &str {
+            "runner_test_probe"
+        }
+
+        fn description(&self) -> &str {
+            "Track concurrent calls"
+        }
+
+        fn input_schema(&self) -> Value {
+            json!({"type": "object"})
+        }
+
+        async fn call(&self, arguments: Value) -> Result<Value> {
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(running, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            Ok(json!({ "result": arguments["text"] }))
+        }
+    }
+
+    /// Sleeps for `ms` milliseconds and records the order in which calls finished
+    struct SleepTool {
+        finished: std::sync::Mutex<Vec<usize>>,
+    }
+
+    #[async_trait]
+    impl Tool for SleepTool {
+        fn name(&self) -> &str {
+            "runner_test_sleep"
+        }
+
+        fn description(&self) -> &str {
+            "Sleep, then return"
+        }
+
+        fn input_schema(&self) -> Value {
+            json!({"type": "object", "required": ["id", "ms"]})
+        }
+
+        async fn call(&self, arguments: Value) -> Result<Value> {
+            let ms = arguments["ms"].as_u64().unwrap_or_default();
+            tokio::time::sleep(std::time::Duration::from_millis(ms)).await;
+            let id = arguments["id"].as_u64().unwrap_or_default() as usize;
+            self.finished.lock().unwrap().push(id);
+            Ok(json!({ "result": id.to_string() }))
+        }
+    }
+
+    fn step(step_id: usize, parameters: Value) -> AgentStep {
+        AgentStep {
+            step_id,
+            description: format!("echo {step_id}"),
+            action: "call_tool".to_string(),
+            tool: Some("runner_test_echo".to_string()),
+            parameters: Some(parameters),
+            ..Default::default()
+        }
+    }
+
+    fn plan(steps: Vec<AgentStep>) -> AgentPlan {
+        AgentPlan {
+            plan_id: "runner-test".to_string(),
+            description: None,
+            version: None,
+            steps,
+            is_succeeded: false,
+            error_step_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_plan_chains_steps() {
+        register_native_tool(Arc::new(EchoTool));
+        let mut runner = AgentRunner::new(Planner::default());
+
+        let mut plan = plan(vec![
+            step(1, json!({"text": "hello"})),
+            step(2, json!({"text": "{{steps.1.output.result}} world"})),
+        ]);
+        let mut state = AgentState::default();
+
+        runner.execute_plan(&mut plan, &mut state).await.unwrap();
+
+        assert!(plan.is_succeeded);
+        assert_eq!(state.get_step_status(2), Some(&StepStatus::Done));
+        assert_eq!(plan.steps[1].output, Some(json!({"result": "hello world"})));
+    }
+
+    #[tokio::test]
+    async fn test_execute_plan_records_failed_step() {
+        register_native_tool(Arc::new(EchoTool));
+        let mut runner = AgentRunner::new(Planner::default());
+
+        // Step 2 violates the tool schema, step 3 must not run
+        let mut plan = plan(vec![
+            step(1, json!({"text": "ok"})),
+            step(2, json!({})),
+            step(3, json!({"text": "never"})),
+        ]);
+        let mut state = AgentState::default();
+
+        let failure = runner.execute_plan(&mut plan, &mut state).await.unwrap_err();
+
+        assert_eq!(failure.step_id, 2);
+        assert!(!plan.is_succeeded);
+        assert_eq!(plan.error_step_id, Some(2));
+        assert_eq!(state.get_step_status(2), Some(&StepStatus::Failed));
+        assert!(state.get_step_status(3).is_none());
+
+        // The rejection keeps the path-annotated schema errors
+        let output = plan.steps[1].output.as_ref().unwrap();
+        assert_eq!(
+            output["schema_errors"],
+            json!([{"path": "$", "message": "missing required property 'text'"}])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_tool_call_reports_error() {
+        let mut runner = AgentRunner::new(Planner::default());
+        let mut plan = plan(vec![AgentStep {
+            tool: Some("runner_test_missing".to_string()),
+            ..step(1, json!({"text": "hello"}))
+        }]);
+        let mut state = AgentState::default();
+
+        runner.execute_plan(&mut plan, &mut state).await.unwrap_err();
+
+        let output = plan.steps[0].output.as_ref().unwrap();
+        assert_eq!(output["tool"], "runner_test_missing");
+        assert!(output["error"].as_str().unwrap().contains("runner_test_missing"));
+        assert!(output.get("result").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_execute_plan_runs_independent_steps_concurrently() {
+        let probe = Arc::new(ConcurrencyProbe {
+            running: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+        });
+        register_native_tool(probe.clone());
+        let mut runner = AgentRunner::new(Planner::default()).with_max_concurrency(2);
+
+        let independent = |step_id: usize| AgentStep {
+            tool: Some("runner_test_probe".to_string()),
+            depends_on: Some(vec![]),
+            ..step(step_id, json!({"text": step_id.to_string()}))
+        };
+        // Step 4 waits for 1 and 2 explicitly and for 3 through its reference
+        let mut plan = plan(vec![
+            independent(1),
+            independent(2),
+            independent(3),
+            AgentStep {
+                depends_on: Some(vec![1, 2]),
+                ..step(4, json!({"text": "{{steps.3.output.result}}"}))
+            },
+        ]);
+        let mut state = AgentState::default();
+
+        runner.execute_plan(&mut plan, &mut state).await.unwrap();
+
+        assert!(plan.is_succeeded);
+        assert_eq!(probe.peak.load(Ordering::SeqCst), 2);
+        assert_eq!(plan.steps[3].output, Some(json!({"result": "3"})));
+    }
+
+    #[tokio::test]
+    async fn test_execute_plan_starts_steps_as_soon_as_ready() {
+        let sleeper = Arc::new(SleepTool {
+            finished: std::sync::Mutex::new(Vec::new()),
+        });
+        register_native_tool(sleeper.clone());
+        let mut runner = AgentRunner::new(Planner::default()).with_max_concurrency(2);
+
+        let sleep = |step_id: usize, ms: u64, depends_on: Vec<usize>| AgentStep {
+            tool: Some("runner_test_sleep".to_string()),
+            depends_on: Some(depends_on),
+            ..step(step_id, json!({"id": step_id, "ms": ms}))
+        };
+        // Step 3 becomes ready when step 2 finishes and must not wait for the slow step 1
+        let mut plan = plan(vec![
+            sleep(1, 200, vec![]),
+            sleep(2, 10, vec![]),
+            sleep(3, 10, vec![2]),
+        ]);
+        let mut state = AgentState::default();
+
+        runner.execute_plan(&mut plan, &mut state).await.unwrap();
+
+        assert!(plan.is_succeeded);
+        assert_eq!(*sleeper.finished.lock().unwrap(), vec![2, 3, 1]);
+    }
+}
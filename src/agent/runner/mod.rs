@@ -0,0 +1,5 @@
+pub mod agent_runner;
+pub mod scheduler;
+
+pub use agent_runner::{AgentRunner, RunReport, StepFailure};
+pub use scheduler::StepHandler;
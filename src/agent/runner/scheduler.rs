@@ -0,0 +1,242 @@
+use async_trait::async_trait;
+use futures::stream::{FuturesUnordered, StreamExt};
+use serde_json::json;
+
+use crate::{
+    agent::{
+        planning::{AgentPlan, AgentStep},
+        runner::{RunReport, StepFailure},
+        state::AgentState,
+        types::{StepResult, StepStatus},
+    },
+    error::{Error, Result, agent_error::AgentError},
+    input::model::UserTaskInput,
+};
+
+/// Executes, verifies and replans plan steps for [`execute_plan`] and [`run_plan`]
+///
+/// Implemented by `AgentRunner`, which calls planner, executor and verifier directly, and by
+/// the runtimes, which send the agents requests.
+#[async_trait]
+pub trait StepHandler: Sync {
+    /// Execute a step against the results recorded so far; failures are returned as an
+    /// unsuccessful result
+    async fn execute_step(&self, plan_id: &str, step: &AgentStep, state: &AgentState)
+    -> StepResult;
+
+    /// Check the result of a successfully executed step
+    async fn verify_step(
+        &self,
+        plan_id: &str,
+        step: &AgentStep,
+        result: &StepResult,
+    ) -> std::result::Result<(), String>;
+
+    /// Ask for a revised plan after `plan` failed with `reason`
+    async fn replan(
+        &self,
+        input: &UserTaskInput,
+        plan: &AgentPlan,
+        state: &AgentState,
+        reason: &str,
+    ) -> Result<AgentPlan>;
+
+    /// Whether the run was cancelled; a cancelled run ends with `AgentError::Cancelled`
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+
+    /// Called once the steps of a plan ran, before any replanning
+    fn plan_finished(
+        &mut self,
+        _plan: &AgentPlan,
+        _state: &AgentState,
+        _outcome: &std::result::Result<(), StepFailure>,
+    ) {
+    }
+}
+
+/// Execute a plan, requesting up to `max_replans` revised plans after failures
+pub async fn run_plan<H: StepHandler>(
+    handler: &mut H,
+    input: &UserTaskInput,
+    mut plan: AgentPlan,
+    max_replans: usize,
+    max_concurrency: usize,
+) -> Result<RunReport> {
+    let mut replans = 0;
+
+    loop {
+        let mut state = AgentState::default();
+        let outcome = execute_plan(&*handler, &mut plan, &mut state, max_concurrency).await;
+        handler.plan_finished(&plan, &state, &outcome);
+
+        match outcome {
+            Ok(()) => {
+                println!("🎉 计划 {} 执行成功", plan.plan_id);
+                return Ok(RunReport {
+                    plan,
+                    state,
+                    replans,
+                });
+            }
+            Err(_) if handler.is_cancelled() => {
+                println!("🛑 计划 {} 已取消", plan.plan_id);
+                return Err(Error::AgentError(AgentError::Cancelled));
+            }
+            Err(failure) => {
+                println!(
+                    "❌ 计划 {} 在步骤 {} 失败: {}",
+                    plan.plan_id, failure.step_id, failure.reason
+                );
+
+                if replans >= max_replans {
+                    return Ok(RunReport {
+                        plan,
+                        state,
+                        replans,
+                    });
+                }
+
+                plan = handler
+                    .replan(input, &plan, &state, &failure.reason)
+                    .await?;
+                replans += 1;
+            }
+        }
+    }
+}
+
+/// Run the pending steps of `plan`, recording progress in `state`.
+///
+/// Steps whose dependencies are done run concurrently, up to `max_concurrency` at a time, and
+/// a finished step immediately makes room for the next ready one; an `ask_user` step always
+/// runs on its own. After the first step that fails to execute or verify no further steps are
+/// started, the running ones are awaited, and the failed step is recorded in
+/// `plan.error_step_id`.
+pub async fn execute_plan<H: StepHandler>(
+    handler: &H,
+    plan: &mut AgentPlan,
+    state: &mut AgentState,
+    max_concurrency: usize,
+) -> std::result::Result<(), StepFailure> {
+    plan.is_succeeded = false;
+    plan.error_step_id = None;
+
+    let max_concurrency = max_concurrency.max(1);
+    let mut running = FuturesUnordered::new();
+    let mut asking_user = false;
+    let mut first_failure = None;
+
+    loop {
+        if first_failure.is_none() && !asking_user {
+            let ready: Vec<AgentStep> = plan.ready_steps(state).into_iter().cloned().collect();
+            for step in ready {
+                if running.len() >= max_concurrency {
+                    break;
+                }
+                if step.action == "ask_user" {
+                    if !running.is_empty() {
+                        break;
+                    }
+                    asking_user = true;
+                }
+
+                println!("▶️ 执行步骤 {}: {}", step.step_id, step.description);
+                state.set_step_status(step.step_id, StepStatus::Executing);
+                running.push(run_step(handler, plan.plan_id.clone(), step, state.clone()));
+                if asking_user {
+                    break;
+                }
+            }
+        }
+
+        let Some((step, result, verdict)) = running.next().await else {
+            break;
+        };
+        if step.action == "ask_user" {
+            asking_user = false;
+        }
+        if let Err(failure) = record_step_outcome(plan, state, step.step_id, &result, verdict) {
+            first_failure.get_or_insert(failure);
+        }
+    }
+
+    if let Some(failure) = first_failure {
+        plan.error_step_id = Some(failure.step_id);
+        return Err(failure);
+    }
+
+    ensure_all_steps_ran(plan, state)?;
+    plan.is_succeeded = true;
+    Ok(())
+}
+
+/// Execute and verify one step on a snapshot of the state
+async fn run_step<H: StepHandler>(
+    handler: &H,
+    plan_id: String,
+    step: AgentStep,
+    state: AgentState,
+) -> (AgentStep, StepResult, std::result::Result<(), String>) {
+    let result = handler.execute_step(&plan_id, &step, &state).await;
+    let verdict = if result.success {
+        handler.verify_step(&plan_id, &step, &result).await
+    } else {
+        Err(format!("步骤执行失败: {}", result.output))
+    };
+    (step, result, verdict)
+}
+
+/// Fail the plan at the first step that never ran because its dependencies were not done
+fn ensure_all_steps_ran(
+    plan: &mut AgentPlan,
+    state: &AgentState,
+) -> std::result::Result<(), StepFailure> {
+    let Some(step) = plan.first_unstarted_step(state) else {
+        return Ok(());
+    };
+    let failure = StepFailure {
+        step_id: step.step_id,
+        reason: format!("步骤 {} 的依赖无法满足", step.step_id),
+    };
+    plan.error_step_id = Some(failure.step_id);
+    Err(failure)
+}
+
+/// Record a step's result and verification verdict on both the plan and the state.
+///
+/// A failed verdict marks the step and `plan.error_step_id` and is returned as a [`StepFailure`].
+fn record_step_outcome(
+    plan: &mut AgentPlan,
+    state: &mut AgentState,
+    step_id: usize,
+    result: &StepResult,
+    verdict: std::result::Result<(), String>,
+) -> std::result::Result<(), StepFailure> {
+    state.append_result(step_id, result.clone());
+
+    let Some(plan_step) = plan.steps.iter_mut().find(|s| s.step_id == step_id) else {
+        return Ok(());
+    };
+    plan_step.output =
+        Some(serde_json::from_str(&result.output).unwrap_or_else(|_| json!(result.output)));
+    plan_step.trace = result.trace.clone();
+
+    match verdict {
+        Ok(()) => {
+            state.set_step_status(step_id, StepStatus::Done);
+            plan_step.status = StepStatus::Done;
+            plan_step.is_succeeded = true;
+            Ok(())
+        }
+        Err(reason) => {
+            state.set_step_status(step_id, StepStatus::Failed);
+            plan_step.status = StepStatus::Failed;
+            plan_step.is_succeeded = false;
+            plan_step.error_reason = Some(reason.clone());
+            plan.error_step_id = Some(step_id);
+            Err(StepFailure { step_id, reason })
+        }
+    }
+}
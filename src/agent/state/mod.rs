@@ -1,8 +1,10 @@
 use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
+
 use crate::agent::types::{StepResult, StepStatus};
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct AgentState {
     pub step_status: HashMap<String, StepStatus>,
     pub step_results: HashMap<String, StepResult>,
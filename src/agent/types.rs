@@ -2,6 +2,8 @@ use std::fmt;
 
 use serde::{Deserialize, Serialize};
 
+use crate::agent::execution::ApprovalRecord;
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum StepStatus {
     #[default]
@@ -12,10 +14,13 @@ pub enum StepStatus {
 }
 
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct StepResult {
     pub output: String,
     pub success: bool,
+    /// Approval decisions made while the step ran
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trace: Vec<ApprovalRecord>,
 }
 
 // ===== Multi-Agent related type definitions =====
@@ -79,6 +84,32 @@ pub enum TaskType {
     Composite,
 }
 
+impl TaskType {
+    /// Capability an agent needs to take a task of this type
+    pub fn required_capability(&self) -> AgentCapability {
+        match self {
+            TaskType::Planning => AgentCapability::TaskPlanning,
+            TaskType::Execution | TaskType::Analysis => AgentCapability::TaskExecution,
+            TaskType::Verification => AgentCapability::TaskVerification,
+            TaskType::Monitoring => AgentCapability::Monitoring,
+            TaskType::Composite => AgentCapability::Coordination,
+        }
+    }
+}
+
+impl fmt::Display for TaskType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskType::Planning => write!(f, "planning"),
+            TaskType::Execution => write!(f, "execution"),
+            TaskType::Verification => write!(f, "verification"),
+            TaskType::Analysis => write!(f, "analysis"),
+            TaskType::Monitoring => write!(f, "monitoring"),
+            TaskType::Composite => write!(f, "composite"),
+        }
+    }
+}
+
 /// Task priority
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Default)]
 pub enum Priority {
@@ -1,15 +1,18 @@
 use async_trait::async_trait;
 use std::sync::Arc;
-use tracing::{debug, info};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
 
 use crate::agent::{
     core::base_agent::{AgentBehavior, BaseAgent},
-    execution::Executor,
+    execution::{ApprovalPolicy, Executor},
+    human::HumanInterface,
     types::{AgentCapability, AgentStatus, AgentType},
 };
 use crate::multi_agent::communication::{Message, MessageType};
 use crate::shared::GlobalContext;
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::error::agent_error::AgentError;
 
 /// Executor Agent responsible for executing specific tasks
 pub struct ExecutorAgent {
@@ -29,7 +32,7 @@ impl ExecutorAgent {
 
         Self {
             base: BaseAgent::new(id, AgentType::Executor, all_capabilities.clone()),
-            executor: Executor,
+            executor: Executor::default(),
             current_task: None,
             capabilities: all_capabilities,
         }
@@ -40,25 +43,52 @@ impl ExecutorAgent {
         Self::new(id, vec![AgentCapability::ToolCalling(tool_name)])
     }
 
+    /// Set where `ask_user` steps get their answers from instead of the terminal
+    pub fn with_human_interface(mut self, human: Arc<dyn HumanInterface>) -> Self {
+        self.executor = self.executor.with_human_interface(human);
+        self
+    }
+
+    /// Set which tool calls need a human's approval before they run
+    pub fn with_approval_policy(mut self, approval: ApprovalPolicy) -> Self {
+        self.executor = self.executor.with_approval_policy(approval);
+        self
+    }
+
     /// Execute task
-    async fn execute_task(&mut self, task: serde_json::Value) -> Result<serde_json::Value> {
+    async fn execute_task(
+        &mut self,
+        task: serde_json::Value,
+        cancel: &CancellationToken,
+    ) -> Result<serde_json::Value> {
         info!("ExecutorAgent {} executing task", self.base.id);
         
         // Extract step information from task
         if let Some(step) = task.get("step") {
             // Construct AgentStep object
             if let Ok(agent_step) = serde_json::from_value::<crate::agent::planning::AgentStep>(step.clone()) {
+                // Results of earlier steps, so `{{steps.N.output}}` references resolve
+                let mut state = crate::agent::state::AgentState::default();
+                if let Some(results) = task.get("step_results")
+                    && let Ok(results) = serde_json::from_value(results.clone())
+                {
+                    state.step_results = results;
+                }
+
                 // Execute using existing Executor
                 let result = self.executor.execute(
                     &agent_step,
                     &self.base.local_context,
                     &crate::agent::memory::Memory::default(),
+                    &state,
+                    cancel,
                 ).await?;
 
                 Ok(serde_json::json!({
                     "step_id": agent_step.step_id,
                     "output": result.output,
                     "success": result.success,
+                    "trace": result.trace,
                 }))
             } else {
                 Err(crate::error::Error::AgentError(crate::error::agent_error::AgentError::ParseError(
@@ -93,8 +123,47 @@ impl ExecutorAgent {
             }),
         );
 
+        // Cancellation token observed while the task runs
+        let task_id = task.get("id").and_then(|v| v.as_str()).unwrap_or("unknown").to_string();
+        let cancellations = self.base.context.as_ref().map(|context| context.cancellations.clone());
+        let cancel = cancellations
+            .as_ref()
+            .map(|cancellations| cancellations.token_for(&task_id))
+            .unwrap_or_default();
+
         // Execute task
-        match self.execute_task(task.clone()).await {
+        let outcome = self.execute_task(task.clone(), &cancel).await;
+        if let Some(cancellations) = &cancellations {
+            cancellations.remove(&task_id);
+        }
+
+        match outcome {
+            // A step that ran but did not succeed, e.g. a failed tool call
+            Ok(result) if result.get("success").and_then(|v| v.as_bool()) == Some(false) => {
+                self.current_task = None;
+                warn!("ExecutorAgent {} failed task {}", self.base.id, task_id);
+
+                let error = result
+                    .get("output")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("step failed")
+                    .to_string();
+                // A call the user skipped would only be skipped again
+                let skipped = serde_json::from_str::<serde_json::Value>(&error)
+                    .is_ok_and(|output| output["skipped"] == true);
+                Ok(Message::new(
+                    self.base.id.clone(),
+                    message.sender_id.clone().into(),
+                    MessageType::Error,
+                    serde_json::json!({
+                        "task_id": task_id,
+                        "status": if skipped { "skipped" } else { "failed" },
+                        "error": error,
+                        "result": result,
+                        "retryable": !skipped,
+                    }),
+                ))
+            }
             Ok(result) => {
                 self.current_task = None;
                 
@@ -110,6 +179,23 @@ impl ExecutorAgent {
                     }),
                 ))
             }
+            Err(Error::AgentError(AgentError::Cancelled)) => {
+                self.current_task = None;
+                warn!("ExecutorAgent {} cancelled task {}", self.base.id, task_id);
+
+                // Cancelled tasks must not be retried
+                Ok(Message::new(
+                    self.base.id.clone(),
+                    message.sender_id.clone().into(),
+                    MessageType::Error,
+                    serde_json::json!({
+                        "task_id": task_id,
+                        "status": "cancelled",
+                        "error": AgentError::Cancelled.to_string(),
+                        "retryable": false,
+                    }),
+                ))
+            }
             Err(e) => {
                 self.current_task = None;
                 
@@ -158,6 +244,15 @@ impl AgentBehavior for ExecutorAgent {
                 let response = self.handle_task_assignment(message).await?;
                 Ok(Some(response))
             }
+            MessageType::TaskCancellation => {
+                // Reaches the agent only after the task finished; drop its token
+                if let Some(task_id) = message.payload.get("task_id").and_then(|v| v.as_str())
+                    && let Some(context) = &self.base.context
+                {
+                    context.cancellations.remove(task_id);
+                }
+                Ok(None)
+            }
             MessageType::Control(cmd) => {
                 // Handle control command
                 debug!("ExecutorAgent received control command: {:?}", cmd);
@@ -1,23 +1,44 @@
 use async_trait::async_trait;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tracing::{debug, info};
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
 
 use crate::agent::{
     core::base_agent::{AgentBehavior, BaseAgent},
-    types::{AgentCapability, AgentType, TaskStatus},
+    types::{AgentCapability, AgentType, TaskStatus, TaskType},
 };
+use crate::agents::monitor_agent::ALERT_TOPICS;
 use crate::multi_agent::{
-    communication::{Message, MessageType},
-    coordination::task_queue::{Task, TaskQueue},
+    communication::{Message, MessageBus, MessageType},
+    coordination::{
+        decomposer::TaskDecomposer,
+        task_queue::{RetryOutcome, Task, TaskQueue},
+    },
+    registry::{AgentRegistry, SelectionStrategy},
 };
 use crate::shared::GlobalContext;
 use crate::error::Result;
 
+/// Default nesting limit for composite task decomposition
+const DEFAULT_MAX_DECOMPOSITION_DEPTH: usize = 3;
+
 /// Master Agent responsible for task distribution and global coordination
 pub struct MasterAgent {
     base: BaseAgent,
     task_queue: Arc<TaskQueue>,
-    active_tasks: std::collections::HashMap<String, Task>,
+    active_tasks: HashMap<String, Task>,
+    /// Assignment message id -> task id, used to match replies
+    assignments: HashMap<String, String>,
+    registry: Option<Arc<AgentRegistry>>,
+    message_bus: Option<Arc<MessageBus>>,
+    strategy: SelectionStrategy,
+    decomposer: Option<Arc<dyn TaskDecomposer>>,
+    max_decomposition_depth: usize,
+    /// Subtask id -> parent task id
+    parents: HashMap<String, String>,
+    /// Parent task id -> subtasks that have not finished yet
+    open_subtasks: HashMap<String, HashSet<String>>,
 }
 
 impl MasterAgent {
@@ -31,52 +52,533 @@ impl MasterAgent {
         Self {
             base: BaseAgent::new(id, AgentType::Master, capabilities),
             task_queue: Arc::new(TaskQueue::new()),
-            active_tasks: std::collections::HashMap::new(),
+            active_tasks: HashMap::new(),
+            assignments: HashMap::new(),
+            registry: None,
+            message_bus: None,
+            strategy: SelectionStrategy::default(),
+            decomposer: None,
+            max_decomposition_depth: DEFAULT_MAX_DECOMPOSITION_DEPTH,
+            parents: HashMap::new(),
+            open_subtasks: HashMap::new(),
+        }
+    }
+
+    /// Dispatch queued tasks to agents found in `registry`, sending assignments over `message_bus`.
+    ///
+    /// Without this the master only accepts and queues tasks.
+    pub fn with_dispatch(
+        mut self,
+        registry: Arc<AgentRegistry>,
+        message_bus: Arc<MessageBus>,
+    ) -> Self {
+        self.registry = Some(registry);
+        self.message_bus = Some(message_bus);
+        self
+    }
+
+    /// Set the strategy used to pick one agent among the capable candidates
+    ///
+    /// With `ConsistentHash` each task is hashed by its `metadata.key` (or its id); the key
+    /// given here is ignored.
+    pub fn with_selection_strategy(mut self, strategy: SelectionStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Split `Composite` tasks into subtasks with `decomposer` before queueing them
+    pub fn with_decomposer(mut self, decomposer: Arc<dyn TaskDecomposer>) -> Self {
+        self.decomposer = Some(decomposer);
+        self
+    }
+
+    /// Set how many levels of composite subtasks may be decomposed
+    pub fn with_max_decomposition_depth(mut self, depth: usize) -> Self {
+        self.max_decomposition_depth = depth;
+        self
+    }
+
+    /// Use `task_queue` instead of a fresh in-memory queue, e.g. one opened with
+    /// `TaskQueue::with_journal` so queued tasks survive a restart
+    pub fn with_task_queue(mut self, task_queue: Arc<TaskQueue>) -> Self {
+        self.task_queue = task_queue;
+        self
+    }
+
+    /// Get the task queue
+    pub fn task_queue(&self) -> Arc<TaskQueue> {
+        self.task_queue.clone()
+    }
+
+    /// Decompose a composite task into subtasks; other tasks are returned unchanged
+    ///
+    /// Subtask dependencies are mapped to the new task ids, and every subtask also waits
+    /// for the dependencies of its parent.
+    async fn decompose_task(&self, task: &Task, depth: usize) -> Result<Vec<Task>> {
+        let Some(decomposer) = &self.decomposer else {
+            return Ok(vec![task.clone()]);
+        };
+        if task.task_type != TaskType::Composite || depth >= self.max_decomposition_depth {
+            return Ok(vec![task.clone()]);
+        }
+
+        let allow_composite = depth + 1 < self.max_decomposition_depth;
+        let specs = decomposer.decompose(task, allow_composite).await?;
+
+        let mut ids: HashMap<String, String> = HashMap::new();
+        let mut subtasks = Vec::with_capacity(specs.len());
+        for spec in specs {
+            let mut payload = serde_json::json!({
+                "goal": spec.description,
+                "description": spec.description,
+            });
+            if let Some(serde_json::Value::Object(extra)) = spec.payload {
+                for (key, value) in extra {
+                    payload[key] = value;
+                }
+            }
+
+            let mut subtask =
+                Task::new(spec.task_type, task.priority, payload, self.base.id.clone())
+                    .with_capabilities(spec.capabilities)
+                    .with_metadata(serde_json::json!({
+                        "parent_id": task.id,
+                        "local_id": spec.id,
+                    }));
+            subtask.deadline = task.deadline;
+            for dependency in &task.dependencies {
+                subtask.add_dependency(dependency.clone());
+            }
+            for dependency in &spec.depends_on {
+                if let Some(id) = ids.get(dependency) {
+                    subtask.add_dependency(id.clone());
+                }
+            }
+
+            ids.insert(spec.id, subtask.id.clone());
+            subtasks.push(subtask);
+        }
+
+        Ok(subtasks)
+    }
+
+    /// Queue a task, recursively decomposing composite tasks first
+    ///
+    /// A decomposed parent is held in progress until all of its subtasks finish.
+    /// Returns the number of tasks actually queued.
+    async fn expand_task(&mut self, task: Task, depth: usize) -> Result<usize> {
+        let subtasks = self.decompose_task(&task, depth).await?;
+        if subtasks.len() == 1 && subtasks[0].id == task.id {
+            self.task_queue.enqueue(task).await?;
+            return Ok(1);
+        }
+
+        info!("Task {} decomposed into {} subtasks", task.id, subtasks.len());
+        self.open_subtasks.insert(
+            task.id.clone(),
+            subtasks.iter().map(|subtask| subtask.id.clone()).collect(),
+        );
+        for subtask in &subtasks {
+            self.parents.insert(subtask.id.clone(), task.id.clone());
+        }
+        self.task_queue.mark_in_progress_unleased(task).await?;
+
+        let mut queued = 0;
+        for subtask in subtasks {
+            queued += Box::pin(self.expand_task(subtask, depth + 1)).await?;
+        }
+        Ok(queued)
+    }
+
+    /// Propagate a completed subtask to its parents
+    ///
+    /// A parent completes once all of its subtasks completed.
+    async fn complete_subtask(&mut self, task_id: &str) -> Result<()> {
+        let mut child_id = task_id.to_string();
+
+        while let Some(parent_id) = self.parents.remove(&child_id) {
+            let Some(open) = self.open_subtasks.get_mut(&parent_id) else {
+                // Parent already failed
+                break;
+            };
+
+            open.remove(&child_id);
+            if !open.is_empty() {
+                break;
+            }
+            self.open_subtasks.remove(&parent_id);
+            info!("Task {} completed: all subtasks finished", parent_id);
+            self.task_queue.mark_completed(&parent_id).await?;
+
+            child_id = parent_id;
         }
+
+        Ok(())
+    }
+
+    /// Fail the task and propagate it
+    async fn fail_task(&mut self, task_id: &str, reason: String) -> Result<()> {
+        let dependents = self.task_queue.mark_failed(task_id, reason.clone()).await?;
+        self.propagate_failure(task_id, reason, dependents).await
     }
 
-    /// Decompose tasks
-    async fn decompose_task(&self, task: &Task) -> Result<Vec<Task>> {
-        // TODO: Implement intelligent task decomposition logic
-        // Simply return the original task here
-        Ok(vec![task.clone()])
+    /// Propagate a failed task to its parents
+    ///
+    /// `dependents` are the queued tasks that already failed with it. A parent fails as
+    /// soon as one of its subtasks fails, which in turn fails the parent's own dependents.
+    async fn propagate_failure(
+        &mut self,
+        task_id: &str,
+        reason: String,
+        dependents: Vec<String>,
+    ) -> Result<()> {
+        let upstream = |id: &str, dependents: Vec<String>| {
+            let reason = format!("upstream task {id} failed");
+            dependents.into_iter().map(move |dependent| (dependent, reason.clone()))
+        };
+        let mut failed: Vec<(String, String)> = upstream(task_id, dependents).collect();
+        failed.push((task_id.to_string(), reason));
+
+        while let Some((child_id, reason)) = failed.pop() {
+            self.active_tasks.remove(&child_id);
+            let Some(parent_id) = self.parents.remove(&child_id) else {
+                continue;
+            };
+            if self.open_subtasks.remove(&parent_id).is_none() {
+                // Parent already failed
+                continue;
+            }
+
+            warn!("Task {} failed because subtask {} failed", parent_id, child_id);
+            let reason = format!("subtask {child_id} failed: {reason}");
+            let dependents = self.task_queue.mark_failed(&parent_id, reason.clone()).await?;
+            failed.extend(upstream(&parent_id, dependents));
+            failed.push((parent_id, reason));
+        }
+
+        Ok(())
     }
 
-    /// Assign task to suitable Agent
-    #[allow(dead_code)]
-    async fn assign_task(&mut self, task: &mut Task, agent_id: String) -> Result<()> {
+    /// Selection strategy to use for one task
+    fn strategy_for(&self, task: &Task) -> SelectionStrategy {
+        match &self.strategy {
+            SelectionStrategy::ConsistentHash(_) => {
+                let key = task
+                    .metadata
+                    .get("key")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or(&task.id);
+                SelectionStrategy::ConsistentHash(key.to_string())
+            }
+            strategy => strategy.clone(),
+        }
+    }
+
+    /// Build the `TaskAssignment` payload in the shape the worker agents expect
+    fn assignment_payload(&self, task: &Task) -> serde_json::Value {
+        let mut payload = match &task.payload {
+            serde_json::Value::Object(_) => task.payload.clone(),
+            serde_json::Value::Null => serde_json::json!({}),
+            other => serde_json::json!({ "data": other }),
+        };
+        payload["id"] = serde_json::json!(task.id);
+        payload["task_type"] = serde_json::json!(task.task_type.to_string());
+        payload["requester_id"] = serde_json::json!(self.base.id);
+        payload
+    }
+
+    /// Assign waiting tasks to capable agents
+    ///
+    /// A task whose assignment could not be sent stays waiting and is tried again on the
+    /// next tick.
+    async fn dispatch_waiting_tasks(&mut self) {
+        let (Some(registry), Some(message_bus)) = (self.registry.clone(), self.message_bus.clone())
+        else {
+            return;
+        };
+
+        let waiting: Vec<Task> = self
+            .active_tasks
+            .values()
+            .filter(|task| task.status == TaskStatus::Pending)
+            .cloned()
+            .collect();
+
+        for task in waiting {
+            let strategy = self.strategy_for(&task);
+            let selected = registry
+                .select_matching(&task.capabilities(), &strategy, &[self.base.id.as_str()])
+                .await;
+            let Some(agent) = selected else {
+                debug!("No agent available for task {} ({})", task.id, task.task_type);
+                continue;
+            };
+
+            if let Err(e) = self.assign_task(task, agent.id, &registry, &message_bus).await {
+                warn!("Failed to assign task: {:?}", e);
+            }
+        }
+    }
+
+    /// Assign task to an Agent: send the assignment, then lease the task and track the reply
+    ///
+    /// The task is only marked in progress once the assignment was sent, so a failed send
+    /// leaves it waiting instead of leased to an agent that never heard of it.
+    async fn assign_task(
+        &mut self,
+        mut task: Task,
+        agent_id: String,
+        registry: &AgentRegistry,
+        message_bus: &MessageBus,
+    ) -> Result<()> {
         task.assigned_to = Some(agent_id.clone());
-        task.status = TaskStatus::Assigned(agent_id);
-        
-        info!("Task {} assigned to agent {}", task.id, task.assigned_to.as_ref().unwrap());
+
+        let message = Message::new(
+            self.base.id.clone(),
+            Some(agent_id.clone()),
+            MessageType::TaskAssignment,
+            self.assignment_payload(&task),
+        );
+        let message_id = message.id.clone();
+        message_bus.send(message).await?;
+
+        let leased = self.task_queue.mark_in_progress(task.clone()).await;
+
+        task.update_status(TaskStatus::Assigned(agent_id.clone()));
+        self.assignments.insert(message_id, task.id.clone());
+        self.active_tasks.insert(task.id.clone(), task.clone());
+        info!("Task {} assigned to agent {}", task.id, agent_id);
+
+        if let Err(e) = registry.begin_task(&agent_id).await {
+            debug!("Could not record task on agent {}: {:?}", agent_id, e);
+        }
+        leased
+    }
+
+    /// Handle a `ResultNotification` or `Error` reply to an assignment
+    async fn handle_task_reply(&mut self, message: &Message) -> Result<bool> {
+        let task_id = message
+            .correlation_id
+            .as_ref()
+            .and_then(|id| self.assignments.remove(id))
+            .or_else(|| {
+                message
+                    .payload
+                    .get("task_id")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string())
+            });
+
+        // Only tasks that were actually handed out can be completed by a reply
+        let Some(task) = task_id
+            .filter(|id| {
+                self.active_tasks
+                    .get(id)
+                    .is_some_and(|task| matches!(task.status, TaskStatus::Assigned(_)))
+            })
+            .and_then(|id| self.active_tasks.remove(&id))
+        else {
+            return Ok(false);
+        };
+        self.assignments.retain(|_, id| id != &task.id);
+        // The task is finished, so a cancellation that arrived for it is no longer needed
+        if let Some(context) = &self.base.context {
+            context.cancellations.remove(&task.id);
+        }
+
+        // A result reporting `success: false` is a failure even if it was not sent as `Error`
+        let unsuccessful = message.payload["result"]["success"].as_bool() == Some(false);
+        if message.message_type == MessageType::Error || unsuccessful {
+            let reason = message
+                .payload
+                .get("error")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown error")
+                .to_string();
+            warn!("Task {} failed on agent {}: {}", task.id, message.sender_id, reason);
+
+            // Failures are retried unless the agent marks them as permanent
+            let retryable = message
+                .payload
+                .get("retryable")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            if !retryable {
+                self.fail_task(&task.id, reason).await?;
+            } else if let RetryOutcome::DeadLettered { failed_dependents } =
+                self.task_queue.mark_retryable_failure(&task.id, reason.clone()).await?
+            {
+                self.propagate_failure(&task.id, reason, failed_dependents).await?;
+            }
+        } else {
+            info!("Task {} completed by agent {}", task.id, message.sender_id);
+            self.task_queue.mark_completed(&task.id).await?;
+            self.complete_subtask(&task.id).await?;
+        }
+
+        if let Some(registry) = &self.registry
+            && let Err(e) = registry.end_task(&message.sender_id).await
+        {
+            debug!("Could not record finished task on agent {}: {:?}", message.sender_id, e);
+        }
+        Ok(true)
+    }
+
+    /// Take back tasks whose lease expired, e.g. because the assigned agent died
+    ///
+    /// Retried tasks go back to the queue and are dispatched again once their backoff
+    /// elapses; dead-lettered tasks fail their parents.
+    async fn reclaim_expired_tasks(&mut self) -> Result<()> {
+        for (task, outcome) in self.task_queue.reclaim_expired_leases().await {
+            warn!("Lease of task {} expired ({:?})", task.id, outcome);
+            self.active_tasks.remove(&task.id);
+            self.assignments.retain(|_, id| id != &task.id);
+
+            if let (Some(registry), Some(agent_id)) = (&self.registry, &task.assigned_to)
+                && let Err(e) = registry.end_task(agent_id).await
+            {
+                debug!("Could not record reclaimed task on agent {}: {:?}", agent_id, e);
+            }
+            if let RetryOutcome::DeadLettered { failed_dependents } = outcome {
+                self.propagate_failure(&task.id, "lease expired".into(), failed_dependents)
+                    .await?;
+            }
+        }
         Ok(())
     }
 
+    /// Cancel a task together with its open subtasks and every task depending on them
+    ///
+    /// Agents running one of the cancelled tasks receive a `TaskCancellation`, and parents
+    /// of cancelled subtasks fail unless they are cancelled too.
+    async fn handle_task_cancellation(&mut self, message: &Message) -> Result<Message> {
+        let master_id = self.base.id.clone();
+        let reply = |message_type, payload| {
+            Message::new(
+                master_id.clone(),
+                Some(message.sender_id.clone()),
+                message_type,
+                payload,
+            )
+        };
+        let Some(task_id) = message.payload.get("task_id").and_then(|v| v.as_str()) else {
+            return Ok(reply(
+                MessageType::Error,
+                serde_json::json!({ "error": "missing task_id" }),
+            ));
+        };
+
+        // A decomposed task takes its open subtasks with it
+        let mut roots = vec![task_id.to_string()];
+        let mut index = 0;
+        while index < roots.len() {
+            if let Some(children) = self.open_subtasks.remove(&roots[index]) {
+                roots.extend(children);
+            }
+            index += 1;
+        }
+
+        let mut cancelled = Vec::new();
+        for id in &roots {
+            match self.task_queue.cancel(id).await {
+                Ok(tasks) => cancelled.extend(tasks),
+                // Already cancelled as a dependent of an earlier root
+                Err(e) => debug!("Task {} not cancelled: {:?}", id, e),
+            }
+        }
+        if cancelled.is_empty() {
+            return Ok(reply(
+                MessageType::Error,
+                serde_json::json!({
+                    "task_id": task_id,
+                    "error": format!("task {task_id} is not pending or in progress"),
+                }),
+            ));
+        }
+
+        let cancelled_ids: HashSet<String> = cancelled.iter().map(|t| t.id.clone()).collect();
+        for task in &cancelled {
+            self.active_tasks.remove(&task.id);
+            self.assignments.retain(|_, id| id != &task.id);
+            self.open_subtasks.remove(&task.id);
+
+            if task.status == TaskStatus::InProgress
+                && let Some(agent_id) = &task.assigned_to
+            {
+                info!("Asking agent {} to stop task {}", agent_id, task.id);
+                if let Some(message_bus) = &self.message_bus {
+                    let cancellation = Message::new(
+                        self.base.id.clone(),
+                        Some(agent_id.clone()),
+                        MessageType::TaskCancellation,
+                        serde_json::json!({ "task_id": task.id }),
+                    );
+                    if let Err(e) = message_bus.send(cancellation).await {
+                        warn!("Failed to send cancellation to agent {}: {:?}", agent_id, e);
+                    }
+                }
+                if let Some(registry) = &self.registry
+                    && let Err(e) = registry.end_task(agent_id).await
+                {
+                    debug!("Could not record cancelled task on agent {}: {:?}", agent_id, e);
+                }
+            }
+
+            let parent_cancelled = self
+                .parents
+                .get(&task.id)
+                .is_some_and(|parent_id| cancelled_ids.contains(parent_id));
+            if parent_cancelled {
+                self.parents.remove(&task.id);
+            } else {
+                self.propagate_failure(&task.id, "cancelled".into(), Vec::new()).await?;
+            }
+        }
+
+        info!("Task {} cancelled along with {} other task(s)", task_id, cancelled.len() - 1);
+        Ok(reply(
+            MessageType::ResultNotification,
+            serde_json::json!({
+                "task_id": task_id,
+                "status": "cancelled",
+                "cancelled": cancelled_ids,
+            }),
+        ))
+    }
+
     /// Handle task assignment request
     async fn handle_task_assignment(&mut self, payload: serde_json::Value) -> Result<Message> {
         // Parse task from payload
         let task: Task = serde_json::from_value(payload.clone())
             .map_err(|e| crate::error::agent_error::AgentError::ParseError(e.to_string()))?;
 
-        // Add to task queue
-        self.task_queue.enqueue(task.clone()).await?;
-        
-        // Decompose task
-        let subtasks = self.decompose_task(&task).await?;
-        
-        // TODO: Find suitable executor and assign task
-        
-        // Return confirmation message
-        Ok(Message::new(
-            self.base.id.clone(),
-            Some(task.created_by.clone()),
-            MessageType::ResultNotification,
-            serde_json::json!({
-                "task_id": task.id,
-                "status": "accepted",
-                "subtasks": subtasks.len()
-            }),
-        ))
+        // Decompose composite tasks and add the results to the task queue
+        let task_id = task.id.clone();
+        let created_by = task.created_by.clone();
+        match self.expand_task(task, 0).await {
+            Ok(queued) => Ok(Message::new(
+                self.base.id.clone(),
+                Some(created_by),
+                MessageType::ResultNotification,
+                serde_json::json!({
+                    "task_id": task_id,
+                    "status": "accepted",
+                    "subtasks": queued
+                }),
+            )),
+            Err(e) => {
+                warn!("Task {} rejected: {:?}", task_id, e);
+                Ok(Message::new(
+                    self.base.id.clone(),
+                    Some(created_by),
+                    MessageType::Error,
+                    serde_json::json!({
+                        "task_id": task_id,
+                        "error": e.to_string(),
+                    }),
+                ))
+            }
+        }
     }
 
     /// Handle status update
@@ -112,6 +614,10 @@ impl AgentBehavior for MasterAgent {
     }
 
     async fn initialize(&mut self, context: Arc<GlobalContext>) -> Result<()> {
+        // Tasks that outlive the configured timeout lose their lease and are retried
+        let timeout_secs = context.get_config().await.task_timeout_secs;
+        self.task_queue.set_lease_timeout(Duration::from_secs(timeout_secs)).await;
+
         self.base.context = Some(context);
         info!("MasterAgent {} initialized", self.base.id);
         Ok(())
@@ -128,10 +634,30 @@ impl AgentBehavior for MasterAgent {
             MessageType::StatusUpdate => {
                 self.handle_status_update(message.payload).await
             }
+            MessageType::TaskCancellation => {
+                let response = self.handle_task_cancellation(&message).await?;
+                Ok(Some(response))
+            }
+            MessageType::ResultNotification | MessageType::Error => {
+                if !self.handle_task_reply(&message).await? {
+                    debug!("MasterAgent ignoring reply from {}", message.sender_id);
+                }
+                Ok(None)
+            }
             MessageType::ResourceRequest => {
                 // TODO: Handle resource request
                 Ok(None)
             }
+            MessageType::Custom(msg_type) if msg_type == "Alert" => {
+                let alert = &message.payload;
+                let (name, text) = (&alert["alert_name"], &alert["message"]);
+                if alert["severity"] == "CRITICAL" {
+                    error!("Alert {} from {}: {}", name, message.sender_id, text);
+                } else {
+                    warn!("Alert {} from {}: {}", name, message.sender_id, text);
+                }
+                Ok(None)
+            }
             _ => {
                 debug!("MasterAgent ignoring message type: {:?}", message.message_type);
                 Ok(None)
@@ -139,37 +665,38 @@ impl AgentBehavior for MasterAgent {
         }
     }
 
-    async fn run(&mut self) -> Result<()> {
-        info!("MasterAgent {} starting main loop", self.base.id);
-        
-        // Main loop: process task queue
-        loop {
-            // Check if there are tasks pending assignment
-            if let Some(task) = self.task_queue.dequeue().await {
-                info!("Processing task: {}", task.id);
-                
-                // TODO: Implement intelligent task assignment logic
-                // 1. Find suitable Agent
-                // 2. Send task assignment message
-                // 3. Track task status
-                
-                self.active_tasks.insert(task.id.clone(), task);
-            }
-            
-            // Avoid busy waiting
-            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    fn topic_subscriptions(&self) -> Vec<String> {
+        vec![ALERT_TOPICS.to_string()]
+    }
+
+    fn tick_interval(&self) -> Option<std::time::Duration> {
+        Some(std::time::Duration::from_millis(100))
+    }
+
+    async fn tick(&mut self) -> Result<()> {
+        if let Err(e) = self.reclaim_expired_tasks().await {
+            warn!("Failed to reclaim expired tasks: {:?}", e);
         }
+
+        // Move tasks whose dependencies are satisfied out of the queue
+        while let Some(task) = self.task_queue.dequeue().await {
+            info!("Processing task: {}", task.id);
+            self.active_tasks.insert(task.id.clone(), task);
+        }
+
+        self.dispatch_waiting_tasks().await;
+        Ok(())
     }
 
     async fn shutdown(&mut self) -> Result<()> {
         info!("MasterAgent {} shutting down", self.base.id);
         
-        // Save pending tasks
+        // Save pending tasks; a journaled queue replays them on the next start
         let pending_tasks = self.task_queue.get_all_pending().await;
         if !pending_tasks.is_empty() {
             info!("Saving {} pending tasks", pending_tasks.len());
-            // TODO: Persist unfinished tasks
         }
+        self.task_queue.compact().await?;
         
         Ok(())
     }
@@ -184,7 +711,7 @@ impl AgentBehavior for MasterAgent {
             "type": self.base.agent_type,
             "healthy": self.is_healthy(),
             "active_tasks": self.active_tasks.len(),
-            "queued_tasks": self.task_queue.size()
+            "decomposed_tasks": self.open_subtasks.len()
         })
     }
 }
\ No newline at end of file
@@ -9,7 +9,7 @@ use crate::agent::{
     core::base_agent::{AgentBehavior, BaseAgent},
     types::{AgentCapability, AgentType},
 };
-use crate::multi_agent::communication::{Message, MessageType};
+use crate::multi_agent::communication::{Message, MessageBus, MessageType};
 use crate::shared::GlobalContext;
 use crate::error::Result;
 
@@ -23,6 +23,8 @@ pub struct Metrics {
     pub agent_health: HashMap<String, bool>,
     pub message_count: u64,
     pub error_count: u64,
+    pub agent_crashes: u64,
+    pub agent_restarts: u64,
 }
 
 /// Monitor Agent responsible for system monitoring and health checks
@@ -31,8 +33,13 @@ pub struct MonitorAgent {
     metrics: Arc<RwLock<Metrics>>,
     alert_rules: Vec<AlertRule>,
     monitoring_interval: std::time::Duration,
+    /// Where alerts are published; without it they are only logged
+    message_bus: Option<Arc<MessageBus>>,
 }
 
+/// Topic pattern matching every alert published by a `MonitorAgent`
+pub const ALERT_TOPICS: &str = "monitor.alert.#";
+
 /// Alert rule
 #[derive(Debug, Clone)]
 pub struct AlertRule {
@@ -68,6 +75,7 @@ impl MonitorAgent {
             metrics: Arc::new(RwLock::new(Metrics::default())),
             alert_rules: Vec::new(),
             monitoring_interval: std::time::Duration::from_secs(10),
+            message_bus: None,
         }
     }
 
@@ -77,6 +85,12 @@ impl MonitorAgent {
         self
     }
 
+    /// Publish alerts on `message_bus`
+    pub fn with_message_bus(mut self, message_bus: Arc<MessageBus>) -> Self {
+        self.message_bus = Some(message_bus);
+        self
+    }
+
     /// Set monitoring interval
     pub fn with_interval(mut self, interval: std::time::Duration) -> Self {
         self.monitoring_interval = interval;
@@ -165,7 +179,7 @@ impl MonitorAgent {
         triggered_alerts
     }
 
-    /// Send alert
+    /// Build an alert, published on `monitor.alert.<severity>`
     async fn send_alert(&self, rule: &AlertRule, message: String) -> Message {
         let severity_str = match rule.severity {
             AlertSeverity::Info => "INFO",
@@ -175,8 +189,9 @@ impl MonitorAgent {
 
         warn!("[{}] Alert '{}': {}", severity_str, rule.name, message);
 
-        Message::broadcast(
+        Message::publish(
             self.base.id.clone(),
+            format!("monitor.alert.{}", severity_str.to_lowercase()),
             MessageType::Custom("Alert".to_string()),
             serde_json::json!({
                 "alert_name": rule.name,
@@ -214,6 +229,8 @@ impl MonitorAgent {
                 "success_rate": success_rate,
                 "message_count": metrics.message_count,
                 "error_count": metrics.error_count,
+                "agent_crashes": metrics.agent_crashes,
+                "agent_restarts": metrics.agent_restarts,
             },
             "agent_health": metrics.agent_health,
         })
@@ -254,6 +271,22 @@ impl AgentBehavior for MonitorAgent {
                 self.metrics.write().await.error_count += 1;
                 Ok(None)
             }
+            MessageType::Custom(msg_type) if msg_type == "AgentCrashed" => {
+                let mut metrics = self.metrics.write().await;
+                metrics.agent_crashes += 1;
+                if let Some(agent_id) = message.payload.get("agent_id").and_then(|v| v.as_str()) {
+                    metrics.agent_health.insert(agent_id.to_string(), false);
+                }
+                Ok(None)
+            }
+            MessageType::Custom(msg_type) if msg_type == "AgentRestarted" => {
+                let mut metrics = self.metrics.write().await;
+                metrics.agent_restarts += 1;
+                if let Some(agent_id) = message.payload.get("agent_id").and_then(|v| v.as_str()) {
+                    metrics.agent_health.insert(agent_id.to_string(), true);
+                }
+                Ok(None)
+            }
             MessageType::Custom(msg_type) if msg_type == "HealthCheck" => {
                 let report = self.generate_health_report().await;
                 Ok(Some(Message::response(
@@ -268,27 +301,28 @@ impl AgentBehavior for MonitorAgent {
         }
     }
 
-    async fn run(&mut self) -> Result<()> {
-        info!("MonitorAgent {} starting monitoring loop", self.base.id);
-        
-        let mut interval = tokio::time::interval(self.monitoring_interval);
-        
-        loop {
-            interval.tick().await;
-            
-            // Check alerts
-            let alerts = self.check_alerts().await;
-            for (rule, message) in alerts {
-                let _alert_msg = self.send_alert(&rule, message).await;
-                // TODO: Send alert message
-            }
-            
-            // Generate periodic health report
-            if self.metrics.read().await.message_count % 100 == 0 {
-                let report = self.generate_health_report().await;
-                info!("Health report: {}", serde_json::to_string_pretty(&report)?);
+    fn tick_interval(&self) -> Option<std::time::Duration> {
+        Some(self.monitoring_interval)
+    }
+
+    async fn tick(&mut self) -> Result<()> {
+        // Check alerts
+        let alerts = self.check_alerts().await;
+        for (rule, message) in alerts {
+            let alert = self.send_alert(&rule, message).await;
+            if let Some(message_bus) = &self.message_bus
+                && let Err(e) = message_bus.send(alert).await
+            {
+                warn!("Failed to publish alert '{}': {:?}", rule.name, e);
             }
         }
+
+        // Generate periodic health report
+        if self.metrics.read().await.message_count % 100 == 0 {
+            let report = self.generate_health_report().await;
+            info!("Health report: {}", serde_json::to_string_pretty(&report)?);
+        }
+        Ok(())
     }
 
     async fn shutdown(&mut self) -> Result<()> {
@@ -6,11 +6,12 @@ use model_gateway_rs::sdk::openai::OpenAiSdk;
 use crate::agent::{
     core::base_agent::{AgentBehavior, BaseAgent},
     planning::{AgentPlan, Planner},
+    state::AgentState,
     types::{AgentCapability, AgentType},
 };
 use crate::multi_agent::communication::{Message, MessageType};
 use crate::shared::GlobalContext;
-use crate::error::Result;
+use crate::error::{Result, agent_error::AgentError};
 use crate::input::model::UserTaskInput;
 
 /// Planner Agent responsible for generating execution plans
@@ -61,15 +62,23 @@ impl PlannerAgent {
             }
         };
 
-        // Generate plan
-        match self.planner.generate_plan(&user_input).await {
-            Ok(llm_output) => {
-                // Return the entire LLM output as plan
-                // TODO: Implement more intelligent plan parsing
-                let plan_json = serde_json::json!({
-                    "plan": format!("{:?}", llm_output), // Temporary solution: convert output to string
-                    "user_input": user_input,
-                });
+        // Generate plan, or revise the failed one sent along with the request
+        let plan = match payload.get("replan") {
+            Some(replan) => self.revise_plan(&user_input, replan).await,
+            None => self.planner.generate_plan(&user_input).await,
+        };
+        let plan = match plan {
+            Ok(plan) => self.optimize_plan(&plan).await,
+            Err(e) => Err(e),
+        };
+        match plan {
+            Ok(plan) => {
+                info!(
+                    "PlannerAgent {} generated plan {} with {} steps",
+                    self.base.id,
+                    plan.plan_id,
+                    plan.steps.len()
+                );
 
                 Ok(Message::new(
                     self.base.id.clone(),
@@ -79,7 +88,8 @@ impl PlannerAgent {
                     MessageType::ResultNotification,
                     serde_json::json!({
                         "status": "success",
-                        "plan": plan_json,
+                        "plan": plan,
+                        "user_input": user_input,
                     }),
                 ))
             }
@@ -99,12 +109,27 @@ impl PlannerAgent {
         }
     }
 
+    /// Revise a failed plan given as `{"plan", "state", "reason"}`
+    async fn revise_plan(
+        &self,
+        user_input: &UserTaskInput,
+        replan: &serde_json::Value,
+    ) -> Result<AgentPlan> {
+        let plan: AgentPlan = serde_json::from_value(replan["plan"].clone())?;
+        let state: AgentState = serde_json::from_value(replan["state"].clone())?;
+        let reason = replan["reason"].as_str().unwrap_or_default();
+        self.planner.replan(user_input, &plan, &state, reason).await
+    }
+
     /// Optimize existing plan
-    #[allow(dead_code)]
+    ///
+    /// Steps that do not declare their dependencies and only call read-only tools are
+    /// relaxed so they can run in parallel; see `AgentPlan::infer_dependencies`.
     async fn optimize_plan(&self, plan: &AgentPlan) -> Result<AgentPlan> {
-        // TODO: Implement plan optimization logic
-        // e.g.: parallelize independent steps, merge similar steps, etc.
-        Ok(plan.clone())
+        let mut optimized = plan.clone();
+        optimized.infer_dependencies();
+        optimized.dependency_graph().validate().map_err(AgentError::from)?;
+        Ok(optimized)
     }
 }
 
@@ -1,3 +1,6 @@
+use crate::tools::schema::{SchemaError, format_schema_errors};
+use crate::utils::dag::DagError;
+
 #[derive(Debug, thiserror::Error)]
 pub enum AgentError {
     #[error("执行失败: {0}")]
@@ -9,12 +12,33 @@ pub enum AgentError {
     #[error("计划已耗尽")]
     PlanExhausted,
 
+    #[error("任务已取消")]
+    Cancelled,
+
+    #[error("等待用户回答超时: {0}")]
+    AnswerTimeout(String),
+
+    #[error("计划无效: {0}")]
+    InvalidPlan(String),
+
+    #[error("引用无法解析: {0}")]
+    UnresolvedReference(String),
+
+    #[error("工具 '{tool}' 参数校验失败: {}", format_schema_errors(.errors))]
+    InvalidToolParameters {
+        tool: String,
+        errors: Vec<SchemaError>,
+    },
+
     #[error("Agent未找到: {0}")]
     AgentNotFound(String),
 
     #[error("任务未找到: {0}")]
     TaskNotFound(String),
 
+    #[error("依赖无效: {0}")]
+    InvalidDependency(#[from] DagError),
+
     #[error("消息传递失败: {0}")]
     MessageDeliveryError(String),
 
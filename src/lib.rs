@@ -2,10 +2,12 @@ pub mod agent;
 pub mod agents;
 pub mod error;
 pub mod input;
+#[cfg(feature = "mcp")]
 pub mod mcp;
 pub mod message;
 pub mod multi_agent;
 pub mod prompt;
+pub mod runtime;
 pub mod shared;
 pub mod tools;
 pub mod utils;
@@ -36,6 +36,14 @@ async fn register_mcp_info() {
 
                             for tool in tools_list {
                                 let tool_name = tool.name.clone();
+                                // A native tool would shadow it when resolving calls
+                                if tool_registry.get(&tool_name).is_some_and(ToolInfo::is_native) {
+                                    eprintln!(
+                                        "Skipped tool from MCP server '{key}': a native tool is \
+                                         already registered as '{tool_name}'"
+                                    );
+                                    continue;
+                                }
                                 let tool_info = ToolInfo::new_with_server(
                                     tool.name,
                                     tool.description,
@@ -1 +1,2 @@
 pub mod instantiate;
+pub mod tool;
@@ -0,0 +1,76 @@
+use async_trait::async_trait;
+use mcp_client::registry::get_mcp_registry;
+use serde_json::{Value, json};
+
+use crate::{error::Result, error::agent_error::AgentError, tools::native::Tool};
+
+/// Tool served by a remote MCP server
+pub struct McpTool {
+    name: String,
+    description: String,
+    input_schema: Value,
+    server: String,
+}
+
+impl McpTool {
+    pub fn new(name: String, description: String, input_schema: Value, server: String) -> Self {
+        Self {
+            name,
+            description,
+            input_schema,
+            server,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for McpTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn input_schema(&self) -> Value {
+        self.input_schema.clone()
+    }
+
+    async fn call(&self, arguments: Value) -> Result<Value> {
+        let tool_name = &self.name;
+        let mcp_server_name = &self.server;
+
+        // 通过 MCP 服务器名称获取对应的客户端
+        let registry = get_mcp_registry();
+        let client = registry.get(mcp_server_name).map_err(|e| {
+            AgentError::ExecutionError(format!(
+                "无法获取 MCP 服务器 '{mcp_server_name}' 的客户端: {e}"
+            ))
+        })?;
+
+        let tool_call_params = json!({
+            "name": tool_name,
+            "arguments": arguments
+        });
+
+        println!("🔧 调用 MCP 服务器 '{mcp_server_name}' 的工具 '{tool_name}', 参数: {tool_call_params:?}");
+
+        match client.call_tool(tool_call_params).await {
+            Ok(response) => {
+                println!("✅ MCP工具调用成功: {response:?}");
+                // 尝试解析响应为JSON
+                match response {
+                    mcp_client::core::protocol::message::JsonRpcMessage::Response(resp) => {
+                        Ok(resp.result.unwrap_or(json!({"success": true})))
+                    }
+                    _ => Ok(json!({"result": "工具执行完成"})),
+                }
+            }
+            Err(e) => Err(AgentError::ExecutionError(format!(
+                "MCP工具 '{tool_name}' 调用失败: {e}"
+            ))
+            .into()),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use model_gateway_rs::model::llm::ChatMessage;
+
+use crate::multi_agent::coordination::task_queue::Task;
+
+/// Messages asking the LLM to split `task` into subtasks.
+///
+/// `allow_composite` is false once the decomposition depth limit is reached.
+pub fn generate_decomposer_message(task: &Task, allow_composite: bool) -> Vec<ChatMessage> {
+    vec![
+        generate_system_message(allow_composite),
+        generate_user_message(task),
+    ]
+}
+
+fn generate_system_message(allow_composite: bool) -> ChatMessage {
+    let task_types = if allow_composite {
+        "Planning, Execution, Verification, Analysis, Monitoring, Composite"
+    } else {
+        "Planning, Execution, Verification, Analysis, Monitoring"
+    };
+
+    let content = format!(
+        r#"
+You are a task decomposition assistant.
+Split the given task into smaller subtasks that agents can work on independently.
+Your only output should be valid JSON matching this structure.
+
+Rules:
+- Give every subtask a short unique "id" such as "s1", "s2"
+- List subtasks in execution order
+- "depends_on" may only contain ids of subtasks listed EARLIER
+- "task_type" must be one of: {task_types}
+- Use Composite only for subtasks that are still too large and need further splitting
+- "capabilities" lists extra capabilities the agent needs besides the task type, for example
+  {{"ToolCalling": "tool_name"}} or {{"Custom": "name"}}; use [] when none are needed
+
+Output JSON structure:
+{{
+  "subtasks": [
+    {{
+      "id": "s1",
+      "description": "string",
+      "task_type": "Execution",
+      "capabilities": [],
+      "depends_on": [],
+      "payload": {{}} or null
+    }}
+  ]
+}}
+
+Never include any notes, explanations, or natural language.
+Only output the JSON in the exact structure above.
+"#
+    );
+    ChatMessage::system(content.as_str())
+}
+
+fn generate_user_message(task: &Task) -> ChatMessage {
+    let content = format!(
+        "Task to decompose:\n{}",
+        serde_json::to_string_pretty(&task.payload).unwrap_or_default()
+    );
+    ChatMessage::user(content.as_str())
+}
@@ -1,2 +1,3 @@
+pub mod decomposer;
 pub mod llm;
 pub mod planner;
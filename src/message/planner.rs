@@ -1,8 +1,10 @@
 use model_gateway_rs::model::llm::ChatMessage;
 
 use crate::{
-    input::UserTaskInput, message::llm::generate_assistant_tools,
-    prompt::builder::build_task_prompt,
+    agent::{planning::AgentPlan, state::AgentState},
+    input::UserTaskInput,
+    message::llm::generate_assistant_tools,
+    prompt::builder::{build_replan_prompt, build_task_prompt},
 };
 
 pub fn generate_planner_message(input: &UserTaskInput) -> Vec<ChatMessage> {
@@ -12,6 +14,21 @@ pub fn generate_planner_message(input: &UserTaskInput) -> Vec<ChatMessage> {
     vec![system_message, tools_message, user_message]
 }
 
+/// Messages asking the planner to revise a failed plan given its execution trace
+pub fn generate_replanner_message(
+    input: &UserTaskInput,
+    plan: &AgentPlan,
+    state: &AgentState,
+    reason: &str,
+) -> Vec<ChatMessage> {
+    let mut messages = generate_planner_message(input);
+    let previous_plan = serde_json::to_string(plan).unwrap_or_default();
+    messages.push(ChatMessage::assistant(previous_plan.as_str()));
+    let content = build_replan_prompt(plan, state, reason);
+    messages.push(ChatMessage::user(content.as_str()));
+    messages
+}
+
 fn generate_system_message() -> ChatMessage {
     let content = r#"
 You are a task planning assistant.
@@ -34,6 +51,19 @@ For call_tool actions:
 For ask_user actions:
 - Set "tool" to null
 - Set "input" to contain the question: {"question": "Your question here"}
+- To let the user pick from fixed answers, also set "choices":
+  {"question": "Continue?", "choices": ["yes", "no"]}
+
+Referencing earlier results:
+- Inside "parameters" or "input", use {{steps.<step_id>.output.<field>}} to insert the output of an earlier step
+- For example {{steps.1.output.answer}} is the user's answer to ask_user step 1
+- Only reference steps with a smaller step_id
+
+Step dependencies:
+- Set "depends_on" to the step_ids that must finish before a step can start
+- Steps whose dependencies are all finished run in parallel, so use [] for a step that needs nothing
+- If "depends_on" is omitted, the step waits for the step before it
+- Never create circular dependencies
 
 Output JSON structure:
 {
@@ -46,7 +76,8 @@ Output JSON structure:
       "action": "call_tool or ask_user",
       "tool": "tool_name or null",
       "parameters": {"param": "value"} or null,
-      "input": {"question": "text"} or null
+      "input": {"question": "text"} or null,
+      "depends_on": [step_id, ...]
     }
   ]
 }
@@ -0,0 +1,77 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::multi_agent::communication::message::{Message, MessageType};
+
+/// Why a message could not be delivered to an agent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeliveryFailure {
+    /// No agent with the receiver id is registered
+    AgentNotFound,
+    /// The agent's channel stayed full
+    ChannelFull,
+    /// The agent stopped receiving
+    ChannelClosed,
+}
+
+impl fmt::Display for DeliveryFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            Self::AgentNotFound => "agent not found",
+            Self::ChannelFull => "channel full",
+            Self::ChannelClosed => "channel closed",
+        };
+        f.write_str(reason)
+    }
+}
+
+/// A message that ran out of delivery attempts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub message: Message,
+    /// The agent it was meant for; for topic messages this is the subscriber
+    pub receiver_id: String,
+    pub reason: DeliveryFailure,
+    pub attempts: u32,
+    pub dead_lettered_at: DateTime<Utc>,
+}
+
+/// Dead letter filter
+#[derive(Debug, Clone, Default)]
+pub struct DeadLetterFilter {
+    pub receiver_id: Option<String>,
+    pub message_type: Option<MessageType>,
+    pub reason: Option<DeliveryFailure>,
+}
+
+impl DeadLetterFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn for_receiver(mut self, receiver_id: String) -> Self {
+        self.receiver_id = Some(receiver_id);
+        self
+    }
+
+    pub fn with_type(mut self, message_type: MessageType) -> Self {
+        self.message_type = Some(message_type);
+        self
+    }
+
+    pub fn with_reason(mut self, reason: DeliveryFailure) -> Self {
+        self.reason = Some(reason);
+        self
+    }
+
+    pub fn matches(&self, letter: &DeadLetter) -> bool {
+        self.receiver_id.as_ref().is_none_or(|id| *id == letter.receiver_id)
+            && self
+                .message_type
+                .as_ref()
+                .is_none_or(|message_type| *message_type == letter.message.message_type)
+            && self.reason.is_none_or(|reason| reason == letter.reason)
+    }
+}
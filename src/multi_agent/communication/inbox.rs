@@ -0,0 +1,114 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use crate::multi_agent::communication::message::{Message, MessagePriority, MessageType};
+
+/// Number of delivery classes, from `Low` to `Urgent`
+const CLASSES: usize = 4;
+
+/// Per-agent inbox that hands out messages by priority
+///
+/// `Control` messages are delivered like `Urgent` ones. Messages of the same class keep their
+/// arrival order. To keep `Low` traffic from starving, a waiting `Low` message is delivered
+/// after `starvation_limit` messages of higher classes have overtaken it.
+#[derive(Debug)]
+pub struct PriorityInbox {
+    queues: [VecDeque<Arc<Message>>; CLASSES],
+    starvation_limit: usize,
+    /// Higher-class messages delivered while a `Low` message was waiting
+    overtaken_low: usize,
+}
+
+impl PriorityInbox {
+    pub fn new(starvation_limit: usize) -> Self {
+        Self {
+            queues: Default::default(),
+            starvation_limit,
+            overtaken_low: 0,
+        }
+    }
+
+    pub fn push(&mut self, message: Arc<Message>) {
+        self.queues[class(&message)].push_back(message);
+    }
+
+    /// Take the next message to deliver
+    pub fn pop(&mut self) -> Option<Arc<Message>> {
+        let low = MessagePriority::Low as usize;
+        if !self.queues[low].is_empty() && self.overtaken_low >= self.starvation_limit {
+            self.overtaken_low = 0;
+            return self.queues[low].pop_front();
+        }
+
+        let index = (0 .. CLASSES).rev().find(|i| !self.queues[*i].is_empty())?;
+        if index == low {
+            self.overtaken_low = 0;
+        } else if !self.queues[low].is_empty() {
+            self.overtaken_low += 1;
+        }
+        self.queues[index].pop_front()
+    }
+
+    /// Queued messages, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<Message>> {
+        self.queues.iter().flatten()
+    }
+
+    pub fn len(&self) -> usize {
+        self.queues.iter().map(VecDeque::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queues.iter().all(VecDeque::is_empty)
+    }
+}
+
+fn class(message: &Message) -> usize {
+    match message.message_type {
+        MessageType::Control(_) => MessagePriority::Urgent as usize,
+        _ => message.priority as usize,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multi_agent::communication::message::ControlCommand;
+
+    fn message(n: usize, priority: MessagePriority) -> Arc<Message> {
+        let message = Message::new(
+            "sender".to_string(),
+            Some("receiver".to_string()),
+            MessageType::StatusUpdate,
+            serde_json::json!({ "n": n }),
+        );
+        Arc::new(message.with_priority(priority))
+    }
+
+    fn drain(inbox: &mut PriorityInbox) -> Vec<u64> {
+        std::iter::from_fn(|| inbox.pop())
+            .map(|message| message.payload["n"].as_u64().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_priority_order_and_starvation() {
+        let mut inbox = PriorityInbox::new(2);
+        inbox.push(message(0, MessagePriority::Normal));
+        inbox.push(message(1, MessagePriority::Low));
+        inbox.push(message(2, MessagePriority::Urgent));
+        inbox.push(message(3, MessagePriority::Normal));
+        let control = Message::new(
+            "sender".to_string(),
+            Some("receiver".to_string()),
+            MessageType::Control(ControlCommand::Pause),
+            serde_json::json!({ "n": 4 }),
+        );
+        inbox.push(Arc::new(control));
+        assert_eq!(inbox.len(), 5);
+
+        // The low message is let through after two messages overtook it
+        assert_eq!(drain(&mut inbox), vec![2, 4, 1, 0, 3]);
+        assert!(inbox.is_empty());
+    }
+}
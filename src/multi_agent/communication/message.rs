@@ -11,6 +11,8 @@ pub enum MessageType {
     StatusUpdate,
     /// Result notification
     ResultNotification,
+    /// Request to cancel a task (`task_id` in the payload)
+    TaskCancellation,
     /// Resource request
     ResourceRequest,
     /// Resource response
@@ -53,8 +55,11 @@ pub struct Message {
     pub id: String,
     /// Sender ID
     pub sender_id: String,
-    /// Receiver ID (None means broadcast)
+    /// Receiver ID (None means broadcast, or delivery to the topic's subscribers)
     pub receiver_id: Option<String>,
+    /// Topic the message is published on, e.g. `task.42.completed`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
     /// Message type
     pub message_type: MessageType,
     /// Message priority
@@ -67,6 +72,9 @@ pub struct Message {
     pub correlation_id: Option<String>,
     /// Message expiration time
     pub expires_at: Option<DateTime<Utc>>,
+    /// Node the message was sent on, set when a bridge forwards it to another node
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin_node: Option<String>,
 }
 
 impl Message {
@@ -81,12 +89,14 @@ impl Message {
             id: Uuid::new_v4().to_string(),
             sender_id,
             receiver_id,
+            topic: None,
             message_type,
             priority: MessagePriority::default(),
             payload,
             timestamp: Utc::now(),
             correlation_id: None,
             expires_at: None,
+            origin_node: None,
         }
     }
 
@@ -99,6 +109,16 @@ impl Message {
         Self::new(sender_id, None, message_type, payload)
     }
 
+    /// Create a message for the subscribers of `topic`
+    pub fn publish(
+        sender_id: String,
+        topic: impl Into<String>,
+        message_type: MessageType,
+        payload: serde_json::Value,
+    ) -> Self {
+        Self::new(sender_id, None, message_type, payload).with_topic(topic)
+    }
+
     /// Create response message
     pub fn response(
         sender_id: String,
@@ -112,6 +132,12 @@ impl Message {
         msg
     }
 
+    /// Set the topic
+    pub fn with_topic(mut self, topic: impl Into<String>) -> Self {
+        self.topic = Some(topic.into());
+        self
+    }
+
     /// Set priority
     pub fn with_priority(mut self, priority: MessagePriority) -> Self {
         self.priority = priority;
@@ -133,9 +159,15 @@ impl Message {
         }
     }
 
-    /// Check if broadcast message
+    /// Check if the message was forwarded from another node; it is already counted and
+    /// logged there
+    pub fn is_forwarded(&self) -> bool {
+        self.origin_node.is_some()
+    }
+
+    /// Check if broadcast message (neither a receiver nor a topic)
     pub fn is_broadcast(&self) -> bool {
-        self.receiver_id.is_none()
+        self.receiver_id.is_none() && self.topic.is_none()
     }
 }
 
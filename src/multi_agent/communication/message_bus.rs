@@ -1,11 +1,23 @@
-use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::{broadcast, mpsc, RwLock};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use chrono::Utc;
+use tokio::sync::{broadcast, mpsc, oneshot, RwLock};
+use tokio::sync::mpsc::error::TrySendError;
 use tracing::{debug, error, info, warn};
 
+use crate::multi_agent::communication::dead_letter::{
+    DeadLetter, DeadLetterFilter, DeliveryFailure,
+};
+use crate::multi_agent::communication::inbox::PriorityInbox;
 use crate::multi_agent::communication::message::{Message, MessageFilter};
+use crate::multi_agent::communication::message_log::{
+    LoggedMessage, MessageLog, MessageLogConfig, ReplayFrom,
+};
+use crate::multi_agent::communication::topic::TopicPattern;
 use crate::error::{Result, Error};
 use crate::error::agent_error::AgentError;
+use crate::utils::retry::RetryPolicy;
 
 /// Message bus configuration
 #[derive(Debug, Clone)]
@@ -16,8 +28,16 @@ pub struct MessageBusConfig {
     pub p2p_capacity: usize,
     /// Message history size
     pub history_size: usize,
-    /// Whether to enable message persistence
+    /// Whether to append every sent message to the persistent message log
     pub enable_persistence: bool,
+    /// Location and retention of the message log
+    pub log: MessageLogConfig,
+    /// Retries of point-to-point and topic deliveries to a full channel
+    pub delivery_retry: RetryPolicy,
+    /// Number of dead letters kept; the oldest are dropped first
+    pub dead_letter_capacity: usize,
+    /// Deliver a waiting `Low` message after this many higher-priority messages overtook it
+    pub low_priority_starvation_limit: usize,
 }
 
 impl Default for MessageBusConfig {
@@ -27,6 +47,15 @@ impl Default for MessageBusConfig {
             p2p_capacity: 100,
             history_size: 1000,
             enable_persistence: false,
+            log: MessageLogConfig::default(),
+            delivery_retry: RetryPolicy {
+                max_retries: 3,
+                initial_backoff_ms: 10,
+                max_backoff_ms: 200,
+                multiplier: 2.0,
+            },
+            dead_letter_capacity: 1000,
+            low_priority_starvation_limit: 16,
         }
     }
 }
@@ -37,8 +66,18 @@ pub struct MessageBus {
     broadcast_tx: broadcast::Sender<Arc<Message>>,
     /// Point-to-point channel mapping (agent_id -> sender)
     p2p_channels: Arc<RwLock<HashMap<String, mpsc::Sender<Arc<Message>>>>>,
-    /// Message history
-    message_history: Arc<RwLock<Vec<Arc<Message>>>>,
+    /// Agents whose channel is a route to another node rather than a local receiver
+    remote_routes: Arc<RwLock<HashSet<String>>>,
+    /// Topic subscriptions (agent_id -> patterns)
+    subscriptions: Arc<RwLock<HashMap<String, Vec<TopicPattern>>>>,
+    /// Most recent messages, bounded by `history_size`
+    message_history: Arc<RwLock<VecDeque<Arc<Message>>>>,
+    /// Persistent log of all sent messages, when persistence is enabled
+    log: Option<Arc<MessageLog>>,
+    /// Messages that could not be delivered, oldest first
+    dead_letters: Arc<RwLock<VecDeque<DeadLetter>>>,
+    /// Pending requests awaiting a response (request message id -> reply sender)
+    pending_requests: Arc<PendingRequests>,
     /// Configuration
     config: MessageBusConfig,
     /// Statistics
@@ -51,7 +90,9 @@ pub struct MessageBusStats {
     pub total_messages: u64,
     pub broadcast_messages: u64,
     pub p2p_messages: u64,
+    pub topic_messages: u64,
     pub failed_deliveries: u64,
+    pub dead_letters: u64,
     pub expired_messages: u64,
 }
 
@@ -63,7 +104,16 @@ impl MessageBus {
         Self {
             broadcast_tx,
             p2p_channels: Arc::new(RwLock::new(HashMap::new())),
-            message_history: Arc::new(RwLock::new(Vec::with_capacity(config.history_size))),
+            remote_routes: Arc::new(RwLock::new(HashSet::new())),
+            subscriptions: Arc::new(RwLock::new(HashMap::new())),
+            message_history: Arc::new(RwLock::new(VecDeque::with_capacity(config.history_size))),
+            log: config.enable_persistence.then(|| {
+                let log = Arc::new(MessageLog::new(config.log.clone()));
+                log.start_retention_task();
+                log
+            }),
+            dead_letters: Arc::new(RwLock::new(VecDeque::new())),
+            pending_requests: Arc::new(Mutex::new(HashMap::new())),
             config,
             stats: Arc::new(RwLock::new(MessageBusStats::default())),
         }
@@ -73,8 +123,9 @@ impl MessageBus {
     pub async fn register_agent(&self, agent_id: String) -> Result<MessageReceiver> {
         let (tx, rx) = mpsc::channel(self.config.p2p_capacity);
         
-        // Register point-to-point channel
+        // Register point-to-point channel; a local agent replaces a route to another node
         self.p2p_channels.write().await.insert(agent_id.clone(), tx);
+        self.remote_routes.write().await.remove(&agent_id);
         
         // Subscribe to broadcast channel
         let broadcast_rx = self.broadcast_tx.subscribe();
@@ -85,12 +136,58 @@ impl MessageBus {
             agent_id,
             p2p_rx: rx,
             broadcast_rx,
+            inbox: PriorityInbox::new(self.config.low_priority_starvation_limit),
+            inbox_capacity: self.config.p2p_capacity.max(1),
+            stats: self.stats.clone(),
+            unreported_expired: 0,
         })
     }
 
+    /// Route the point-to-point messages of an agent into `tx` instead of a receiver
+    ///
+    /// Used to reach agents living on other nodes; see `multi_agent::transport::BusBridge`.
+    /// An existing route is replaced, but an agent registered on this bus is never shadowed.
+    pub async fn add_route(&self, agent_id: String, tx: mpsc::Sender<Arc<Message>>) -> Result<()> {
+        let mut channels = self.p2p_channels.write().await;
+        let mut remote_routes = self.remote_routes.write().await;
+        if channels.contains_key(&agent_id) && !remote_routes.contains(&agent_id) {
+            warn!("Not routing agent {} to a remote node: it is registered locally", agent_id);
+            return Err(Error::AgentError(AgentError::MessageDeliveryError(format!(
+                "Agent {agent_id} is registered locally"
+            ))));
+        }
+
+        debug!("Agent {} routed to a remote node", agent_id);
+        channels.insert(agent_id.clone(), tx);
+        remote_routes.insert(agent_id);
+        Ok(())
+    }
+
+    /// Remove a route added with `add_route` through `tx`
+    ///
+    /// Local agents and routes through other channels are left alone.
+    pub async fn remove_route(&self, agent_id: &str, tx: &mpsc::Sender<Arc<Message>>) {
+        let mut channels = self.p2p_channels.write().await;
+        let mut remote_routes = self.remote_routes.write().await;
+        if remote_routes.contains(agent_id)
+            && channels.get(agent_id).is_some_and(|current| current.same_channel(tx))
+        {
+            channels.remove(agent_id);
+            remote_routes.remove(agent_id);
+            debug!("Route of agent {} removed", agent_id);
+        }
+    }
+
+    /// Receive every broadcast message, including those sent by agents of this bus
+    pub fn subscribe_broadcasts(&self) -> broadcast::Receiver<Arc<Message>> {
+        self.broadcast_tx.subscribe()
+    }
+
     /// Unregister Agent
     pub async fn unregister_agent(&self, agent_id: &str) -> Result<()> {
         self.p2p_channels.write().await.remove(agent_id);
+        self.remote_routes.write().await.remove(agent_id);
+        self.subscriptions.write().await.remove(agent_id);
         info!("Agent {} unregistered from message bus", agent_id);
         Ok(())
     }
@@ -106,13 +203,38 @@ impl MessageBus {
 
         let message = Arc::new(message);
 
-        // Update statistics
-        self.stats.write().await.total_messages += 1;
+        // Update statistics; a forwarded message was counted on the node it was sent on
+        if !message.is_forwarded() {
+            self.stats.write().await.total_messages += 1;
+        }
 
         // Save to history
         self.save_to_history(message.clone()).await;
 
-        if message.is_broadcast() {
+        // Append to the persistent log before delivering, unless another node already did
+        if let Some(log) = &self.log
+            && !message.is_forwarded()
+            && let Err(e) = log.append(&message).await
+        {
+            error!("Failed to append message {} to the message log: {}", message.id, e);
+        }
+
+        // Resolve a pending request if this is its response
+        if let Some(correlation_id) = &message.correlation_id {
+            let pending = self.pending_requests.lock().unwrap().remove(correlation_id);
+            if let Some(reply_tx) = pending {
+                debug!("Resolving request {} with message {}", correlation_id, message.id);
+                let _ = reply_tx.send(Message::clone(&message));
+                return Ok(());
+            }
+        }
+
+        if let Some(topic) = message.topic.clone()
+            && message.receiver_id.is_none()
+        {
+            // Topic message
+            self.publish(&topic, message).await
+        } else if message.is_broadcast() {
             // Broadcast message
             self.broadcast(message).await
         } else {
@@ -121,10 +243,126 @@ impl MessageBus {
         }
     }
 
+    /// Send a request and wait for the message whose `correlation_id` matches its id
+    ///
+    /// The request stops waiting for a response when this returns or its future is dropped.
+    pub async fn request(&self, message: Message, timeout: Duration) -> Result<Message> {
+        let request_id = message.id.clone();
+        let (reply_tx, reply_rx) = oneshot::channel();
+
+        self.pending_requests
+            .lock()
+            .unwrap()
+            .insert(request_id.clone(), reply_tx);
+        let _pending = PendingRequest {
+            pending: &self.pending_requests,
+            request_id: &request_id,
+        };
+
+        self.send(message).await?;
+
+        match tokio::time::timeout(timeout, reply_rx).await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(_)) => Err(Error::AgentError(AgentError::MessageDeliveryError(format!(
+                "Request {request_id} was dropped before a response arrived"
+            )))),
+            Err(_) => {
+                warn!("Request {} timed out after {:?}", request_id, timeout);
+                Err(Error::AgentError(AgentError::MessageDeliveryError(format!(
+                    "Request {request_id} timed out"
+                ))))
+            }
+        }
+    }
+
+    /// Subscribe an agent to the topics matching `pattern`, e.g. `task.*.completed`
+    pub async fn subscribe(&self, agent_id: &str, pattern: &str) -> Result<()> {
+        let pattern = TopicPattern::parse(pattern)?;
+        if !self.p2p_channels.read().await.contains_key(agent_id) {
+            return Err(Error::AgentError(AgentError::MessageDeliveryError(format!(
+                "Agent {agent_id} not found"
+            ))));
+        }
+
+        let mut subscriptions = self.subscriptions.write().await;
+        let patterns = subscriptions.entry(agent_id.to_string()).or_default();
+        if !patterns.contains(&pattern) {
+            info!("Agent {} subscribed to {}", agent_id, pattern.as_str());
+            patterns.push(pattern);
+        }
+        Ok(())
+    }
+
+    /// Remove a subscription; returns whether the agent was subscribed to `pattern`
+    pub async fn unsubscribe(&self, agent_id: &str, pattern: &str) -> bool {
+        let mut subscriptions = self.subscriptions.write().await;
+        let Some(patterns) = subscriptions.get_mut(agent_id) else {
+            return false;
+        };
+        let before = patterns.len();
+        patterns.retain(|p| p.as_str() != pattern);
+        let removed = patterns.len() < before;
+        if patterns.is_empty() {
+            subscriptions.remove(agent_id);
+        }
+        if removed {
+            info!("Agent {} unsubscribed from {}", agent_id, pattern);
+        }
+        removed
+    }
+
+    /// Patterns an agent is subscribed to
+    pub async fn get_subscriptions(&self, agent_id: &str) -> Vec<String> {
+        self.subscriptions
+            .read()
+            .await
+            .get(agent_id)
+            .map(|patterns| patterns.iter().map(|p| p.as_str().to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Check whether an agent is subscribed to a topic
+    async fn is_subscribed(&self, agent_id: &str, topic: &str) -> bool {
+        self.subscriptions
+            .read()
+            .await
+            .get(agent_id)
+            .is_some_and(|patterns| patterns.iter().any(|p| p.matches(topic)))
+    }
+
+    /// Deliver a topic message to every subscriber except its sender
+    ///
+    /// Unlike a broadcast, a topic nobody subscribes to is not an error.
+    async fn publish(&self, topic: &str, message: Arc<Message>) -> Result<()> {
+        debug!("Publishing message {:?} on topic {}", message.id, topic);
+        if !message.is_forwarded() {
+            self.stats.write().await.topic_messages += 1;
+        }
+
+        let subscribers: Vec<String> = self
+            .subscriptions
+            .read()
+            .await
+            .iter()
+            .filter(|(agent_id, patterns)| {
+                **agent_id != message.sender_id && patterns.iter().any(|p| p.matches(topic))
+            })
+            .map(|(agent_id, _)| agent_id.clone())
+            .collect();
+
+        // A subscriber that cannot be reached gets a dead letter; the others are unaffected
+        for agent_id in subscribers {
+            let _ = self.deliver(&agent_id, message.clone()).await;
+        }
+        Ok(())
+    }
+
     /// Send broadcast message
     async fn broadcast(&self, message: Arc<Message>) -> Result<()> {
         debug!("Broadcasting message: {:?}", message.id);
-        self.stats.write().await.broadcast_messages += 1;
+        if !message.is_forwarded() {
+            self.stats.write().await.broadcast_messages += 1;
+        }
 
         match self.broadcast_tx.send(message) {
             Ok(_) => Ok(()),
@@ -146,28 +384,130 @@ impl MessageBus {
             .ok_or_else(|| Error::AgentError(AgentError::MessageDeliveryError("No receiver specified".into())))?;
 
         debug!("Sending P2P message to {}: {:?}", receiver_id, message.id);
-        self.stats.write().await.p2p_messages += 1;
+        if !message.is_forwarded() {
+            self.stats.write().await.p2p_messages += 1;
+        }
+
+        let receiver_id = receiver_id.clone();
+        self.deliver(&receiver_id, message).await
+    }
+
+    /// Deliver a message to an agent's channel
+    ///
+    /// A full channel is retried with backoff according to `delivery_retry`, since the agent
+    /// is only busy; once the retries are used up the message becomes a dead letter. A missing
+    /// agent or closed channel is dead-lettered straight away instead of stalling the sender,
+    /// and `redrive` delivers the message once the agent registered again.
+    async fn deliver(&self, agent_id: &str, message: Arc<Message>) -> Result<()> {
+        let policy = &self.config.delivery_retry;
+        let mut attempt = 0;
+
+        loop {
+            let failure = match self.try_deliver(agent_id, message.clone()).await {
+                Ok(()) => return Ok(()),
+                Err(failure) => failure,
+            };
 
-        let receiver_id_string = receiver_id.to_string();
+            if failure != DeliveryFailure::ChannelFull || attempt >= policy.max_retries {
+                warn!("Failed to deliver message {} to {}: {}", message.id, agent_id, failure);
+                self.stats.write().await.failed_deliveries += 1;
+                self.add_dead_letter(agent_id, &message, failure, attempt + 1).await;
+
+                let error = match failure {
+                    DeliveryFailure::AgentNotFound => format!("Agent {agent_id} not found"),
+                    _ => format!("Failed to send to {agent_id}: {failure}"),
+                };
+                return Err(Error::AgentError(AgentError::MessageDeliveryError(error)));
+            }
+
+            attempt += 1;
+            debug!(
+                "Delivery of message {} to {} failed ({}), retry {}",
+                message.id, agent_id, failure, attempt
+            );
+            tokio::time::sleep(policy.backoff(attempt)).await;
+        }
+    }
+
+    async fn try_deliver(
+        &self,
+        agent_id: &str,
+        message: Arc<Message>,
+    ) -> std::result::Result<(), DeliveryFailure> {
         let channels = self.p2p_channels.read().await;
-        if let Some(tx) = channels.get(receiver_id) {
-            match tx.send(message).await {
-                Ok(_) => Ok(()),
-                Err(_) => {
-                    error!("Failed to send message to {}", receiver_id_string);
-                    self.stats.write().await.failed_deliveries += 1;
-                    Err(Error::AgentError(AgentError::MessageDeliveryError(format!(
-                        "Failed to send to {receiver_id_string}"
-                    ))))
-                }
+        let tx = channels.get(agent_id).ok_or(DeliveryFailure::AgentNotFound)?;
+        tx.try_send(message).map_err(|e| match e {
+            TrySendError::Full(_) => DeliveryFailure::ChannelFull,
+            TrySendError::Closed(_) => DeliveryFailure::ChannelClosed,
+        })
+    }
+
+    async fn add_dead_letter(
+        &self,
+        agent_id: &str,
+        message: &Message,
+        reason: DeliveryFailure,
+        attempts: u32,
+    ) {
+        let mut dead_letters = self.dead_letters.write().await;
+        if dead_letters.len() >= self.config.dead_letter_capacity {
+            dead_letters.pop_front();
+        }
+        dead_letters.push_back(DeadLetter {
+            message: message.clone(),
+            receiver_id: agent_id.to_string(),
+            reason,
+            attempts,
+            dead_lettered_at: Utc::now(),
+        });
+        self.stats.write().await.dead_letters += 1;
+    }
+
+    /// Get dead letters, oldest first
+    pub async fn get_dead_letters(&self, filter: Option<DeadLetterFilter>) -> Vec<DeadLetter> {
+        let dead_letters = self.dead_letters.read().await;
+        dead_letters
+            .iter()
+            .filter(|letter| filter.as_ref().is_none_or(|filter| filter.matches(letter)))
+            .cloned()
+            .collect()
+    }
+
+    /// Deliver the dead letters of `agent_id` again, e.g. after it registered again
+    ///
+    /// Letters that are delivered are removed from the store; the others stay. Returns the
+    /// number of letters delivered.
+    pub async fn redrive(&self, agent_id: &str) -> usize {
+        let letters: VecDeque<DeadLetter> = {
+            let mut dead_letters = self.dead_letters.write().await;
+            let (letters, kept) = dead_letters
+                .drain(..)
+                .partition(|letter| letter.receiver_id == agent_id);
+            *dead_letters = kept;
+            letters
+        };
+
+        let mut delivered = 0;
+        let mut failed = Vec::new();
+        for letter in letters {
+            match self.try_deliver(agent_id, Arc::new(letter.message.clone())).await {
+                Ok(()) => delivered += 1,
+                Err(reason) => failed.push(DeadLetter {
+                    reason,
+                    attempts: letter.attempts + 1,
+                    ..letter
+                }),
             }
-        } else {
-            warn!("Agent {} not found", receiver_id_string);
-            self.stats.write().await.failed_deliveries += 1;
-            Err(Error::AgentError(AgentError::MessageDeliveryError(format!(
-                "Agent {receiver_id_string} not found"
-            ))))
         }
+
+        if !failed.is_empty() {
+            let mut dead_letters = self.dead_letters.write().await;
+            for letter in failed.into_iter().rev() {
+                dead_letters.push_front(letter);
+            }
+        }
+        info!("Re-drove {} dead letters to agent {}", delivered, agent_id);
+        delivered
     }
 
     /// Save message to history
@@ -176,10 +516,10 @@ impl MessageBus {
         
         // Limit history size
         if history.len() >= self.config.history_size {
-            history.remove(0);
+            history.pop_front();
         }
         
-        history.push(message);
+        history.push_back(message);
     }
 
     /// Get message history
@@ -193,8 +533,60 @@ impl MessageBus {
                 .cloned()
                 .collect()
         } else {
-            history.clone()
+            history.iter().cloned().collect()
+        }
+    }
+
+    /// Read the persistent message log starting at `from`
+    pub async fn read_log(&self, from: ReplayFrom) -> Result<Vec<LoggedMessage>> {
+        self.message_log()?.read(from).await
+    }
+
+    /// Deliver the logged messages `agent_id` received, starting at `from`, to it again
+    ///
+    /// Direct messages to the agent, broadcasts from other agents and messages on topics the
+    /// agent is subscribed to are re-delivered in their original order, e.g. to rebuild the
+    /// agent's state after a crash. Returns the number of messages delivered.
+    pub async fn replay(&self, agent_id: &str, from: ReplayFrom) -> Result<usize> {
+        let entries = self.message_log()?.read(from).await?;
+
+        let tx = self.p2p_channels.read().await.get(agent_id).cloned().ok_or_else(|| {
+            Error::AgentError(AgentError::MessageDeliveryError(format!(
+                "Agent {agent_id} not found"
+            )))
+        })?;
+
+        let mut delivered = 0;
+        for entry in entries {
+            let message = entry.message;
+            let received = match (&message.receiver_id, &message.topic) {
+                (Some(receiver_id), _) => receiver_id == agent_id,
+                (None, Some(topic)) => {
+                    message.sender_id != agent_id && self.is_subscribed(agent_id, topic).await
+                }
+                (None, None) => message.sender_id != agent_id,
+            };
+            if !received {
+                continue;
+            }
+            tx.send(Arc::new(message)).await.map_err(|_| {
+                Error::AgentError(AgentError::MessageDeliveryError(format!(
+                    "Failed to replay to {agent_id}"
+                )))
+            })?;
+            delivered += 1;
         }
+
+        info!("Replayed {} messages to agent {}", delivered, agent_id);
+        Ok(delivered)
+    }
+
+    fn message_log(&self) -> Result<&MessageLog> {
+        self.log.as_deref().ok_or_else(|| {
+            Error::AgentError(AgentError::InternalError(
+                "Message persistence is not enabled".into(),
+            ))
+        })
     }
 
     /// Get statistics
@@ -206,9 +598,39 @@ impl MessageBus {
     pub async fn get_registered_agents(&self) -> Vec<String> {
         self.p2p_channels.read().await.keys().cloned().collect()
     }
+
+    /// Get list of Agents registered on this bus, without routes to other nodes
+    pub async fn get_local_agents(&self) -> Vec<String> {
+        let channels = self.p2p_channels.read().await;
+        let remote_routes = self.remote_routes.read().await;
+        channels
+            .keys()
+            .filter(|agent_id| !remote_routes.contains(*agent_id))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Reply senders of pending requests, keyed by request message id
+type PendingRequests = Mutex<HashMap<String, oneshot::Sender<Message>>>;
+
+/// Removes a pending request once `MessageBus::request` stops waiting for it
+struct PendingRequest<'a> {
+    pending: &'a PendingRequests,
+    request_id: &'a str,
+}
+
+impl Drop for PendingRequest<'_> {
+    fn drop(&mut self) {
+        self.pending.lock().unwrap().remove(self.request_id);
+    }
 }
 
 /// Message receiver, each Agent holds one
+///
+/// Point-to-point, topic and broadcast messages are collected in a priority inbox, so
+/// `Urgent` and `Control` messages overtake queued `Normal` traffic. Expired messages are
+/// dropped when they are received.
 pub struct MessageReceiver {
     /// Agent ID
     pub agent_id: String,
@@ -216,48 +638,151 @@ pub struct MessageReceiver {
     p2p_rx: mpsc::Receiver<Arc<Message>>,
     /// Broadcast message receiver
     broadcast_rx: broadcast::Receiver<Arc<Message>>,
+    /// Messages taken off the channels but not yet delivered
+    inbox: PriorityInbox,
+    /// Stop draining the channels into the inbox at this size, so senders still see backpressure
+    inbox_capacity: usize,
+    /// Bus statistics, for counting expired messages
+    stats: Arc<RwLock<MessageBusStats>>,
+    /// Expired messages not yet added to the statistics
+    unreported_expired: u64,
 }
 
 impl MessageReceiver {
-    /// Receive next message (prioritize point-to-point messages)
+    /// Receive the next message by priority
+    ///
+    /// Cancel safe: nothing is awaited once a message has left the inbox, so dropping the
+    /// future (e.g. in `select!`) never loses a message.
     pub async fn recv(&mut self) -> Option<Arc<Message>> {
-        tokio::select! {
-            // Prioritize point-to-point messages
-            Some(msg) = self.p2p_rx.recv() => {
-                debug!("Agent {} received P2P message: {:?}", self.agent_id, msg.id);
-                Some(msg)
+        loop {
+            self.report_expired().await;
+            self.fill_inbox();
+            let msg = self.next_from_inbox();
+            self.try_report_expired();
+            if msg.is_some() {
+                return msg;
+            }
+
+            // Nothing is queued: wait for the next message from either channel
+            let msg = self.next_incoming().await?;
+            self.accept(msg);
+        }
+    }
+
+    /// Move the next incoming message into the inbox and return it without delivering it
+    ///
+    /// Lets a busy owner react to a message early, e.g. to a cancellation, while `recv` still
+    /// delivers it in its turn. Waits while the inbox is full, so senders keep seeing
+    /// backpressure, and returns `None` once the channels are closed. Cancel safe.
+    pub async fn peek_incoming(&mut self) -> Option<Arc<Message>> {
+        loop {
+            if self.inbox.len() >= self.inbox_capacity {
+                std::future::pending::<()>().await;
             }
-            // Then receive broadcast messages
-            Ok(msg) = self.broadcast_rx.recv() => {
-                // Filter out broadcast messages sent by self
-                if msg.sender_id != self.agent_id {
-                    debug!("Agent {} received broadcast message: {:?}", self.agent_id, msg.id);
-                    Some(msg)
-                } else {
-                    // Recursively call to continue receiving next message
-                    Box::pin(self.recv()).await
+            let msg = self.next_incoming().await?;
+            if self.accept(msg.clone()) {
+                return Some(msg);
+            }
+        }
+    }
+
+    /// Messages waiting in the inbox, in no particular order
+    pub fn queued(&self) -> impl Iterator<Item = &Arc<Message>> {
+        self.inbox.iter()
+    }
+
+    /// Wait for the next message from either channel
+    async fn next_incoming(&mut self) -> Option<Arc<Message>> {
+        loop {
+            tokio::select! {
+                biased;
+                Some(msg) = self.p2p_rx.recv() => return Some(msg),
+                result = self.broadcast_rx.recv(), if !self.broadcast_rx.is_closed() => {
+                    match result {
+                        Ok(msg) => return Some(msg),
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            warn!("Agent {} missed {} broadcast messages", self.agent_id, skipped);
+                        }
+                        Err(broadcast::error::RecvError::Closed) => {}
+                    }
                 }
+                else => return None,
             }
-            else => None
         }
     }
 
     /// Try to receive message (non-blocking)
     pub fn try_recv(&mut self) -> Option<Arc<Message>> {
-        // First try to receive point-to-point message
-        if let Ok(msg) = self.p2p_rx.try_recv() {
-            return Some(msg);
-        }
+        self.fill_inbox();
+        let msg = self.next_from_inbox();
+        self.try_report_expired();
+        msg
+    }
 
-        // Then try to receive broadcast message
-        if let Ok(msg) = self.broadcast_rx.try_recv()
-            && msg.sender_id != self.agent_id {
-                return Some(msg);
+    /// Move messages that are ready on the channels into the inbox
+    fn fill_inbox(&mut self) {
+        while self.inbox.len() < self.inbox_capacity {
+            match self.p2p_rx.try_recv() {
+                Ok(msg) => {
+                    self.accept(msg);
+                }
+                Err(_) => break,
             }
+        }
+        while self.inbox.len() < self.inbox_capacity {
+            match self.broadcast_rx.try_recv() {
+                Ok(msg) => {
+                    self.accept(msg);
+                }
+                Err(broadcast::error::TryRecvError::Lagged(skipped)) => {
+                    warn!("Agent {} missed {} broadcast messages", self.agent_id, skipped);
+                }
+                Err(_) => break,
+            }
+        }
+    }
 
+    /// Queue a message in the inbox; returns whether it was kept
+    fn accept(&mut self, msg: Arc<Message>) -> bool {
+        // Filter out broadcast messages sent by self
+        if msg.is_broadcast() && msg.sender_id == self.agent_id {
+            return false;
+        }
+        self.inbox.push(msg);
+        true
+    }
+
+    fn next_from_inbox(&mut self) -> Option<Arc<Message>> {
+        while let Some(msg) = self.inbox.pop() {
+            if msg.is_expired() {
+                debug!("Agent {} dropped expired message {}", self.agent_id, msg.id);
+                self.unreported_expired += 1;
+                continue;
+            }
+            debug!("Agent {} received message: {:?}", self.agent_id, msg.id);
+            return Some(msg);
+        }
         None
     }
 
+    /// Count expired messages without waiting; if the statistics are busy they are counted on
+    /// a later call
+    fn try_report_expired(&mut self) {
+        if self.unreported_expired > 0
+            && let Ok(mut stats) = self.stats.try_write()
+        {
+            stats.expired_messages += self.unreported_expired;
+            self.unreported_expired = 0;
+        }
+    }
+
+    async fn report_expired(&mut self) {
+        if self.unreported_expired > 0 {
+            self.stats.write().await.expired_messages += self.unreported_expired;
+            self.unreported_expired = 0;
+        }
+    }
+
     /// Receive message (with filter)
     pub async fn recv_filtered(&mut self, filter: MessageFilter) -> Option<Arc<Message>> {
         loop {
@@ -279,7 +804,9 @@ impl Clone for MessageBusStats {
             total_messages: self.total_messages,
             broadcast_messages: self.broadcast_messages,
             p2p_messages: self.p2p_messages,
+            topic_messages: self.topic_messages,
             failed_deliveries: self.failed_deliveries,
+            dead_letters: self.dead_letters,
             expired_messages: self.expired_messages,
         }
     }
@@ -288,7 +815,7 @@ impl Clone for MessageBusStats {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::multi_agent::communication::message::MessageType;
+    use crate::multi_agent::communication::message::{MessagePriority, MessageType};
 
     #[tokio::test]
     async fn test_message_bus_registration() {
@@ -344,4 +871,292 @@ mod tests {
         assert_eq!(received1.sender_id, "master");
         assert!(received1.is_broadcast());
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_request_response() {
+        let bus = Arc::new(MessageBus::new(MessageBusConfig::default()));
+
+        let mut responder = bus.register_agent("agent1".to_string()).await.unwrap();
+        let responder_bus = bus.clone();
+        tokio::spawn(async move {
+            let request = responder.recv().await.unwrap();
+            let reply = Message::response(
+                "agent1".to_string(),
+                request.sender_id.clone(),
+                MessageType::ResultNotification,
+                serde_json::json!({"answer": 42}),
+                request.id.clone(),
+            );
+            responder_bus.send(reply).await.unwrap();
+        });
+
+        let request = Message::new(
+            "client".to_string(),
+            Some("agent1".to_string()),
+            MessageType::ResourceRequest,
+            serde_json::json!({}),
+        );
+        let request_id = request.id.clone();
+
+        let response = bus.request(request, Duration::from_secs(1)).await.unwrap();
+        assert_eq!(response.correlation_id, Some(request_id));
+        assert_eq!(response.payload["answer"], 42);
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout() {
+        let bus = MessageBus::new(MessageBusConfig::default());
+        let _receiver = bus.register_agent("agent1".to_string()).await.unwrap();
+
+        let request = Message::new(
+            "client".to_string(),
+            Some("agent1".to_string()),
+            MessageType::ResourceRequest,
+            serde_json::json!({}),
+        );
+
+        let result = bus.request(request, Duration::from_millis(50)).await;
+        assert!(matches!(
+            result,
+            Err(Error::AgentError(AgentError::MessageDeliveryError(_)))
+        ));
+        assert!(bus.pending_requests.lock().unwrap().is_empty());
+
+        // A caller that gives up early does not leave its request behind
+        let request = Message::new(
+            "client".to_string(),
+            Some("agent1".to_string()),
+            MessageType::ResourceRequest,
+            serde_json::json!({}),
+        );
+        let abandoned = tokio::time::timeout(
+            Duration::from_millis(20),
+            bus.request(request, Duration::from_secs(10)),
+        )
+        .await;
+        assert!(abandoned.is_err());
+        assert!(bus.pending_requests.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_replay_after_restart() {
+        let dir = std::env::temp_dir().join(format!("message-bus-log-{}", uuid::Uuid::new_v4()));
+        let config = MessageBusConfig {
+            enable_persistence: true,
+            log: MessageLogConfig::new(&dir),
+            ..Default::default()
+        };
+
+        {
+            let bus = MessageBus::new(config.clone());
+            let _agent1 = bus.register_agent("agent1".to_string()).await.unwrap();
+            let _agent2 = bus.register_agent("agent2".to_string()).await.unwrap();
+            for (receiver, n) in [(Some("agent1"), 1), (Some("agent2"), 2), (None, 3)] {
+                let msg = Message::new(
+                    "master".to_string(),
+                    receiver.map(str::to_string),
+                    MessageType::TaskAssignment,
+                    serde_json::json!({ "n": n }),
+                );
+                bus.send(msg).await.unwrap();
+            }
+        }
+
+        let bus = MessageBus::new(config);
+        assert_eq!(bus.read_log(ReplayFrom::Offset(1)).await.unwrap().len(), 2);
+
+        let mut agent1 = bus.register_agent("agent1".to_string()).await.unwrap();
+        assert_eq!(bus.replay("agent1", ReplayFrom::Offset(0)).await.unwrap(), 2);
+        assert_eq!(agent1.recv().await.unwrap().payload["n"], 1);
+        assert_eq!(agent1.recv().await.unwrap().payload["n"], 3);
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
+    #[tokio::test]
+    async fn test_topic_subscriptions() {
+        let bus = MessageBus::new(MessageBusConfig::default());
+        let mut watcher = bus.register_agent("watcher".to_string()).await.unwrap();
+        let mut other = bus.register_agent("other".to_string()).await.unwrap();
+
+        bus.subscribe("watcher", "task.*.completed").await.unwrap();
+        assert!(bus.subscribe("watcher", "task..completed").await.is_err());
+        assert!(bus.subscribe("nobody", "task.#").await.is_err());
+
+        for topic in ["task.1.failed", "task.1.completed"] {
+            let msg = Message::publish(
+                "master".to_string(),
+                topic,
+                MessageType::StatusUpdate,
+                serde_json::json!({}),
+            );
+            bus.send(msg).await.unwrap();
+        }
+        let received = watcher.recv().await.unwrap();
+        assert_eq!(received.topic.as_deref(), Some("task.1.completed"));
+        assert!(watcher.try_recv().is_none());
+        assert!(other.try_recv().is_none());
+
+        // Broadcasts still reach every agent
+        let msg =
+            Message::broadcast("master".to_string(), MessageType::Heartbeat, serde_json::json!({}));
+        bus.send(msg).await.unwrap();
+        assert_eq!(watcher.recv().await.unwrap().message_type, MessageType::Heartbeat);
+        assert_eq!(other.recv().await.unwrap().message_type, MessageType::Heartbeat);
+
+        assert!(bus.unsubscribe("watcher", "task.*.completed").await);
+        assert!(bus.get_subscriptions("watcher").await.is_empty());
+        let msg = Message::publish(
+            "master".to_string(),
+            "task.2.completed",
+            MessageType::StatusUpdate,
+            serde_json::json!({}),
+        );
+        bus.send(msg).await.unwrap();
+        assert!(watcher.try_recv().is_none());
+        assert_eq!(bus.get_stats().await.topic_messages, 3);
+    }
+
+    #[tokio::test]
+    async fn test_dead_letters_and_redrive() {
+        let config = MessageBusConfig {
+            p2p_capacity: 1,
+            delivery_retry: RetryPolicy {
+                max_retries: 2,
+                initial_backoff_ms: 1,
+                max_backoff_ms: 1,
+                multiplier: 1.0,
+            },
+            ..Default::default()
+        };
+        let bus = MessageBus::new(config);
+        let mut busy = bus.register_agent("busy".to_string()).await.unwrap();
+
+        let task = |receiver: &str| {
+            Message::new(
+                "master".to_string(),
+                Some(receiver.to_string()),
+                MessageType::TaskAssignment,
+                serde_json::json!({}),
+            )
+        };
+        assert!(bus.send(task("restarting")).await.is_err());
+        bus.send(task("busy")).await.unwrap();
+        assert!(bus.send(task("busy")).await.is_err());
+
+        let letters = bus.get_dead_letters(None).await;
+        assert_eq!(letters.len(), 2);
+        assert_eq!(letters[0].attempts, 1);
+        assert_eq!(letters[1].attempts, 3);
+        let filter = DeadLetterFilter::new()
+            .with_type(MessageType::TaskAssignment)
+            .with_reason(DeliveryFailure::ChannelFull);
+        let letters = bus.get_dead_letters(Some(filter)).await;
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].receiver_id, "busy");
+        assert_eq!(bus.get_stats().await.dead_letters, 2);
+
+        // Nothing is re-driven while the agent is still away
+        assert_eq!(bus.redrive("restarting").await, 0);
+        let mut restarted = bus.register_agent("restarting".to_string()).await.unwrap();
+        assert_eq!(bus.redrive("restarting").await, 1);
+        assert_eq!(restarted.recv().await.unwrap().message_type, MessageType::TaskAssignment);
+
+        busy.recv().await.unwrap();
+        assert_eq!(bus.redrive("busy").await, 1);
+        assert!(bus.get_dead_letters(None).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_priority_delivery_and_expiry() {
+        let bus = MessageBus::new(MessageBusConfig::default());
+        let mut agent = bus.register_agent("agent".to_string()).await.unwrap();
+
+        let message = |n: u64, priority: MessagePriority| {
+            Message::new(
+                "master".to_string(),
+                Some("agent".to_string()),
+                MessageType::StatusUpdate,
+                serde_json::json!({ "n": n }),
+            )
+            .with_priority(priority)
+        };
+        bus.send(message(0, MessagePriority::Normal)).await.unwrap();
+        let expiring = message(1, MessagePriority::High)
+            .with_expiry(Utc::now() + chrono::Duration::milliseconds(20));
+        bus.send(expiring).await.unwrap();
+        bus.send(message(2, MessagePriority::Urgent)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(40)).await;
+
+        assert_eq!(agent.recv().await.unwrap().payload["n"], 2);
+        assert_eq!(agent.recv().await.unwrap().payload["n"], 0);
+        assert!(agent.try_recv().is_none());
+        assert_eq!(bus.get_stats().await.expired_messages, 1);
+    }
+
+    #[tokio::test]
+    async fn test_peek_keeps_messages_and_backpressure() {
+        let config = MessageBusConfig {
+            p2p_capacity: 2,
+            ..Default::default()
+        };
+        let bus = MessageBus::new(config);
+        let mut agent = bus.register_agent("agent".to_string()).await.unwrap();
+
+        let message = |n: u64| {
+            Message::new(
+                "master".to_string(),
+                Some("agent".to_string()),
+                MessageType::StatusUpdate,
+                serde_json::json!({ "n": n }),
+            )
+        };
+        for n in 0 .. 2 {
+            bus.send(message(n)).await.unwrap();
+        }
+        assert_eq!(agent.peek_incoming().await.unwrap().payload["n"], 0);
+        assert_eq!(agent.peek_incoming().await.unwrap().payload["n"], 1);
+        assert_eq!(agent.queued().count(), 2);
+
+        // The inbox is full, so further messages stay in the channel until it is full too
+        for n in 2 .. 4 {
+            bus.send(message(n)).await.unwrap();
+        }
+        let peek = tokio::time::timeout(Duration::from_millis(20), agent.peek_incoming()).await;
+        assert!(peek.is_err());
+        assert!(bus.send(message(4)).await.is_err());
+
+        for n in 0 .. 4 {
+            assert_eq!(agent.recv().await.unwrap().payload["n"], n);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_routes_do_not_shadow_local_agents() {
+        let bus = MessageBus::new(MessageBusConfig::default());
+        let mut local = bus.register_agent("worker".to_string()).await.unwrap();
+        let (tx, mut remote) = mpsc::channel(10);
+
+        assert!(bus.add_route("worker".to_string(), tx.clone()).await.is_err());
+        bus.add_route("remote".to_string(), tx.clone()).await.unwrap();
+        assert_eq!(bus.get_local_agents().await, vec!["worker".to_string()]);
+
+        // Removing through the bridge's channel leaves the local agent registered
+        bus.remove_route("worker", &tx).await;
+        let task = |receiver: &str| {
+            Message::new(
+                "master".to_string(),
+                Some(receiver.to_string()),
+                MessageType::TaskAssignment,
+                serde_json::json!({}),
+            )
+        };
+        bus.send(task("worker")).await.unwrap();
+        assert!(local.try_recv().is_some());
+        bus.send(task("remote")).await.unwrap();
+        assert!(remote.try_recv().is_ok());
+
+        bus.remove_route("remote", &tx).await;
+        assert_eq!(bus.get_registered_agents().await, vec!["worker".to_string()]);
+    }
+}
@@ -0,0 +1,472 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+use crate::error::Result;
+use crate::multi_agent::communication::message::Message;
+
+/// Extension of segment files
+const SEGMENT_EXTENSION: &str = "log";
+
+/// Message log configuration
+#[derive(Debug, Clone)]
+pub struct MessageLogConfig {
+    /// Directory holding the segment files
+    pub dir: PathBuf,
+    /// Start a new segment once the current one reaches this size in bytes
+    pub segment_bytes: u64,
+    /// Delete the oldest segments while the log is larger than this
+    pub retention_bytes: Option<u64>,
+    /// Delete segments that were last written longer ago than this
+    pub retention_age: Option<Duration>,
+    /// How often retention is applied besides on rotation and reads, so a quiet log still
+    /// drops old segments
+    pub retention_check_interval: Duration,
+}
+
+impl Default for MessageLogConfig {
+    fn default() -> Self {
+        Self::new("message_log")
+    }
+}
+
+impl MessageLogConfig {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            segment_bytes: 16 * 1024 * 1024,
+            retention_bytes: Some(1024 * 1024 * 1024),
+            retention_age: Some(Duration::from_secs(7 * 24 * 3600)),
+            retention_check_interval: Duration::from_secs(3600),
+        }
+    }
+
+    pub fn with_segment_bytes(mut self, bytes: u64) -> Self {
+        self.segment_bytes = bytes;
+        self
+    }
+
+    pub fn with_retention_bytes(mut self, bytes: Option<u64>) -> Self {
+        self.retention_bytes = bytes;
+        self
+    }
+
+    pub fn with_retention_age(mut self, age: Option<Duration>) -> Self {
+        self.retention_age = age;
+        self
+    }
+
+    pub fn with_retention_check_interval(mut self, interval: Duration) -> Self {
+        self.retention_check_interval = interval;
+        self
+    }
+}
+
+/// Where reading or replaying the log starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayFrom {
+    /// The message with this offset and everything after it
+    Offset(u64),
+    /// Messages sent at or after this time
+    Timestamp(DateTime<Utc>),
+}
+
+impl ReplayFrom {
+    fn includes(&self, entry: &LoggedMessage) -> bool {
+        match self {
+            Self::Offset(offset) => entry.offset >= *offset,
+            Self::Timestamp(timestamp) => entry.message.timestamp >= *timestamp,
+        }
+    }
+}
+
+/// A message as stored in the log (one JSON object per line)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggedMessage {
+    /// Position of the message in the log, starting at 0
+    pub offset: u64,
+    pub message: Message,
+}
+
+#[derive(Debug, Clone)]
+struct Segment {
+    /// Offset of the first message in the segment, also its file name
+    base_offset: u64,
+    path: PathBuf,
+    size: u64,
+}
+
+struct LogWriter {
+    /// Segments from oldest to newest; the last one is written to
+    segments: Vec<Segment>,
+    file: File,
+    next_offset: u64,
+}
+
+/// Append-only message log split into segment files
+///
+/// Segments are opened on first use, so creating the log does no I/O. Retention is applied
+/// when the log is opened, whenever a segment is full, on every read and periodically by
+/// `start_retention_task`; the segment being written is never deleted.
+pub struct MessageLog {
+    config: MessageLogConfig,
+    writer: Mutex<Option<LogWriter>>,
+}
+
+impl MessageLog {
+    pub fn new(config: MessageLogConfig) -> Self {
+        Self {
+            config,
+            writer: Mutex::new(None),
+        }
+    }
+
+    /// Append a message and return its offset
+    pub async fn append(&self, message: &Message) -> Result<u64> {
+        let mut guard = self.writer.lock().await;
+        let writer = self.open(&mut guard).await?;
+
+        let offset = writer.next_offset;
+        let entry = LoggedMessage {
+            offset,
+            message: message.clone(),
+        };
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+
+        writer.file.write_all(&line).await?;
+        writer.file.flush().await?;
+        writer.next_offset += 1;
+
+        let active = writer.segments.last_mut().expect("message log has an active segment");
+        active.size += line.len() as u64;
+        if active.size >= self.config.segment_bytes {
+            writer.rotate(&self.config).await?;
+        }
+        Ok(offset)
+    }
+
+    /// Read the retained messages starting at `from`, oldest first
+    ///
+    /// Only the list of segments is taken under the writer lock; the files are read after
+    /// releasing it, so appends are not held up by a replay.
+    pub async fn read(&self, from: ReplayFrom) -> Result<Vec<LoggedMessage>> {
+        let segments = {
+            let mut guard = self.writer.lock().await;
+            let writer = self.open(&mut guard).await?;
+            writer.apply_retention(&self.config).await?;
+            writer.segments.clone()
+        };
+
+        let mut entries = Vec::new();
+        for (index, segment) in segments.iter().enumerate() {
+            // Segments that end before the requested offset can be skipped without reading
+            if let ReplayFrom::Offset(offset) = from
+                && segments
+                    .get(index + 1)
+                    .is_some_and(|next| next.base_offset <= offset)
+            {
+                continue;
+            }
+            let mut content = match fs::read(&segment.path).await {
+                Ok(content) => content,
+                // Removed by retention since the snapshot
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            // Ignore what was appended after the snapshot, possibly a partly written line
+            content.truncate(segment.size as usize);
+            let content = String::from_utf8_lossy(&content);
+            entries.extend(parse(&segment.path, &content).filter(|entry| from.includes(entry)));
+        }
+        Ok(entries)
+    }
+
+    /// Apply retention now, if the log has been opened
+    pub async fn apply_retention(&self) -> Result<()> {
+        match self.writer.lock().await.as_mut() {
+            Some(writer) => writer.apply_retention(&self.config).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Apply retention every `retention_check_interval` until the log is dropped
+    pub fn start_retention_task(self: &Arc<Self>) -> JoinHandle<()> {
+        let log: Weak<Self> = Arc::downgrade(self);
+        let period = self.config.retention_check_interval;
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+                let Some(log) = log.upgrade() else {
+                    break;
+                };
+                if let Err(e) = log.apply_retention().await {
+                    error!("Failed to apply message log retention: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Offset the next appended message will get
+    pub async fn next_offset(&self) -> Result<u64> {
+        let mut guard = self.writer.lock().await;
+        Ok(self.open(&mut guard).await?.next_offset)
+    }
+
+    async fn open<'a>(&self, slot: &'a mut Option<LogWriter>) -> Result<&'a mut LogWriter> {
+        if slot.is_none() {
+            *slot = Some(LogWriter::open(&self.config).await?);
+        }
+        Ok(slot.as_mut().expect("message log was just opened"))
+    }
+}
+
+impl LogWriter {
+    async fn open(config: &MessageLogConfig) -> Result<Self> {
+        fs::create_dir_all(&config.dir).await?;
+
+        let mut segments = Vec::new();
+        let mut dir = fs::read_dir(&config.dir).await?;
+        while let Some(entry) = dir.next_entry().await? {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(SEGMENT_EXTENSION) {
+                continue;
+            }
+            let Some(base_offset) = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(|s| s.parse().ok())
+            else {
+                continue;
+            };
+            let size = entry.metadata().await?.len();
+            segments.push(Segment {
+                base_offset,
+                path,
+                size,
+            });
+        }
+        segments.sort_by_key(|segment| segment.base_offset);
+
+        let (file, next_offset) = match segments.last_mut() {
+            Some(active) => {
+                let content = fs::read_to_string(&active.path).await?;
+                let next_offset = parse(&active.path, &content)
+                    .last()
+                    .map_or(active.base_offset, |entry| entry.offset + 1);
+
+                let mut file = OpenOptions::new().append(true).open(&active.path).await?;
+                // Terminate a line cut short by a crash so the next record starts on its own line
+                if !content.is_empty() && !content.ends_with('\n') {
+                    file.write_all(b"\n").await?;
+                    active.size += 1;
+                }
+                (file, next_offset)
+            }
+            None => {
+                let segment = Segment::create(&config.dir, 0);
+                let file = File::create(&segment.path).await?;
+                segments.push(segment);
+                (file, 0)
+            }
+        };
+
+        info!(
+            "Message log {} opened with {} segments, next offset {}",
+            config.dir.display(),
+            segments.len(),
+            next_offset
+        );
+
+        let mut writer = Self {
+            segments,
+            file,
+            next_offset,
+        };
+        writer.apply_retention(config).await?;
+        Ok(writer)
+    }
+
+    /// Close the full segment, start a new one and drop segments past retention
+    async fn rotate(&mut self, config: &MessageLogConfig) -> Result<()> {
+        self.file.sync_data().await?;
+
+        let segment = Segment::create(&config.dir, self.next_offset);
+        self.file = File::create(&segment.path).await?;
+        self.segments.push(segment);
+
+        self.apply_retention(config).await
+    }
+
+    async fn apply_retention(&mut self, config: &MessageLogConfig) -> Result<()> {
+        while self.segments.len() > 1 {
+            let oldest = &self.segments[0];
+            let total: u64 = self.segments.iter().map(|segment| segment.size).sum();
+            let too_large = config.retention_bytes.is_some_and(|max| total > max);
+            let too_old = match config.retention_age {
+                Some(age) => {
+                    let modified = fs::metadata(&oldest.path).await?.modified()?;
+                    SystemTime::now()
+                        .duration_since(modified)
+                        .is_ok_and(|elapsed| elapsed > age)
+                }
+                None => false,
+            };
+            if !too_large && !too_old {
+                break;
+            }
+
+            fs::remove_file(&oldest.path).await?;
+            info!("Message log segment {} removed by retention", oldest.path.display());
+            self.segments.remove(0);
+        }
+        Ok(())
+    }
+}
+
+impl Segment {
+    fn create(dir: &Path, base_offset: u64) -> Self {
+        Self {
+            base_offset,
+            path: dir.join(format!("{base_offset:020}.{SEGMENT_EXTENSION}")),
+            size: 0,
+        }
+    }
+}
+
+/// Parse the records of a segment, skipping corrupt lines
+fn parse<'a>(path: &'a Path, content: &'a str) -> impl Iterator<Item = LoggedMessage> + 'a {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(move |(number, line)| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                warn!(
+                    "Skipping corrupt line {} of message log segment {}: {}",
+                    number + 1,
+                    path.display(),
+                    e
+                );
+                None
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multi_agent::communication::message::MessageType;
+
+    fn message(n: usize) -> Message {
+        Message::new(
+            "sender".to_string(),
+            Some("receiver".to_string()),
+            MessageType::StatusUpdate,
+            serde_json::json!({ "n": n }),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_segments_rotation_and_retention() {
+        let dir = std::env::temp_dir().join(format!("message-log-{}", uuid::Uuid::new_v4()));
+        let line_size = serde_json::to_vec(&LoggedMessage {
+            offset: 0,
+            message: message(0),
+        })
+        .unwrap()
+        .len() as u64;
+        // Two messages per segment; retention keeps three full segments besides the active one
+        let config = MessageLogConfig::new(&dir)
+            .with_segment_bytes(line_size * 3 / 2)
+            .with_retention_bytes(Some(line_size * 7));
+
+        let log = MessageLog::new(config.clone());
+        for n in 0 .. 10 {
+            assert_eq!(log.append(&message(n)).await.unwrap(), n as u64);
+        }
+        let offsets: Vec<u64> = log
+            .read(ReplayFrom::Offset(0))
+            .await
+            .unwrap()
+            .iter()
+            .map(|entry| entry.offset)
+            .collect();
+        assert_eq!(offsets, vec![4, 5, 6, 7, 8, 9]);
+        drop(log);
+
+        // A record cut short by a crash is skipped and numbering continues after restart
+        let active = dir.join(format!("{:020}.log", 10));
+        fs::write(&active, b"{\"offset\":10,\"mess").await.unwrap();
+        let log = MessageLog::new(config);
+        assert_eq!(log.next_offset().await.unwrap(), 10);
+        log.append(&message(10)).await.unwrap();
+
+        let entries = log.read(ReplayFrom::Offset(8)).await.unwrap();
+        let payloads: Vec<u64> = entries
+            .iter()
+            .map(|entry| entry.message.payload["n"].as_u64().unwrap())
+            .collect();
+        assert_eq!(payloads, vec![8, 9, 10]);
+
+        let since = entries[1].message.timestamp;
+        assert_eq!(log.read(ReplayFrom::Timestamp(since)).await.unwrap().len(), 2);
+
+        let _ = fs::remove_dir_all(&dir).await;
+    }
+
+    #[tokio::test]
+    async fn test_age_retention_on_read_and_timer() {
+        let dir = std::env::temp_dir().join(format!("message-log-{}", uuid::Uuid::new_v4()));
+        let config = MessageLogConfig::new(&dir)
+            .with_segment_bytes(1)
+            .with_retention_bytes(None)
+            .with_retention_age(Some(Duration::from_millis(100)))
+            .with_retention_check_interval(Duration::from_millis(20));
+        let segment_count = |dir: PathBuf| async move {
+            let mut entries = fs::read_dir(dir).await.unwrap();
+            let mut count = 0;
+            while entries.next_entry().await.unwrap().is_some() {
+                count += 1;
+            }
+            count
+        };
+
+        // Every message fills a segment; a read drops the expired ones before replaying
+        let log = MessageLog::new(config.clone());
+        for n in 0 .. 3 {
+            log.append(&message(n)).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(log.read(ReplayFrom::Offset(0)).await.unwrap().is_empty());
+        assert_eq!(segment_count(dir.clone()).await, 1);
+        drop(log);
+
+        // The timer drops them on a log nobody reads
+        let log = Arc::new(MessageLog::new(config));
+        let task = log.start_retention_task();
+        for n in 3 .. 6 {
+            log.append(&message(n)).await.unwrap();
+        }
+        assert!(segment_count(dir.clone()).await > 1);
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(segment_count(dir.clone()).await, 1);
+
+        drop(log);
+        tokio::time::timeout(Duration::from_secs(1), task).await.unwrap().unwrap();
+        let _ = fs::remove_dir_all(&dir).await;
+    }
+}
@@ -1,5 +1,13 @@
+pub mod dead_letter;
+pub mod inbox;
 pub mod message;
 pub mod message_bus;
+pub mod message_log;
+pub mod topic;
 
+pub use dead_letter::{DeadLetter, DeadLetterFilter, DeliveryFailure};
+pub use inbox::PriorityInbox;
 pub use message::{Message, MessageFilter, MessagePriority, MessageType};
-pub use message_bus::{MessageBus, MessageBusConfig, MessageReceiver};
\ No newline at end of file
+pub use message_bus::{MessageBus, MessageBusConfig, MessageReceiver};
+pub use message_log::{LoggedMessage, MessageLog, MessageLogConfig, ReplayFrom};
+pub use topic::TopicPattern;
\ No newline at end of file
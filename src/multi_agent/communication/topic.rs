@@ -0,0 +1,77 @@
+use crate::error::{Error, Result};
+use crate::error::agent_error::AgentError;
+
+/// A topic subscription pattern
+///
+/// Topics are dot-separated names such as `task.42.completed`. In a pattern `*` matches
+/// exactly one segment and `#` matches any number of segments, including none, so
+/// `task.*.completed` matches `task.42.completed` and `monitor.#` matches every topic under
+/// `monitor`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicPattern {
+    pattern: String,
+}
+
+impl TopicPattern {
+    /// Parse a pattern; segments must be non-empty
+    pub fn parse(pattern: &str) -> Result<Self> {
+        if pattern.split('.').any(str::is_empty) {
+            return Err(Error::AgentError(AgentError::ParseError(format!(
+                "Invalid topic pattern '{pattern}': empty segment"
+            ))));
+        }
+        Ok(Self {
+            pattern: pattern.to_string(),
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Check whether a topic matches the pattern
+    pub fn matches(&self, topic: &str) -> bool {
+        let pattern: Vec<&str> = self.pattern.split('.').collect();
+        let topic: Vec<&str> = topic.split('.').collect();
+        matches_segments(&pattern, &topic)
+    }
+}
+
+fn matches_segments(pattern: &[&str], topic: &[&str]) -> bool {
+    match (pattern.first(), topic.first()) {
+        (None, None) => true,
+        (Some(&"#"), _) => {
+            matches_segments(&pattern[1 ..], topic)
+                || (!topic.is_empty() && matches_segments(pattern, &topic[1 ..]))
+        }
+        (Some(segment), Some(name)) if *segment == "*" || segment == name => {
+            matches_segments(&pattern[1 ..], &topic[1 ..])
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_topic_patterns() {
+        let completed = TopicPattern::parse("task.*.completed").unwrap();
+        assert!(completed.matches("task.42.completed"));
+        assert!(!completed.matches("task.42.failed"));
+        assert!(!completed.matches("task.completed"));
+
+        let monitor = TopicPattern::parse("monitor.#").unwrap();
+        assert!(monitor.matches("monitor"));
+        assert!(monitor.matches("monitor.alert.critical"));
+        assert!(!monitor.matches("task.monitor"));
+
+        let failures = TopicPattern::parse("#.failed").unwrap();
+        assert!(failures.matches("task.1.failed"));
+        assert!(!failures.matches("task.1.failed.retry"));
+
+        assert!(TopicPattern::parse("task..completed").is_err());
+        assert!(TopicPattern::parse("").is_err());
+    }
+}
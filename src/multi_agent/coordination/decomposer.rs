@@ -0,0 +1,195 @@
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use model_gateway_rs::{
+    clients::llm::LlmClient,
+    model::llm::{ChatMessage, LlmInput, LlmOutput},
+    sdk::{ModelSDK, openai::OpenAiSdk},
+    traits::ModelClient,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::agent::types::{AgentCapability, TaskType};
+use crate::error::{Error, Result};
+use crate::error::agent_error::AgentError;
+use crate::message::decomposer::generate_decomposer_message;
+use crate::multi_agent::coordination::task_queue::Task;
+use crate::utils::string_util::StripCodeBlock;
+
+/// 默认的 LLM 修复次数
+const DEFAULT_MAX_REPAIRS: usize = 1;
+
+/// 分解得到的子任务描述
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubtaskSpec {
+    /// 在本次分解内唯一的局部 ID
+    pub id: String,
+    pub description: String,
+    pub task_type: TaskType,
+    #[serde(default)]
+    pub capabilities: Vec<AgentCapability>,
+    /// 依赖的同级子任务（局部 ID）
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    pub payload: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Decomposition {
+    subtasks: Vec<SubtaskSpec>,
+}
+
+/// 将复合任务拆分为子任务
+#[async_trait]
+pub trait TaskDecomposer: Send + Sync {
+    /// 拆分 `task`；`allow_composite` 为 false 时子任务不得再是 Composite
+    async fn decompose(&self, task: &Task, allow_composite: bool) -> Result<Vec<SubtaskSpec>>;
+}
+
+/// 解析并校验 LLM 输出的分解结果
+pub fn parse_decomposition(text: &str, allow_composite: bool) -> Result<Vec<SubtaskSpec>> {
+    let decomposition: Decomposition = serde_json::from_str(text.strip_code_block())
+        .map_err(|e| AgentError::ParseError(format!("任务分解 JSON 解析失败: {e}")))?;
+    let subtasks = decomposition.subtasks;
+
+    if subtasks.is_empty() {
+        return Err(invalid("分解结果中没有任何子任务".into()));
+    }
+
+    let mut seen = HashSet::new();
+    for subtask in &subtasks {
+        if !allow_composite && subtask.task_type == TaskType::Composite {
+            return Err(invalid(format!("子任务 {} 不能再是 Composite", subtask.id)));
+        }
+        // 只允许依赖前面的子任务，保证无环
+        for dependency in &subtask.depends_on {
+            if !seen.contains(dependency.as_str()) {
+                return Err(invalid(format!(
+                    "子任务 {} 依赖了未知或靠后的子任务: {dependency}",
+                    subtask.id
+                )));
+            }
+        }
+        if !seen.insert(subtask.id.as_str()) {
+            return Err(invalid(format!("子任务 ID 重复: {}", subtask.id)));
+        }
+    }
+
+    Ok(subtasks)
+}
+
+fn invalid(reason: String) -> Error {
+    Error::AgentError(AgentError::InvalidPlan(reason))
+}
+
+/// 通过与 `Planner` 相同的 LLM 客户端进行任务分解
+pub struct LlmTaskDecomposer<T>
+where
+    T: ModelSDK<Input = LlmInput, Output = LlmOutput> + Sync + Send,
+{
+    llm_client: LlmClient<T>,
+    max_repairs: usize,
+}
+
+impl<T> LlmTaskDecomposer<T>
+where
+    T: ModelSDK<Input = LlmInput, Output = LlmOutput> + Sync + Send,
+{
+    pub fn new(llm_client: LlmClient<T>) -> Self {
+        Self {
+            llm_client,
+            max_repairs: DEFAULT_MAX_REPAIRS,
+        }
+    }
+
+    /// 设置输出无效时的修复次数
+    pub fn with_max_repairs(mut self, max_repairs: usize) -> Self {
+        self.max_repairs = max_repairs;
+        self
+    }
+}
+
+#[async_trait]
+impl<T> TaskDecomposer for LlmTaskDecomposer<T>
+where
+    T: ModelSDK<Input = LlmInput, Output = LlmOutput> + Sync + Send,
+{
+    async fn decompose(&self, task: &Task, allow_composite: bool) -> Result<Vec<SubtaskSpec>> {
+        let mut messages = generate_decomposer_message(task, allow_composite);
+        let mut last_error = None;
+
+        for _ in 0 ..= self.max_repairs {
+            let input = LlmInput {
+                messages: messages.clone(),
+                max_tokens: Some(4096),
+            };
+
+            let output: LlmOutput = self.llm_client.infer(input).await?;
+            let content = output.get_content().to_string();
+
+            match parse_decomposition(&content, allow_composite) {
+                Ok(subtasks) => return Ok(subtasks),
+                Err(e) => {
+                    messages.push(ChatMessage::assistant(content.as_str()));
+                    messages.push(ChatMessage::user(
+                        format!(
+                            "The previous decomposition is invalid: {e}\nFix the problem and output only the corrected JSON."
+                        )
+                        .as_str(),
+                    ));
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| invalid("未能生成有效的任务分解".into())))
+    }
+}
+
+impl Default for LlmTaskDecomposer<OpenAiSdk> {
+    fn default() -> Self {
+        Self::new(LlmClient::new(
+            OpenAiSdk::new("", "http://192.168.1.64:11434/v1", "llama4:scout").unwrap(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_decomposition() {
+        let text = r#"```json
+        {"subtasks": [
+            {"id": "s1", "description": "collect", "task_type": "Execution",
+             "capabilities": [{"ToolCalling": "search"}]},
+            {"id": "s2", "description": "check", "task_type": "Verification", "depends_on": ["s1"]}
+        ]}
+        ```"#;
+
+        let subtasks = parse_decomposition(text, false).unwrap();
+        assert_eq!(subtasks.len(), 2);
+        assert_eq!(
+            subtasks[0].capabilities,
+            vec![AgentCapability::ToolCalling("search".to_string())]
+        );
+        assert_eq!(subtasks[1].depends_on, vec!["s1".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_decomposition_rejects_invalid_dependencies() {
+        let forward = r#"{"subtasks": [
+            {"id": "s1", "description": "a", "task_type": "Execution", "depends_on": ["s2"]},
+            {"id": "s2", "description": "b", "task_type": "Execution"}
+        ]}"#;
+        assert!(parse_decomposition(forward, true).is_err());
+
+        let composite = r#"{"subtasks": [
+            {"id": "s1", "description": "a", "task_type": "Composite"}
+        ]}"#;
+        assert!(parse_decomposition(composite, true).is_ok());
+        assert!(parse_decomposition(composite, false).is_err());
+    }
+}
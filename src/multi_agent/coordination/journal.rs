@@ -0,0 +1,287 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::error::Result;
+use crate::multi_agent::coordination::task_queue::Task;
+
+/// 日志写入后何时调用 fsync
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsyncPolicy {
+    /// 每条记录后都 fsync
+    Always,
+    /// 每 N 条记录 fsync 一次
+    Every(usize),
+    /// 交给操作系统决定
+    Never,
+}
+
+/// 任务日志配置
+#[derive(Debug, Clone)]
+pub struct JournalConfig {
+    /// 日志文件路径
+    pub path: PathBuf,
+    pub fsync: FsyncPolicy,
+    /// 追加多少条记录后压缩日志
+    pub compact_after: usize,
+}
+
+impl JournalConfig {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            fsync: FsyncPolicy::Always,
+            compact_after: 1000,
+        }
+    }
+
+    pub fn with_fsync(mut self, fsync: FsyncPolicy) -> Self {
+        self.fsync = fsync;
+        self
+    }
+
+    pub fn with_compact_after(mut self, entries: usize) -> Self {
+        self.compact_after = entries;
+        self
+    }
+}
+
+/// 日志中的一条记录（每行一个 JSON）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum JournalEntry {
+    Enqueued { task: Task },
+    InProgress { task: Task },
+    Completed { task: Task },
+    Failed { task: Task },
+    /// 失败后重新入队等待重试
+    Retried { task: Task },
+    /// 重试次数用尽
+    DeadLettered { task: Task },
+    Cancelled { task: Task },
+    /// 从待处理队列中移除（例如已过期）
+    Removed { task_id: String },
+}
+
+struct JournalWriter {
+    file: File,
+    unsynced: usize,
+    since_compaction: usize,
+}
+
+/// 追加写入的任务日志
+pub struct TaskJournal {
+    config: JournalConfig,
+    writer: Mutex<JournalWriter>,
+}
+
+impl TaskJournal {
+    /// 打开日志并读出已有记录；末尾未写完整的一行会被忽略
+    pub async fn open(config: JournalConfig) -> Result<(Self, Vec<JournalEntry>)> {
+        let entries = match fs::read_to_string(&config.path).await {
+            Ok(content) => Self::parse(&content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        if let Some(parent) = config.path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent).await?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&config.path)
+            .await?;
+
+        info!(
+            "Task journal {} opened with {} entries",
+            config.path.display(),
+            entries.len()
+        );
+
+        let writer = JournalWriter {
+            file,
+            unsynced: 0,
+            since_compaction: entries.len(),
+        };
+        Ok((
+            Self {
+                config,
+                writer: Mutex::new(writer),
+            },
+            entries,
+        ))
+    }
+
+    fn parse(content: &str) -> Vec<JournalEntry> {
+        let mut entries = Vec::new();
+        for (number, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(line) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => warn!("Skipping corrupt task journal line {}: {}", number + 1, e),
+            }
+        }
+        entries
+    }
+
+    /// 追加一条记录；返回是否到了压缩的时候
+    pub async fn append(&self, entry: &JournalEntry) -> Result<bool> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        let mut writer = self.writer.lock().await;
+        writer.file.write_all(&line).await?;
+        writer.unsynced += 1;
+        writer.since_compaction += 1;
+
+        let sync = match self.config.fsync {
+            FsyncPolicy::Always => true,
+            FsyncPolicy::Every(n) => writer.unsynced >= n.max(1),
+            FsyncPolicy::Never => false,
+        };
+        if sync {
+            writer.file.sync_data().await?;
+            writer.unsynced = 0;
+        }
+
+        Ok(writer.since_compaction >= self.config.compact_after)
+    }
+
+    /// 用当前状态的快照替换日志
+    ///
+    /// 先写入临时文件并 fsync，再原子地重命名覆盖原日志。
+    pub async fn rewrite(&self, entries: &[JournalEntry]) -> Result<()> {
+        let mut writer = self.writer.lock().await;
+
+        let tmp_path = self.config.path.with_extension("compact");
+        let mut content = Vec::new();
+        for entry in entries {
+            content.extend(serde_json::to_vec(entry)?);
+            content.push(b'\n');
+        }
+
+        let mut tmp = File::create(&tmp_path).await?;
+        tmp.write_all(&content).await?;
+        tmp.sync_all().await?;
+        drop(tmp);
+        fs::rename(&tmp_path, &self.config.path).await?;
+
+        writer.file = OpenOptions::new()
+            .append(true)
+            .open(&self.config.path)
+            .await?;
+        writer.unsynced = 0;
+        writer.since_compaction = entries.len();
+
+        info!(
+            "Task journal {} compacted to {} entries",
+            self.config.path.display(),
+            entries.len()
+        );
+        Ok(())
+    }
+
+    /// 将尚未 fsync 的记录刷到磁盘
+    pub async fn sync(&self) -> Result<()> {
+        let mut writer = self.writer.lock().await;
+        writer.file.sync_data().await?;
+        writer.unsynced = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::types::{Priority, TaskStatus, TaskType};
+    use crate::multi_agent::coordination::task_queue::TaskQueue;
+
+    fn journal_path() -> PathBuf {
+        std::env::temp_dir().join(format!("task-journal-{}.jsonl", uuid::Uuid::new_v4()))
+    }
+
+    fn task(name: &str) -> Task {
+        Task::new(
+            TaskType::Execution,
+            Priority::Normal,
+            serde_json::json!({ "name": name }),
+            "test".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_queue_state_survives_restart() {
+        let path = journal_path();
+        let (done, failed) = (task("done"), task("failed"));
+        let (running, waiting) = (task("running"), task("waiting"));
+
+        {
+            let queue = TaskQueue::with_journal(JournalConfig::new(&path)).await.unwrap();
+            for task in [&done, &failed, &running, &waiting] {
+                queue.enqueue(task.clone()).await.unwrap();
+            }
+            for _ in 0 .. 3 {
+                let task = queue.dequeue().await.unwrap();
+                queue.mark_in_progress(task).await.unwrap();
+            }
+            queue.mark_completed(&done.id).await.unwrap();
+            queue.mark_failed(&failed.id, "boom".into()).await.unwrap();
+        }
+
+        // 末尾写了一半的记录会被忽略
+        let mut file = OpenOptions::new().append(true).open(&path).await.unwrap();
+        file.write_all(b"{\"op\":\"enqueued\",\"ta").await.unwrap();
+        drop(file);
+
+        let queue = TaskQueue::with_journal(JournalConfig::new(&path)).await.unwrap();
+        let stats = queue.get_stats().await;
+        assert_eq!((stats.pending, stats.in_progress, stats.completed, stats.failed), (1, 1, 1, 1));
+        assert_eq!(queue.get_task_status(&running.id).await, Some(TaskStatus::InProgress));
+        assert_eq!(queue.dequeue().await.unwrap().id, waiting.id);
+
+        let _ = fs::remove_file(&path).await;
+    }
+
+    #[tokio::test]
+    async fn test_journal_compaction() {
+        let path = journal_path();
+        let config = JournalConfig::new(&path)
+            .with_fsync(FsyncPolicy::Every(2))
+            .with_compact_after(4);
+
+        let queue = TaskQueue::with_journal(config.clone()).await.unwrap();
+        let mut kept = Vec::new();
+        for i in 0 .. 3 {
+            let task = task(&i.to_string());
+            kept.push(task.id.clone());
+            queue.enqueue(task).await.unwrap();
+            let task = queue.dequeue().await.unwrap();
+            queue.mark_in_progress(task).await.unwrap();
+        }
+        // 已出队但未开始的任务在压缩后仍是待处理任务
+        queue.enqueue(task("waiting")).await.unwrap();
+        let waiting = queue.dequeue().await.unwrap();
+        queue.compact().await.unwrap();
+
+        let lines = fs::read_to_string(&path).await.unwrap().lines().count();
+        assert_eq!(lines, 4);
+
+        drop(queue);
+        let queue = TaskQueue::with_journal(config).await.unwrap();
+        for id in &kept {
+            assert_eq!(queue.get_task_status(id).await, Some(TaskStatus::InProgress));
+        }
+        assert_eq!(queue.get_task_status(&waiting.id).await, Some(TaskStatus::Pending));
+
+        let _ = fs::remove_file(&path).await;
+    }
+}
@@ -1,3 +1,8 @@
+pub mod decomposer;
+pub mod journal;
 pub mod task_queue;
 
-pub use task_queue::{Task, TaskQueue, TaskQueueStats};
\ No newline at end of file
+pub use decomposer::{LlmTaskDecomposer, SubtaskSpec, TaskDecomposer};
+pub use journal::{FsyncPolicy, JournalConfig, TaskJournal};
+pub use task_queue::{RetryOutcome, Task, TaskQueue, TaskQueueStats};
+pub use crate::utils::retry::RetryPolicy;
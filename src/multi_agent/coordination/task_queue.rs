@@ -1,12 +1,29 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
+use tracing::{info, warn};
 
-use crate::agent::types::{Priority, TaskStatus, TaskType};
+use crate::agent::types::{AgentCapability, Priority, TaskStatus, TaskType};
 use crate::error::{Result, Error};
 use crate::error::agent_error::AgentError;
+use crate::multi_agent::coordination::journal::{JournalConfig, JournalEntry, TaskJournal};
+use crate::utils::dag::{DagError, DependencyGraph};
+use crate::utils::retry::RetryPolicy;
+
+/// 默认租约时长，与 `GlobalConfig::task_timeout_secs` 的默认值一致
+const DEFAULT_LEASE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// 可重试失败的处理结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum RetryOutcome {
+    /// 已重新入队，`retry_at` 之前不会出队
+    Retrying { attempt: u32, retry_at: DateTime<Utc> },
+    /// 重试次数用尽，已进入死信列表；`failed_dependents` 是因此一并失败的下游任务
+    DeadLettered { failed_dependents: Vec<String> },
+}
 
 /// 任务定义
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,12 +34,26 @@ pub struct Task {
     pub status: TaskStatus,
     pub payload: serde_json::Value,
     pub dependencies: Vec<String>,
+    /// 除任务类型外，执行者还需具备的能力
+    #[serde(default)]
+    pub required_capabilities: Vec<AgentCapability>,
     pub assigned_to: Option<String>,
     pub created_by: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub deadline: Option<DateTime<Utc>>,
     pub metadata: serde_json::Value,
+    #[serde(default)]
+    pub retry_policy: RetryPolicy,
+    /// 已失败的次数
+    #[serde(default)]
+    pub attempts: u32,
+    /// 重试的最早出队时间
+    #[serde(default)]
+    pub retry_at: Option<DateTime<Utc>>,
+    /// 进行中任务的租约到期时间，到期未完成视为执行者已失联
+    #[serde(default)]
+    pub lease_expires_at: Option<DateTime<Utc>>,
 }
 
 impl Task {
@@ -40,12 +71,17 @@ impl Task {
             status: TaskStatus::Pending,
             payload,
             dependencies: Vec::new(),
+            required_capabilities: Vec::new(),
             assigned_to: None,
             created_by,
             created_at: now,
             updated_at: now,
             deadline: None,
             metadata: serde_json::json!({}),
+            retry_policy: RetryPolicy::default(),
+            attempts: 0,
+            retry_at: None,
+            lease_expires_at: None,
         }
     }
 
@@ -56,6 +92,27 @@ impl Task {
         }
     }
 
+    /// 添加所需能力
+    pub fn with_capabilities(mut self, capabilities: Vec<AgentCapability>) -> Self {
+        for capability in capabilities {
+            if !self.required_capabilities.contains(&capability) {
+                self.required_capabilities.push(capability);
+            }
+        }
+        self
+    }
+
+    /// 执行者必须具备的全部能力（任务类型对应的能力在前）
+    pub fn capabilities(&self) -> Vec<AgentCapability> {
+        let mut capabilities = vec![self.task_type.required_capability()];
+        for capability in &self.required_capabilities {
+            if !capabilities.contains(capability) {
+                capabilities.push(capability.clone());
+            }
+        }
+        capabilities
+    }
+
     /// 设置截止时间
     pub fn with_deadline(mut self, deadline: DateTime<Utc>) -> Self {
         self.deadline = Some(deadline);
@@ -68,6 +125,12 @@ impl Task {
         self
     }
 
+    /// 设置重试策略
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
     /// 检查是否已过期
     pub fn is_expired(&self) -> bool {
         if let Some(deadline) = self.deadline {
@@ -77,6 +140,11 @@ impl Task {
         }
     }
 
+    /// 检查租约是否已到期
+    pub fn is_lease_expired(&self) -> bool {
+        self.lease_expires_at.is_some_and(|expires_at| Utc::now() > expires_at)
+    }
+
     /// 更新状态
     pub fn update_status(&mut self, status: TaskStatus) {
         self.status = status;
@@ -94,8 +162,18 @@ pub struct TaskQueue {
     completed: Arc<RwLock<Vec<Task>>>,
     /// 失败的任务
     failed: Arc<RwLock<Vec<Task>>>,
+    /// 重试次数用尽的任务
+    dead_letter: Arc<RwLock<Vec<Task>>>,
+    /// 已取消的任务
+    cancelled: Arc<RwLock<Vec<Task>>>,
     /// 任务依赖图
     dependencies: Arc<RwLock<HashMap<String, Vec<String>>>>,
+    /// 已出队但尚未开始的任务，压缩日志时仍视为待处理
+    dequeued: Arc<RwLock<HashMap<String, Task>>>,
+    /// 可选的持久化日志
+    journal: Option<Arc<TaskJournal>>,
+    /// 进行中任务的租约时长
+    lease_timeout: RwLock<Duration>,
 }
 
 impl Default for TaskQueue {
@@ -117,12 +195,177 @@ impl TaskQueue {
             in_progress: Arc::new(RwLock::new(HashMap::new())),
             completed: Arc::new(RwLock::new(Vec::new())),
             failed: Arc::new(RwLock::new(Vec::new())),
+            dead_letter: Arc::new(RwLock::new(Vec::new())),
+            cancelled: Arc::new(RwLock::new(Vec::new())),
             dependencies: Arc::new(RwLock::new(HashMap::new())),
+            dequeued: Arc::new(RwLock::new(HashMap::new())),
+            journal: None,
+            lease_timeout: RwLock::new(DEFAULT_LEASE_TIMEOUT),
+        }
+    }
+
+    /// 设置进行中任务的租约时长；只影响之后开始的任务
+    pub async fn set_lease_timeout(&self, timeout: Duration) {
+        *self.lease_timeout.write().await = timeout;
+    }
+
+    /// 创建由追加日志持久化的任务队列
+    ///
+    /// 打开时会重放日志恢复各状态的任务；已出队但尚未开始的任务会回到待处理队列。
+    pub async fn with_journal(config: JournalConfig) -> Result<Self> {
+        let (journal, entries) = TaskJournal::open(config).await?;
+        let queue = Self {
+            journal: Some(Arc::new(journal)),
+            ..Self::new()
+        };
+        queue.replay(entries).await;
+        Ok(queue)
+    }
+
+    /// 根据日志记录重建队列状态
+    async fn replay(&self, entries: Vec<JournalEntry>) {
+        enum Slot {
+            Pending,
+            InProgress,
+            Completed,
+            Failed,
+            DeadLetter,
+            Cancelled,
+        }
+
+        // 按首次出现的顺序保留任务，待处理队列因此保持原有的先后顺序
+        let mut order: Vec<String> = Vec::new();
+        let mut tasks: HashMap<String, (Slot, Task)> = HashMap::new();
+
+        for entry in entries {
+            let (slot, task) = match entry {
+                JournalEntry::Enqueued { mut task } => {
+                    if tasks.contains_key(&task.id) {
+                        continue;
+                    }
+                    task.status = TaskStatus::Pending;
+                    (Slot::Pending, task)
+                }
+                JournalEntry::Retried { task } => (Slot::Pending, task),
+                JournalEntry::InProgress { task } => (Slot::InProgress, task),
+                JournalEntry::Completed { task } => (Slot::Completed, task),
+                JournalEntry::Failed { task } => (Slot::Failed, task),
+                JournalEntry::DeadLettered { task } => (Slot::DeadLetter, task),
+                JournalEntry::Cancelled { task } => (Slot::Cancelled, task),
+                JournalEntry::Removed { task_id } => {
+                    tasks.remove(&task_id);
+                    continue;
+                }
+            };
+            if !tasks.contains_key(&task.id) {
+                order.push(task.id.clone());
+            }
+            tasks.insert(task.id.clone(), (slot, task));
+        }
+
+        let mut pending = self.pending.write().await;
+        let mut in_progress = self.in_progress.write().await;
+        let mut completed = self.completed.write().await;
+        let mut failed = self.failed.write().await;
+        let mut dead_letter = self.dead_letter.write().await;
+        let mut cancelled = self.cancelled.write().await;
+        let mut dependencies = self.dependencies.write().await;
+
+        for id in order {
+            let Some((slot, task)) = tasks.remove(&id) else {
+                continue;
+            };
+            if !task.dependencies.is_empty() && matches!(slot, Slot::Pending | Slot::InProgress) {
+                dependencies.insert(task.id.clone(), task.dependencies.clone());
+            }
+            match slot {
+                Slot::Pending => {
+                    if let Some(queue) = pending.get_mut(&task.priority) {
+                        queue.push_back(task);
+                    }
+                }
+                Slot::InProgress => {
+                    in_progress.insert(task.id.clone(), task);
+                }
+                Slot::Completed => completed.push(task),
+                Slot::Failed => failed.push(task),
+                Slot::DeadLetter => dead_letter.push(task),
+                Slot::Cancelled => cancelled.push(task),
+            }
+        }
+    }
+
+    /// 写入日志；日志增长到阈值时返回 true
+    async fn record(&self, entry: JournalEntry) -> Result<bool> {
+        match &self.journal {
+            Some(journal) => journal.append(&entry).await,
+            None => Ok(false),
+        }
+    }
+
+    async fn compact_if_due(&self, due: bool) {
+        if due && let Err(e) = self.compact().await {
+            warn!("Failed to compact task journal: {}", e);
+        }
+    }
+
+    /// 用当前状态的快照压缩日志；未配置日志时什么也不做
+    pub async fn compact(&self) -> Result<()> {
+        let Some(journal) = &self.journal else {
+            return Ok(());
+        };
+
+        // 持有全部读锁直到写完，避免快照与并发的日志追加交错
+        let pending = self.pending.read().await;
+        let in_progress = self.in_progress.read().await;
+        let completed = self.completed.read().await;
+        let failed = self.failed.read().await;
+        let dead_letter = self.dead_letter.read().await;
+        let dequeued = self.dequeued.read().await;
+        let cancelled = self.cancelled.read().await;
+
+        let mut entries = Vec::new();
+        entries.extend(completed.iter().cloned().map(|task| JournalEntry::Completed { task }));
+        entries.extend(failed.iter().cloned().map(|task| JournalEntry::Failed { task }));
+        entries.extend(
+            dead_letter
+                .iter()
+                .cloned()
+                .map(|task| JournalEntry::DeadLettered { task }),
+        );
+        entries.extend(cancelled.iter().cloned().map(|task| JournalEntry::Cancelled { task }));
+        entries.extend(in_progress.values().cloned().map(|task| JournalEntry::InProgress { task }));
+        for priority in [Priority::Critical, Priority::High, Priority::Normal, Priority::Low] {
+            if let Some(queue) = pending.get(&priority) {
+                entries.extend(queue.iter().cloned().map(|task| JournalEntry::Enqueued { task }));
+            }
+        }
+        entries.extend(dequeued.values().cloned().map(|task| JournalEntry::Enqueued { task }));
+
+        journal.rewrite(&entries).await
+    }
+
+    /// 将日志中尚未落盘的记录刷到磁盘
+    pub async fn sync(&self) -> Result<()> {
+        match &self.journal {
+            Some(journal) => journal.sync().await,
+            None => Ok(()),
         }
     }
 
     /// 入队任务
+    ///
+    /// 依赖必须是队列中已有的任务，且不能依赖自身、已失败的任务或形成环。
     pub async fn enqueue(&self, task: Task) -> Result<()> {
+        let mut pending = self.pending.write().await;
+        self.validate_dependencies(&pending, &task).await?;
+        let queue = pending.get_mut(&task.priority).ok_or_else(|| {
+            Error::AgentError(AgentError::InternalError("Invalid priority".into()))
+        })?;
+
+        // 先写日志，失败时不改变队列
+        let due = self.record(JournalEntry::Enqueued { task: task.clone() }).await?;
+
         // 检查依赖
         if !task.dependencies.is_empty() {
             self.dependencies
@@ -132,21 +375,161 @@ impl TaskQueue {
         }
 
         // 根据优先级入队
-        self.pending
-            .write()
-            .await
-            .get_mut(&task.priority)
-            .ok_or_else(|| Error::AgentError(AgentError::InternalError("Invalid priority".into())))?
-            .push_back(task);
+        queue.push_back(task);
+        drop(pending);
+
+        self.compact_if_due(due).await;
+        Ok(())
+    }
+
+    /// 校验新任务的依赖
+    async fn validate_dependencies(
+        &self,
+        pending: &HashMap<Priority, VecDeque<Task>>,
+        task: &Task,
+    ) -> Result<()> {
+        let in_progress = self.in_progress.read().await;
+        let completed = self.completed.read().await;
+        let failed = self.failed.read().await;
+        let dead_letter = self.dead_letter.read().await;
+        let dequeued = self.dequeued.read().await;
+        let cancelled = self.cancelled.read().await;
+
+        let mut graph = DependencyGraph::new();
+        let open: Vec<&Task> = pending
+            .values()
+            .flatten()
+            .chain(in_progress.values())
+            .chain(dequeued.values())
+            .collect();
+        for existing in &open {
+            graph.add_node(existing.id.clone(), existing.dependencies.clone());
+        }
+        for done in completed.iter() {
+            graph.add_external(done.id.clone());
+        }
+        if graph.contains(&task.id) {
+            return Err(AgentError::from(DagError::DuplicateNode(task.id.clone())).into());
+        }
 
+        let ended: HashSet<&str> = failed
+            .iter()
+            .chain(dead_letter.iter())
+            .chain(cancelled.iter())
+            .map(|t| t.id.as_str())
+            .collect();
+        if let Some(dependency) = task.dependencies.iter().find(|d| ended.contains(d.as_str())) {
+            return Err(AgentError::from(DagError::FailedDependency {
+                node: task.id.clone(),
+                dependency: dependency.clone(),
+            })
+            .into());
+        }
+
+        // 已有任务中失效的依赖（例如已过期被移除的任务）不影响新任务的校验
+        for existing in &open {
+            for dependency in &existing.dependencies {
+                if !graph.contains(dependency) {
+                    graph.add_external(dependency.clone());
+                }
+            }
+        }
+
+        graph.add_node(task.id.clone(), task.dependencies.clone());
+        graph.validate().map_err(AgentError::from)?;
         Ok(())
     }
 
+    /// 未结束任务的依赖图；已完成的任务作为外部节点
+    pub async fn dependency_graph(&self) -> DependencyGraph<String> {
+        let pending = self.pending.read().await;
+        let in_progress = self.in_progress.read().await;
+        let completed = self.completed.read().await;
+        let dequeued = self.dequeued.read().await;
+
+        let mut graph = DependencyGraph::new();
+        for task in completed.iter() {
+            graph.add_external(task.id.clone());
+        }
+        let open = pending
+            .values()
+            .flatten()
+            .chain(in_progress.values())
+            .chain(dequeued.values());
+        for task in open {
+            graph.add_node(task.id.clone(), task.dependencies.clone());
+        }
+        graph
+    }
+
+    /// 未结束任务的拓扑顺序
+    pub async fn topological_order(&self) -> Result<Vec<String>> {
+        let order = self.dependency_graph().await.topological_order();
+        Ok(order.map_err(AgentError::from)?)
+    }
+
+    /// 未结束任务的关键路径及其总耗时，`cost` 给出单个任务的耗时
+    pub async fn critical_path(&self, cost: impl Fn(&str) -> u64) -> Result<(Vec<String>, u64)> {
+        let path = self.dependency_graph().await.critical_path(|id: &String| cost(id));
+        Ok(path.map_err(AgentError::from)?)
+    }
+
+    /// 将直接或间接依赖 `failed_id` 的待处理任务标记为失败
+    ///
+    /// 返回这些任务的 ID，以及日志是否需要压缩。
+    async fn fail_dependents(
+        &self,
+        pending: &mut HashMap<Priority, VecDeque<Task>>,
+        failed_id: &str,
+    ) -> Result<(Vec<String>, bool)> {
+        let mut failed = self.failed.write().await;
+        let mut dequeued = self.dequeued.write().await;
+
+        let mut graph = DependencyGraph::new();
+        for task in pending.values().flatten().chain(dequeued.values()) {
+            graph.add_node(task.id.clone(), task.dependencies.clone());
+        }
+        let ids: HashSet<String> = graph.dependents(&failed_id.to_string()).into_iter().collect();
+        if ids.is_empty() {
+            return Ok((Vec::new(), false));
+        }
+
+        let reason = format!("upstream task {failed_id} failed");
+        let updated: Vec<Task> = pending
+            .values()
+            .flatten()
+            .chain(dequeued.values())
+            .filter(|task| ids.contains(&task.id))
+            .cloned()
+            .map(|mut task| {
+                task.update_status(TaskStatus::Failed(reason.clone()));
+                task
+            })
+            .collect();
+
+        // 先写日志，再修改队列
+        let mut due = false;
+        for task in &updated {
+            due |= self.record(JournalEntry::Failed { task: task.clone() }).await?;
+        }
+
+        for queue in pending.values_mut() {
+            queue.retain(|task| !ids.contains(&task.id));
+        }
+        dequeued.retain(|id, _| !ids.contains(id));
+
+        let failed_ids = updated.iter().map(|task| task.id.clone()).collect();
+        warn!("{} task(s) failed because task {} failed", updated.len(), failed_id);
+        failed.extend(updated);
+        Ok((failed_ids, due))
+    }
+
     /// 出队任务（考虑优先级和依赖）
     pub async fn dequeue(&self) -> Option<Task> {
         let mut pending = self.pending.write().await;
         let in_progress = self.in_progress.read().await;
         let completed = self.completed.read().await;
+        let now = Utc::now();
 
         // 按优先级顺序检查
         for priority in [Priority::Critical, Priority::High, Priority::Normal, Priority::Low] {
@@ -154,6 +537,10 @@ impl TaskQueue {
                 // 查找可执行的任务（依赖已满足）
                 let mut index = None;
                 for (i, task) in queue.iter().enumerate() {
+                    // 仍在重试等待期内的任务暂不出队
+                    if task.retry_at.is_some_and(|retry_at| retry_at > now) {
+                        continue;
+                    }
                     if self.are_dependencies_satisfied(task, &in_progress, &completed).await {
                         index = Some(i);
                         break;
@@ -161,7 +548,9 @@ impl TaskQueue {
                 }
 
                 if let Some(i) = index {
-                    return queue.remove(i);
+                    let task = queue.remove(i)?;
+                    self.dequeued.write().await.insert(task.id.clone(), task.clone());
+                    return Some(task);
                 }
             }
         }
@@ -196,39 +585,244 @@ impl TaskQueue {
         true
     }
 
-    /// 标记任务开始
+    /// 标记任务开始，并为其授予一个租约
+    ///
+    /// 租约到期前未完成的任务会被 `reclaim_expired_leases` 收回。
     pub async fn mark_in_progress(&self, mut task: Task) -> Result<()> {
+        let lease_timeout = *self.lease_timeout.read().await;
+        task.lease_expires_at = Some(Utc::now() + lease_timeout);
+        self.start(task).await
+    }
+
+    /// 标记任务开始但不授予租约
+    ///
+    /// 用于完成与否由其他任务决定的任务，例如等待子任务的父任务。
+    pub async fn mark_in_progress_unleased(&self, mut task: Task) -> Result<()> {
+        task.lease_expires_at = None;
+        self.start(task).await
+    }
+
+    async fn start(&self, mut task: Task) -> Result<()> {
+        task.retry_at = None;
         task.update_status(TaskStatus::InProgress);
-        self.in_progress
-            .write()
-            .await
-            .insert(task.id.clone(), task);
+        let mut in_progress = self.in_progress.write().await;
+        let due = self.record(JournalEntry::InProgress { task: task.clone() }).await?;
+        self.dequeued.write().await.remove(&task.id);
+        in_progress.insert(task.id.clone(), task);
+        drop(in_progress);
+
+        self.compact_if_due(due).await;
         Ok(())
     }
 
     /// 标记任务完成
     pub async fn mark_completed(&self, task_id: &str) -> Result<()> {
-        if let Some(mut task) = self.in_progress.write().await.remove(task_id) {
-            task.update_status(TaskStatus::Completed);
-            self.completed.write().await.push(task);
-            
-            // 清理依赖信息
-            self.dependencies.write().await.remove(task_id);
-            Ok(())
-        } else {
-            Err(Error::AgentError(AgentError::TaskNotFound(task_id.to_string())))
-        }
+        let mut in_progress = self.in_progress.write().await;
+        let Some(mut task) = in_progress.get(task_id).cloned() else {
+            return Err(Error::AgentError(AgentError::TaskNotFound(task_id.to_string())));
+        };
+        task.update_status(TaskStatus::Completed);
+        let due = self.record(JournalEntry::Completed { task: task.clone() }).await?;
+
+        in_progress.remove(task_id);
+        self.completed.write().await.push(task);
+        drop(in_progress);
+
+        // 清理依赖信息
+        self.dependencies.write().await.remove(task_id);
+
+        self.compact_if_due(due).await;
+        Ok(())
     }
 
     /// 标记任务失败
-    pub async fn mark_failed(&self, task_id: &str, reason: String) -> Result<()> {
-        if let Some(mut task) = self.in_progress.write().await.remove(task_id) {
-            task.update_status(TaskStatus::Failed(reason));
-            self.failed.write().await.push(task);
-            Ok(())
+    ///
+    /// 依赖它的待处理任务也随之失败；返回这些下游任务的 ID。
+    pub async fn mark_failed(&self, task_id: &str, reason: String) -> Result<Vec<String>> {
+        let mut pending = self.pending.write().await;
+        let mut in_progress = self.in_progress.write().await;
+        let Some(mut task) = in_progress.get(task_id).cloned() else {
+            return Err(Error::AgentError(AgentError::TaskNotFound(task_id.to_string())));
+        };
+        task.update_status(TaskStatus::Failed(reason));
+        let due = self.record(JournalEntry::Failed { task: task.clone() }).await?;
+
+        in_progress.remove(task_id);
+        self.failed.write().await.push(task);
+        drop(in_progress);
+
+        let (dependents, compact) = self.fail_dependents(&mut pending, task_id).await?;
+        drop(pending);
+
+        self.compact_if_due(due || compact).await;
+        Ok(dependents)
+    }
+
+    /// 续约进行中的任务
+    pub async fn renew_lease(&self, task_id: &str) -> Result<()> {
+        let mut in_progress = self.in_progress.write().await;
+        let Some(mut task) = in_progress.get(task_id).cloned() else {
+            return Err(Error::AgentError(AgentError::TaskNotFound(task_id.to_string())));
+        };
+        if task.lease_expires_at.is_none() {
+            return Ok(());
+        }
+        task.lease_expires_at = Some(Utc::now() + *self.lease_timeout.read().await);
+        let due = self.record(JournalEntry::InProgress { task: task.clone() }).await?;
+        in_progress.insert(task.id.clone(), task);
+        drop(in_progress);
+
+        self.compact_if_due(due).await;
+        Ok(())
+    }
+
+    /// 标记任务可重试地失败
+    ///
+    /// 未用尽重试次数时按退避时间重新入队，否则移入死信列表。
+    pub async fn mark_retryable_failure(
+        &self,
+        task_id: &str,
+        reason: String,
+    ) -> Result<RetryOutcome> {
+        let mut pending = self.pending.write().await;
+        let mut in_progress = self.in_progress.write().await;
+        let Some(mut task) = in_progress.get(task_id).cloned() else {
+            return Err(Error::AgentError(AgentError::TaskNotFound(task_id.to_string())));
+        };
+
+        task.attempts += 1;
+        task.lease_expires_at = None;
+        task.assigned_to = None;
+
+        let (outcome, due) = if task.attempts <= task.retry_policy.max_retries {
+            let retry_at = Utc::now() + task.retry_policy.backoff(task.attempts);
+            task.retry_at = Some(retry_at);
+            task.update_status(TaskStatus::Pending);
+            let due = self.record(JournalEntry::Retried { task: task.clone() }).await?;
+
+            info!(
+                "Task {} failed ({}), retry {}/{} at {}",
+                task.id, reason, task.attempts, task.retry_policy.max_retries, retry_at
+            );
+            in_progress.remove(task_id);
+            let outcome = RetryOutcome::Retrying {
+                attempt: task.attempts,
+                retry_at,
+            };
+            if let Some(queue) = pending.get_mut(&task.priority) {
+                queue.push_back(task);
+            }
+            (outcome, due)
         } else {
-            Err(Error::AgentError(AgentError::TaskNotFound(task_id.to_string())))
+            task.update_status(TaskStatus::Failed(reason));
+            let due = self.record(JournalEntry::DeadLettered { task: task.clone() }).await?;
+
+            warn!("Task {} dead-lettered after {} attempts", task.id, task.attempts);
+            in_progress.remove(task_id);
+            self.dead_letter.write().await.push(task);
+
+            let (failed_dependents, compact) = self.fail_dependents(&mut pending, task_id).await?;
+            (RetryOutcome::DeadLettered { failed_dependents }, due || compact)
+        };
+        drop(in_progress);
+        drop(pending);
+
+        self.compact_if_due(due).await;
+        Ok(outcome)
+    }
+
+    /// 收回租约已到期的任务，按可重试失败处理
+    ///
+    /// 返回被收回的任务（收回前的状态）及其处理结果。
+    pub async fn reclaim_expired_leases(&self) -> Vec<(Task, RetryOutcome)> {
+        let expired: Vec<Task> = self
+            .in_progress
+            .read()
+            .await
+            .values()
+            .filter(|task| task.is_lease_expired())
+            .cloned()
+            .collect();
+
+        let mut reclaimed = Vec::with_capacity(expired.len());
+        for task in expired {
+            match self.mark_retryable_failure(&task.id, "lease expired".into()).await {
+                Ok(outcome) => reclaimed.push((task, outcome)),
+                Err(e) => warn!("Failed to reclaim task {}: {}", task.id, e),
+            }
+        }
+        reclaimed
+    }
+
+    /// 取消任务，并级联取消所有直接或间接依赖它的任务
+    ///
+    /// 待处理或已出队的任务被移除，进行中的任务被标记为已取消。
+    /// 返回被取消的任务（取消前的状态），执行者可据此通知正在运行的 Agent。
+    pub async fn cancel(&self, task_id: &str) -> Result<Vec<Task>> {
+        let mut pending = self.pending.write().await;
+        let mut in_progress = self.in_progress.write().await;
+        let mut dequeued = self.dequeued.write().await;
+
+        let open: Vec<&Task> = pending
+            .values()
+            .flatten()
+            .chain(in_progress.values())
+            .chain(dequeued.values())
+            .collect();
+        if !open.iter().any(|task| task.id == task_id) {
+            return Err(Error::AgentError(AgentError::TaskNotFound(task_id.to_string())));
+        }
+
+        let mut graph = DependencyGraph::new();
+        for task in &open {
+            graph.add_node(task.id.clone(), task.dependencies.clone());
+        }
+        let mut ids: HashSet<String> = graph.dependents(&task_id.to_string()).into_iter().collect();
+        ids.insert(task_id.to_string());
+
+        let targets: Vec<Task> = open
+            .into_iter()
+            .filter(|task| ids.contains(&task.id))
+            .cloned()
+            .collect();
+
+        let updated: Vec<Task> = targets
+            .iter()
+            .cloned()
+            .map(|mut task| {
+                task.lease_expires_at = None;
+                task.update_status(TaskStatus::Cancelled);
+                task
+            })
+            .collect();
+
+        // 先写日志，再修改队列
+        let mut due = false;
+        for task in &updated {
+            due |= self.record(JournalEntry::Cancelled { task: task.clone() }).await?;
+        }
+
+        for queue in pending.values_mut() {
+            queue.retain(|task| !ids.contains(&task.id));
         }
+        in_progress.retain(|id, _| !ids.contains(id));
+        dequeued.retain(|id, _| !ids.contains(id));
+
+        self.cancelled.write().await.extend(updated);
+        drop(dequeued);
+        drop(in_progress);
+        drop(pending);
+
+        self.dependencies.write().await.retain(|id, _| !ids.contains(id));
+        info!("Cancelled {} task(s) starting from {}", targets.len(), task_id);
+
+        self.compact_if_due(due).await;
+        Ok(targets)
+    }
+
+    /// 获取死信列表中的任务
+    pub async fn dead_letters(&self) -> Vec<Task> {
+        self.dead_letter.read().await.clone()
     }
 
     /// 获取任务状态
@@ -248,6 +842,21 @@ impl TaskQueue {
             return Some(task.status.clone());
         }
 
+        // 检查死信
+        if let Some(task) = self.dead_letter.read().await.iter().find(|t| t.id == task_id) {
+            return Some(task.status.clone());
+        }
+
+        // 检查已取消
+        if let Some(task) = self.cancelled.read().await.iter().find(|t| t.id == task_id) {
+            return Some(task.status.clone());
+        }
+
+        // 检查已出队
+        if let Some(task) = self.dequeued.read().await.get(task_id) {
+            return Some(task.status.clone());
+        }
+
         // 检查待处理
         for queue in self.pending.read().await.values() {
             if let Some(task) = queue.iter().find(|t| t.id == task_id) {
@@ -267,35 +876,32 @@ impl TaskQueue {
         tasks
     }
 
-    /// 获取队列大小
-    pub fn size(&self) -> usize {
-        // 这是一个简化版本，实际使用时应该使用异步版本
-        0
+    /// 获取等待出队的任务数
+    pub async fn size(&self) -> usize {
+        self.pending.read().await.values().map(|q| q.len()).sum()
     }
 
     /// 获取统计信息
     pub async fn get_stats(&self) -> TaskQueueStats {
-        let pending_count: usize = self
-            .pending
-            .read()
-            .await
-            .values()
-            .map(|q| q.len())
-            .sum();
+        let pending_count = self.size().await;
 
         TaskQueueStats {
             pending: pending_count,
             in_progress: self.in_progress.read().await.len(),
             completed: self.completed.read().await.len(),
             failed: self.failed.read().await.len(),
+            dead_lettered: self.dead_letter.read().await.len(),
+            cancelled: self.cancelled.read().await.len(),
         }
     }
 
     /// 清理过期任务
     pub async fn cleanup_expired(&self) -> usize {
         let mut count = 0;
-        
-        for queue in self.pending.write().await.values_mut() {
+        let mut due = false;
+
+        let mut pending = self.pending.write().await;
+        for queue in pending.values_mut() {
             let expired: Vec<usize> = queue
                 .iter()
                 .enumerate()
@@ -304,11 +910,21 @@ impl TaskQueue {
                 .collect();
 
             for i in expired.into_iter().rev() {
+                let task_id = queue[i].id.clone();
+                match self.record(JournalEntry::Removed { task_id: task_id.clone() }).await {
+                    Ok(compact) => due |= compact,
+                    Err(e) => {
+                        warn!("Failed to journal removal of expired task {}: {}", task_id, e);
+                        continue;
+                    }
+                }
                 queue.remove(i);
                 count += 1;
             }
         }
+        drop(pending);
 
+        self.compact_if_due(due).await;
         count
     }
 }
@@ -320,4 +936,174 @@ pub struct TaskQueueStats {
     pub in_progress: usize,
     pub completed: usize,
     pub failed: usize,
-}
\ No newline at end of file
+    pub dead_lettered: usize,
+    pub cancelled: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(retry_policy: RetryPolicy) -> Task {
+        Task::new(
+            TaskType::Execution,
+            Priority::Normal,
+            serde_json::json!({}),
+            "test".to_string(),
+        )
+        .with_retry_policy(retry_policy)
+    }
+
+    #[tokio::test]
+    async fn test_retry_then_dead_letter() {
+        let queue = TaskQueue::new();
+        let policy = RetryPolicy {
+            max_retries: 1,
+            initial_backoff_ms: 0,
+            ..RetryPolicy::default()
+        };
+        let task = task(policy);
+        queue.enqueue(task.clone()).await.unwrap();
+        assert_eq!(queue.size().await, 1);
+
+        let started = queue.dequeue().await.unwrap();
+        queue.mark_in_progress(started).await.unwrap();
+        let outcome = queue.mark_retryable_failure(&task.id, "flaky".into()).await.unwrap();
+        assert!(matches!(outcome, RetryOutcome::Retrying { attempt: 1, .. }));
+
+        let retried = queue.dequeue().await.unwrap();
+        assert_eq!(retried.attempts, 1);
+        queue.mark_in_progress(retried).await.unwrap();
+        let outcome = queue.mark_retryable_failure(&task.id, "flaky".into()).await.unwrap();
+        assert_eq!(
+            outcome,
+            RetryOutcome::DeadLettered {
+                failed_dependents: vec![]
+            }
+        );
+
+        assert!(queue.dequeue().await.is_none());
+        assert_eq!(queue.size().await, 0);
+        assert_eq!(queue.dead_letters().await.len(), 1);
+        assert_eq!(queue.get_stats().await.dead_lettered, 1);
+    }
+
+    #[tokio::test]
+    async fn test_backoff_delays_dequeue() {
+        let queue = TaskQueue::new();
+        let task = task(RetryPolicy {
+            initial_backoff_ms: 60_000,
+            ..RetryPolicy::default()
+        });
+        queue.mark_in_progress(task.clone()).await.unwrap();
+        queue.mark_retryable_failure(&task.id, "flaky".into()).await.unwrap();
+
+        assert!(queue.dequeue().await.is_none());
+        assert_eq!(queue.get_task_status(&task.id).await, Some(TaskStatus::Pending));
+    }
+
+    #[tokio::test]
+    async fn test_expired_lease_is_reclaimed() {
+        let queue = TaskQueue::new();
+        queue.set_lease_timeout(Duration::ZERO).await;
+
+        let leased = task(RetryPolicy::none());
+        let parent = task(RetryPolicy::none());
+        queue.mark_in_progress(leased.clone()).await.unwrap();
+        queue.mark_in_progress_unleased(parent.clone()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        let reclaimed = queue.reclaim_expired_leases().await;
+        assert_eq!(reclaimed.len(), 1);
+        assert_eq!(reclaimed[0].0.id, leased.id);
+        assert_eq!(
+            reclaimed[0].1,
+            RetryOutcome::DeadLettered {
+                failed_dependents: vec![]
+            }
+        );
+        assert_eq!(queue.get_task_status(&parent.id).await, Some(TaskStatus::InProgress));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_cascades_to_dependents() {
+        let queue = TaskQueue::new();
+        let running = task(RetryPolicy::default());
+        let mut child = task(RetryPolicy::default());
+        child.add_dependency(running.id.clone());
+        let mut grandchild = task(RetryPolicy::default());
+        grandchild.add_dependency(child.id.clone());
+        let unrelated = task(RetryPolicy::default());
+
+        queue.mark_in_progress(running.clone()).await.unwrap();
+        for task in [&child, &grandchild, &unrelated] {
+            queue.enqueue(task.clone()).await.unwrap();
+        }
+
+        let cancelled = queue.cancel(&running.id).await.unwrap();
+        assert_eq!(cancelled.len(), 3);
+        let before = cancelled.iter().find(|t| t.id == running.id).unwrap();
+        assert_eq!(before.status, TaskStatus::InProgress);
+        for id in [&running.id, &child.id, &grandchild.id] {
+            assert_eq!(queue.get_task_status(id).await, Some(TaskStatus::Cancelled));
+        }
+
+        assert_eq!(queue.dequeue().await.unwrap().id, unrelated.id);
+        assert_eq!(queue.get_stats().await.cancelled, 3);
+        assert!(queue.cancel(&running.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_enqueue_validates_dependencies() {
+        let queue = TaskQueue::new();
+        let first = task(RetryPolicy::default());
+        queue.enqueue(first.clone()).await.unwrap();
+
+        let mut unknown = task(RetryPolicy::default());
+        unknown.add_dependency("missing".to_string());
+        assert!(queue.enqueue(unknown).await.is_err());
+
+        let mut itself = task(RetryPolicy::default());
+        itself.add_dependency(itself.id.clone());
+        assert!(queue.enqueue(itself).await.is_err());
+
+        assert!(queue.enqueue(first.clone()).await.is_err());
+
+        let mut second = task(RetryPolicy::default());
+        second.add_dependency(first.id.clone());
+        queue.enqueue(second.clone()).await.unwrap();
+        assert_eq!(queue.topological_order().await.unwrap(), vec![first.id, second.id]);
+    }
+
+    #[tokio::test]
+    async fn test_failure_fails_dependents() {
+        let queue = TaskQueue::new();
+        let upstream = task(RetryPolicy::none());
+        let mut child = task(RetryPolicy::default());
+        child.add_dependency(upstream.id.clone());
+        let mut grandchild = task(RetryPolicy::default());
+        grandchild.add_dependency(child.id.clone());
+
+        for task in [&upstream, &child, &grandchild] {
+            queue.enqueue(task.clone()).await.unwrap();
+        }
+        let (path, length) = queue.critical_path(|_| 1).await.unwrap();
+        assert_eq!(path.len(), 3);
+        assert_eq!(length, 3);
+
+        let started = queue.dequeue().await.unwrap();
+        queue.mark_in_progress(started).await.unwrap();
+        let dependents = queue.mark_failed(&upstream.id, "boom".into()).await.unwrap();
+        assert_eq!(dependents, vec![child.id.clone(), grandchild.id.clone()]);
+        assert!(matches!(
+            queue.get_task_status(&grandchild.id).await,
+            Some(TaskStatus::Failed(_))
+        ));
+        assert!(queue.dequeue().await.is_none());
+
+        // 依赖已失败任务的新任务不会入队
+        let mut late = task(RetryPolicy::default());
+        late.add_dependency(upstream.id.clone());
+        assert!(queue.enqueue(late).await.is_err());
+    }
+}
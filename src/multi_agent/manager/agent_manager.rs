@@ -1,8 +1,12 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+};
 
 use tokio::{
-    sync::{RwLock, oneshot},
+    sync::{RwLock, broadcast, oneshot},
     task::JoinHandle,
+    time::{Interval, MissedTickBehavior},
 };
 use tracing::{debug, error, info, warn};
 
@@ -13,10 +17,13 @@ use crate::{
     },
     error::{Error, Result, agent_error::AgentError},
     multi_agent::{
-        communication::{Message, MessageBus, MessageBusConfig, MessageReceiver, MessageType},
+        communication::{
+            Message, MessageBus, MessageBusConfig, MessageReceiver, MessageType,
+            message::ControlCommand,
+        },
         registry::{AgentInfo, AgentRegistry, RegistryConfig},
     },
-    shared::GlobalContext,
+    shared::{GlobalContext, TaskCancellations},
 };
 
 /// Agent manager configuration
@@ -39,11 +46,19 @@ impl Default for AgentManagerConfig {
     }
 }
 
+/// Notification emitted when an Agent task dies abnormally
+#[derive(Debug, Clone)]
+pub struct AgentCrash {
+    pub agent_id: String,
+    pub agent_type: AgentType,
+    pub reason: String,
+}
+
 /// Agent runtime information
 struct AgentRuntime {
     task_handle: Option<JoinHandle<()>>,
     shutdown_tx: Option<oneshot::Sender<()>>,
-    state: AgentLifecycleState,
+    state: Arc<RwLock<AgentLifecycleState>>,
     agent_id: String,
     agent_type: AgentType,
 }
@@ -62,6 +77,8 @@ pub struct AgentManager {
     config: AgentManagerConfig,
     /// Manager state
     running: Arc<RwLock<bool>>,
+    /// Crash notifications (consumed by supervisors)
+    crash_tx: broadcast::Sender<AgentCrash>,
 }
 
 impl AgentManager {
@@ -73,6 +90,8 @@ impl AgentManager {
         // Start registry cleanup task
         registry.clone().start_cleanup_task();
 
+        let (crash_tx, _) = broadcast::channel(64);
+
         Self {
             agents: Arc::new(RwLock::new(HashMap::new())),
             message_bus,
@@ -80,6 +99,7 @@ impl AgentManager {
             context,
             config,
             running: Arc::new(RwLock::new(true)),
+            crash_tx,
         }
     }
 
@@ -101,6 +121,11 @@ impl AgentManager {
 
         // Register to message bus
         let message_receiver = self.message_bus.register_agent(agent_id.clone()).await?;
+        for pattern in agent.topic_subscriptions() {
+            if let Err(e) = self.message_bus.subscribe(&agent_id, &pattern).await {
+                warn!("Agent {} could not subscribe to {}: {:?}", agent_id, pattern, e);
+            }
+        }
 
         // Register to registry
         let agent_info = AgentInfo::new(agent_id.clone(), agent_type, capabilities);
@@ -109,22 +134,36 @@ impl AgentManager {
         // Create shutdown channel
         let (shutdown_tx, shutdown_rx) = oneshot::channel();
 
+        // Lifecycle state shared between the manager and the Agent task
+        let state = Arc::new(RwLock::new(AgentLifecycleState::Running));
+
+        // Start heartbeat task
+        let heartbeat_handle = Self::start_heartbeat_task(agent_id.clone(), self.registry.clone());
+
         // Start Agent task
-        let agent_id_clone = agent_id.clone();
-        let message_bus = self.message_bus.clone();
-        let registry = self.registry.clone();
-
-        let task_handle = tokio::spawn(async move {
-            Self::agent_loop(
-                agent,
-                agent_id_clone,
-                message_receiver,
-                message_bus,
-                registry,
-                shutdown_rx,
-            )
-            .await;
-        });
+        let loop_handle = tokio::spawn(Self::agent_loop(
+            agent,
+            agent_id.clone(),
+            message_receiver,
+            self.message_bus.clone(),
+            self.registry.clone(),
+            state.clone(),
+            self.context.cancellations.clone(),
+            shutdown_rx,
+        ));
+
+        // Watch the Agent task so errors and panics are reported as crashes
+        let task_handle = tokio::spawn(Self::watch_agent(
+            self.agents.clone(),
+            loop_handle,
+            heartbeat_handle,
+            agent_id.clone(),
+            agent_type,
+            state.clone(),
+            self.message_bus.clone(),
+            self.registry.clone(),
+            self.crash_tx.clone(),
+        ));
 
         // Save runtime information
         // Note: agent has been moved to task, we create a placeholder here
@@ -132,7 +171,7 @@ impl AgentManager {
         let runtime = AgentRuntime {
             task_handle: Some(task_handle),
             shutdown_tx: Some(shutdown_tx),
-            state: AgentLifecycleState::Running,
+            state,
             agent_id: agent_id.clone(),
             agent_type,
         };
@@ -143,69 +182,269 @@ impl AgentManager {
         Ok(agent_id)
     }
 
+    /// Wait for an Agent task to finish and publish a crash if it did not stop cleanly
+    ///
+    /// An Agent that stopped on its own, e.g. after a `Stop` command, is removed from the
+    /// manager, the registry and the message bus. A crashed one is kept, marked as failed,
+    /// until it is terminated or restarted.
+    #[allow(clippy::too_many_arguments)]
+    async fn watch_agent(
+        agents: Arc<RwLock<HashMap<String, AgentRuntime>>>,
+        loop_handle: JoinHandle<Result<()>>,
+        heartbeat_handle: JoinHandle<()>,
+        agent_id: String,
+        agent_type: AgentType,
+        state: Arc<RwLock<AgentLifecycleState>>,
+        message_bus: Arc<MessageBus>,
+        registry: Arc<AgentRegistry>,
+        crash_tx: broadcast::Sender<AgentCrash>,
+    ) {
+        let outcome = loop_handle.await;
+        heartbeat_handle.abort();
+
+        let reason = match outcome {
+            Ok(Ok(())) => {
+                // Unless `terminate_agent` took the runtime out and cleans up itself
+                let current = {
+                    let mut agents = agents.write().await;
+                    let is_current = agents
+                        .get(&agent_id)
+                        .is_some_and(|runtime| Arc::ptr_eq(&runtime.state, &state));
+                    is_current && agents.remove(&agent_id).is_some()
+                };
+                if current {
+                    Self::unregister(&agent_id, &registry, &message_bus).await;
+                    info!("Agent {} stopped and was removed", agent_id);
+                }
+                return;
+            }
+            Ok(Err(e)) => e.to_string(),
+            Err(e) if e.is_panic() => format!("panicked: {e}"),
+            Err(e) => format!("task aborted: {e}"),
+        };
+
+        error!("Agent {} crashed: {}", agent_id, reason);
+        *state.write().await = AgentLifecycleState::Failed;
+        let _ = registry.update_status(&agent_id, AgentStatus::Failed).await;
+
+        let notice = Message::broadcast(
+            "agent-manager".to_string(),
+            MessageType::Custom("AgentCrashed".to_string()),
+            serde_json::json!({
+                "agent_id": agent_id,
+                "agent_type": agent_type,
+                "reason": reason,
+            }),
+        );
+        if let Err(e) = message_bus.send(notice).await {
+            debug!("No listeners for crash of agent {}: {:?}", agent_id, e);
+        }
+
+        let _ = crash_tx.send(AgentCrash {
+            agent_id,
+            agent_type,
+            reason,
+        });
+    }
+
     /// Agent main loop
+    ///
+    /// This task owns the agent: inbound bus messages are dispatched through
+    /// `handle_agent_message` between calls of `agent.tick()`, and any reply the
+    /// agent returns is sent back over the message bus. Control commands drive
+    /// the lifecycle state: a paused agent buffers messages until resumed.
+    /// While a message is being handled, incoming messages are peeked at so a
+    /// `TaskCancellation` reaches a task that is already running; they stay in the
+    /// inbox and are handled in their turn. A cancellation reaching a paused agent
+    /// is applied at once as well.
+    /// Only the wait for the next message or tick is raced, with shutdown checked
+    /// first; handlers and ticks always run to completion. An error returned from
+    /// `tick()` ends the loop and is reported as a crash.
+    #[allow(clippy::too_many_arguments)]
     async fn agent_loop(
         mut agent: Box<dyn AgentBehavior>,
         agent_id: String,
         mut message_receiver: MessageReceiver,
-        _message_bus: Arc<MessageBus>,
+        message_bus: Arc<MessageBus>,
         registry: Arc<AgentRegistry>,
+        state: Arc<RwLock<AgentLifecycleState>>,
+        cancellations: TaskCancellations,
         mut shutdown_rx: oneshot::Receiver<()>,
-    ) {
+    ) -> Result<()> {
         info!("Agent {} started", agent_id);
 
-        // Start heartbeat task
-        let heartbeat_handle = Self::start_heartbeat_task(agent_id.clone(), registry.clone());
-
-        // Split Agent into two parts: one for running, one for message processing
-        let agent_id_for_run = agent_id.clone();
-
-        // Create a channel to coordinate agent.run() execution
-        let (run_tx, run_rx) = tokio::sync::oneshot::channel::<()>();
-
-        // Start a task to run agent.run()
-        tokio::spawn(async move {
-            tokio::select! {
-                result = agent.run() => {
-                    if let Err(e) = result {
-                        error!("Agent {} run error: {:?}", agent_id_for_run, e);
-                    }
-                }
-                _ = run_rx => {
-                    // Received stop signal, execute shutdown logic
-                    if let Err(e) = agent.shutdown().await {
-                        error!("Agent {} shutdown error: {:?}", agent_id_for_run, e);
-                    }
-                }
-            }
+        let mut ticker = agent.tick_interval().map(|period| {
+            let mut ticker = tokio::time::interval(period);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            ticker
         });
+        let mut paused = false;
+        let mut buffered: VecDeque<Message> = VecDeque::new();
+        let mut crash = None;
 
-        // Message processing loop
         loop {
-            tokio::select! {
-                // Receive message
-                Some(msg) = message_receiver.recv() => {
-                    // Since agent has been moved, we can only log
-                    debug!("Agent {} received message: {:?}", agent_id, msg.message_type);
-                    // TODO: Consider using message forwarding mechanism instead of direct processing
-                }
+            let draining = !paused && !buffered.is_empty();
+            let message = tokio::select! {
+                biased;
+
                 // Shutdown signal
                 _ = &mut shutdown_rx => {
                     info!("Agent {} received shutdown signal", agent_id);
                     break;
                 }
+                // Messages buffered while paused
+                _ = std::future::ready(()), if draining => match buffered.pop_front() {
+                    Some(message) => message,
+                    None => continue,
+                },
+                // Receive message
+                received = message_receiver.recv() => {
+                    let Some(msg) = received else {
+                        warn!("Agent {} message channel closed", agent_id);
+                        break;
+                    };
+                    Arc::unwrap_or_clone(msg)
+                }
+                // Background work of the agent
+                _ = Self::next_tick(&mut ticker), if !paused => {
+                    if let Err(e) = agent.tick().await {
+                        error!("Agent {} tick error: {:?}", agent_id, e);
+                        crash = Some(e);
+                        break;
+                    }
+                    continue;
+                }
+            };
+
+            debug!("Agent {} received message: {:?}", agent_id, message.message_type);
+
+            if let MessageType::Control(cmd) = &message.message_type {
+                match cmd {
+                    ControlCommand::Pause => {
+                        if !paused {
+                            paused = true;
+                            *state.write().await = AgentLifecycleState::Paused;
+                            info!("Agent {} paused", agent_id);
+                        }
+                    }
+                    ControlCommand::Start | ControlCommand::Resume => {
+                        if paused {
+                            paused = false;
+                            *state.write().await = AgentLifecycleState::Running;
+                            info!(
+                                "Agent {} resumed, draining {} buffered messages",
+                                agent_id,
+                                buffered.len()
+                            );
+                        }
+                    }
+                    ControlCommand::Stop | ControlCommand::Shutdown => {
+                        info!("Agent {} received {:?} command", agent_id, cmd);
+                        break;
+                    }
+                }
+                continue;
+            }
+
+            if paused {
+                Self::apply_cancellation(&message, &cancellations);
+                buffered.push_back(message);
+            } else {
+                Self::observe_cancellations(
+                    Self::handle_or_log(&mut agent, &agent_id, message, &message_bus, &registry),
+                    &mut message_receiver,
+                    &cancellations,
+                )
+                .await;
             }
         }
 
         // Cleanup work
-        heartbeat_handle.abort();
-        let _ = run_tx.send(()); // Notify agent task to stop
+        *state.write().await = AgentLifecycleState::Stopping;
+
+        if !buffered.is_empty() {
+            warn!("Agent {} dropping {} buffered messages", agent_id, buffered.len());
+        }
+
+        let final_state = match agent.shutdown().await {
+            Ok(()) => AgentLifecycleState::Stopped,
+            Err(e) => {
+                error!("Agent {} shutdown error: {:?}", agent_id, e);
+                AgentLifecycleState::Failed
+            }
+        };
+        *state.write().await = final_state;
 
         info!("Agent {} stopped", agent_id);
+        crash.map_or(Ok(()), Err)
+    }
+
+    /// Wait for the next tick; never completes for agents without background work
+    async fn next_tick(ticker: &mut Option<Interval>) {
+        match ticker {
+            Some(ticker) => {
+                ticker.tick().await;
+            }
+            None => std::future::pending().await,
+        }
+    }
+
+    /// Drive `handling` to completion while peeking at incoming messages
+    ///
+    /// A `TaskCancellation`, already queued or arriving meanwhile, cancels the task's token
+    /// right away so the running task can stop early. The messages stay in the inbox, which
+    /// keeps their priority order and the bus's backpressure.
+    async fn observe_cancellations(
+        handling: impl Future<Output = ()>,
+        message_receiver: &mut MessageReceiver,
+        cancellations: &TaskCancellations,
+    ) {
+        tokio::pin!(handling);
+        for message in message_receiver.queued() {
+            Self::apply_cancellation(message, cancellations);
+        }
+
+        let mut inbox_open = true;
+        loop {
+            tokio::select! {
+                biased;
+
+                _ = &mut handling => return,
+                incoming = message_receiver.peek_incoming(), if inbox_open => match incoming {
+                    Some(message) => Self::apply_cancellation(&message, cancellations),
+                    None => inbox_open = false,
+                },
+            }
+        }
+    }
+
+    /// Cancel the task a `TaskCancellation` refers to
+    fn apply_cancellation(message: &Message, cancellations: &TaskCancellations) {
+        let task_id = message.payload.get("task_id").and_then(|v| v.as_str());
+        if message.message_type == MessageType::TaskCancellation
+            && let Some(task_id) = task_id
+        {
+            info!("Cancelling task {}", task_id);
+            cancellations.cancel(task_id);
+        }
+    }
+
+    /// Handle a message, logging failures instead of stopping the loop
+    async fn handle_or_log(
+        agent: &mut Box<dyn AgentBehavior>,
+        agent_id: &str,
+        message: Message,
+        message_bus: &Arc<MessageBus>,
+        registry: &Arc<AgentRegistry>,
+    ) {
+        if let Err(e) = Self::handle_agent_message(agent, message, message_bus, registry).await {
+            error!("Agent {} failed to handle message: {:?}", agent_id, e);
+        }
     }
 
     /// Process Agent messages
-    #[allow(dead_code)]
+    ///
+    /// Control commands are consumed by `agent_loop` before reaching this point.
     async fn handle_agent_message(
         agent: &mut Box<dyn AgentBehavior>,
         message: Message,
@@ -213,35 +452,48 @@ impl AgentManager {
         registry: &Arc<AgentRegistry>,
     ) -> Result<()> {
         match &message.message_type {
-            MessageType::Control(cmd) => {
-                // Process control command
-                match cmd {
-                    crate::multi_agent::communication::message::ControlCommand::Stop => {
-                        // Handled externally
-                    }
-                    _ => {
-                        warn!("Unhandled control command: {:?}", cmd);
-                    }
-                }
-            }
             MessageType::StatusUpdate => {
-                // Update Agent status
+                // Update the reporting Agent's status, then let the receiver see the update too
                 if let Some(status) = message.payload.get("status")
-                    && let Ok(status) = serde_json::from_value::<AgentStatus>(status.clone()) {
-                        registry.update_status(agent.get_id(), status).await?;
+                    && let Ok(status) = serde_json::from_value::<AgentStatus>(status.clone())
+                {
+                    let reporter_id = message
+                        .payload
+                        .get("agent_id")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or(&message.sender_id);
+                    if let Err(e) = registry.update_status(reporter_id, status).await {
+                        debug!("Status update for unknown agent {}: {:?}", reporter_id, e);
                     }
+                }
+                Self::dispatch_to_agent(agent, message, message_bus).await?;
             }
             _ => {
                 // Let Agent process other messages
-                if let Some(response) = agent.process_message(message).await? {
-                    message_bus.send(response).await?;
-                }
+                Self::dispatch_to_agent(agent, message, message_bus).await?;
             }
         }
 
         Ok(())
     }
 
+    /// Hand a message to the Agent and send back any response it produces
+    async fn dispatch_to_agent(
+        agent: &mut Box<dyn AgentBehavior>,
+        message: Message,
+        message_bus: &Arc<MessageBus>,
+    ) -> Result<()> {
+        let request_id = message.id.clone();
+        if let Some(mut response) = agent.process_message(message).await? {
+            // Correlate the reply so callers waiting in `MessageBus::request` receive it
+            if response.correlation_id.is_none() {
+                response.correlation_id = Some(request_id);
+            }
+            message_bus.send(response).await?;
+        }
+        Ok(())
+    }
+
     /// Start heartbeat task
     fn start_heartbeat_task(agent_id: String, registry: Arc<AgentRegistry>) -> JoinHandle<()> {
         tokio::spawn(async move {
@@ -259,31 +511,36 @@ impl AgentManager {
 
     /// Terminate Agent
     pub async fn terminate_agent(&self, agent_id: &str) -> Result<()> {
-        let mut agents = self.agents.write().await;
-
-        if let Some(mut runtime) = agents.remove(agent_id) {
-            // Send shutdown signal
-            if let Some(tx) = runtime.shutdown_tx.take() {
-                let _ = tx.send(());
-            }
+        // Not holding the lock while waiting for the Agent to stop
+        let runtime = self.agents.write().await.remove(agent_id);
+        let Some(mut runtime) = runtime else {
+            return Err(Error::AgentError(AgentError::AgentNotFound(
+                agent_id.to_string(),
+            )));
+        };
 
-            // Wait for task to finish
-            if let Some(handle) = runtime.task_handle.take() {
-                let _ = tokio::time::timeout(std::time::Duration::from_secs(10), handle).await;
-            }
+        // Send shutdown signal
+        if let Some(tx) = runtime.shutdown_tx.take() {
+            let _ = tx.send(());
+        }
 
-            // Remove from registry
-            self.registry.unregister(agent_id).await?;
+        // Wait for task to finish
+        if let Some(handle) = runtime.task_handle.take() {
+            let _ = tokio::time::timeout(std::time::Duration::from_secs(10), handle).await;
+        }
 
-            // Remove from message bus
-            self.message_bus.unregister_agent(agent_id).await?;
+        Self::unregister(agent_id, &self.registry, &self.message_bus).await;
+        info!("Agent {} terminated", agent_id);
+        Ok(())
+    }
 
-            info!("Agent {} terminated", agent_id);
-            Ok(())
-        } else {
-            Err(Error::AgentError(AgentError::AgentNotFound(
-                agent_id.to_string(),
-            )))
+    /// Remove an Agent from the registry and the message bus
+    async fn unregister(agent_id: &str, registry: &AgentRegistry, message_bus: &MessageBus) {
+        if let Err(e) = registry.unregister(agent_id).await {
+            debug!("Agent {} was not registered: {:?}", agent_id, e);
+        }
+        if let Err(e) = message_bus.unregister_agent(agent_id).await {
+            warn!("Agent {} could not be removed from the message bus: {:?}", agent_id, e);
         }
     }
 
@@ -295,7 +552,7 @@ impl AgentManager {
             Ok(serde_json::json!({
                 "id": runtime.agent_id,
                 "type": runtime.agent_type,
-                "state": runtime.state,
+                "state": *runtime.state.read().await,
             }))
         } else {
             Err(Error::AgentError(AgentError::AgentNotFound(
@@ -306,18 +563,69 @@ impl AgentManager {
 
     /// Get status of all Agents
     pub async fn get_all_agent_status(&self) -> Vec<serde_json::Value> {
-        self.agents
-            .read()
-            .await
-            .values()
-            .map(|runtime| {
-                serde_json::json!({
-                    "id": runtime.agent_id,
-                    "type": runtime.agent_type,
-                    "state": runtime.state,
-                })
-            })
-            .collect()
+        let agents = self.agents.read().await;
+        let mut statuses = Vec::with_capacity(agents.len());
+
+        for runtime in agents.values() {
+            statuses.push(serde_json::json!({
+                "id": runtime.agent_id,
+                "type": runtime.agent_type,
+                "state": *runtime.state.read().await,
+            }));
+        }
+
+        statuses
+    }
+
+    /// Get the lifecycle state of an Agent
+    pub async fn get_agent_state(&self, agent_id: &str) -> Result<AgentLifecycleState> {
+        let agents = self.agents.read().await;
+        let runtime = agents
+            .get(agent_id)
+            .ok_or_else(|| Error::AgentError(AgentError::AgentNotFound(agent_id.to_string())))?;
+        Ok(*runtime.state.read().await)
+    }
+
+    /// Send a control command to an Agent
+    pub async fn send_control(&self, agent_id: &str, command: ControlCommand) -> Result<()> {
+        if !self.agents.read().await.contains_key(agent_id) {
+            return Err(Error::AgentError(AgentError::AgentNotFound(
+                agent_id.to_string(),
+            )));
+        }
+
+        let message = Message::new(
+            "agent-manager".to_string(),
+            Some(agent_id.to_string()),
+            MessageType::Control(command),
+            serde_json::json!({}),
+        );
+        self.message_bus.send(message).await
+    }
+
+    /// Pause an Agent; messages are buffered until it is resumed
+    pub async fn pause_agent(&self, agent_id: &str) -> Result<()> {
+        self.send_control(agent_id, ControlCommand::Pause).await
+    }
+
+    /// Resume a paused Agent
+    pub async fn resume_agent(&self, agent_id: &str) -> Result<()> {
+        self.send_control(agent_id, ControlCommand::Resume).await
+    }
+
+    /// Subscribe to crash notifications of managed Agents
+    pub fn subscribe_crashes(&self) -> broadcast::Receiver<AgentCrash> {
+        self.crash_tx.subscribe()
+    }
+
+    /// Get the message bus shared by all managed Agents
+    pub fn get_message_bus(&self) -> Arc<MessageBus> {
+        self.message_bus.clone()
+    }
+
+    /// Get the Agent registry
+    pub fn get_registry(&self) -> Arc<AgentRegistry> {
+        self.registry.clone()
     }
 
     /// Send message to specific Agent
@@ -325,6 +633,15 @@ impl AgentManager {
         self.message_bus.send(message).await
     }
 
+    /// Send a request to an Agent and wait for its correlated response
+    pub async fn send_request(
+        &self,
+        message: Message,
+        timeout: std::time::Duration,
+    ) -> Result<Message> {
+        self.message_bus.request(message, timeout).await
+    }
+
     /// Broadcast message to all Agents
     pub async fn broadcast_message(&self, message: Message) -> Result<()> {
         self.message_bus.send(message).await
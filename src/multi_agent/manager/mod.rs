@@ -1,3 +1,5 @@
 pub mod agent_manager;
+pub mod supervisor;
 
-pub use agent_manager::{AgentManager, AgentManagerConfig, ManagerStats};
\ No newline at end of file
+pub use agent_manager::{AgentCrash, AgentManager, AgentManagerConfig, ManagerStats};
+pub use supervisor::{AgentFactory, RestartStrategy, Supervisor, SupervisorConfig};
\ No newline at end of file
@@ -0,0 +1,348 @@
+use std::{
+    collections::VecDeque,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use tokio::{
+    sync::{RwLock, broadcast::error::RecvError},
+    task::JoinHandle,
+};
+use tracing::{error, info, warn};
+
+use crate::{
+    agent::core::base_agent::AgentBehavior,
+    error::Result,
+    multi_agent::{
+        communication::{Message, MessageType},
+        manager::agent_manager::{AgentCrash, AgentManager},
+    },
+};
+
+/// Builds a fresh Agent instance when a supervised child has to be restarted
+pub type AgentFactory = Arc<dyn Fn() -> Box<dyn AgentBehavior> + Send + Sync>;
+
+/// Which children are restarted when one of them crashes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartStrategy {
+    /// Restart only the crashed child
+    OneForOne,
+    /// Restart every child
+    OneForAll,
+    /// Restart the crashed child and every child started after it
+    RestForOne,
+}
+
+/// Supervisor configuration
+#[derive(Debug, Clone)]
+pub struct SupervisorConfig {
+    pub strategy: RestartStrategy,
+    /// Maximum number of restarts allowed within `restart_window`
+    pub max_restarts: usize,
+    pub restart_window: Duration,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            strategy: RestartStrategy::OneForOne,
+            max_restarts: 3,
+            restart_window: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Supervised child specification
+struct ChildSpec {
+    agent_id: String,
+    factory: AgentFactory,
+}
+
+/// Supervisor that restarts crashed Agents according to a restart strategy
+pub struct Supervisor {
+    manager: Arc<AgentManager>,
+    config: SupervisorConfig,
+    /// Children in start order
+    children: RwLock<Vec<ChildSpec>>,
+    /// Timestamps of recent restarts (for restart intensity)
+    restarts: RwLock<VecDeque<Instant>>,
+    /// Sender ID used for bus notifications
+    id: String,
+}
+
+impl Supervisor {
+    pub fn new(manager: Arc<AgentManager>, config: SupervisorConfig) -> Self {
+        Self {
+            manager,
+            config,
+            children: RwLock::new(Vec::new()),
+            restarts: RwLock::new(VecDeque::new()),
+            id: format!("supervisor-{}", uuid::Uuid::new_v4().simple()),
+        }
+    }
+
+    /// Spawn a supervised Agent built by `factory`
+    ///
+    /// The children lock is held across the spawn, so a crash on the child's first tick is
+    /// only handled once the child is known.
+    pub async fn start_child(&self, factory: AgentFactory) -> Result<String> {
+        let mut children = self.children.write().await;
+        let agent_id = self.manager.spawn_agent(factory()).await?;
+        children.push(ChildSpec {
+            agent_id: agent_id.clone(),
+            factory,
+        });
+        info!("Supervisor {} started child {}", self.id, agent_id);
+        Ok(agent_id)
+    }
+
+    /// IDs of supervised children in start order
+    pub async fn children(&self) -> Vec<String> {
+        self.children
+            .read()
+            .await
+            .iter()
+            .map(|child| child.agent_id.clone())
+            .collect()
+    }
+
+    /// Start watching for crashes; the task ends when the restart intensity is exceeded
+    pub fn start(self: Arc<Self>) -> JoinHandle<()> {
+        let mut crashes = self.manager.subscribe_crashes();
+
+        tokio::spawn(async move {
+            loop {
+                match crashes.recv().await {
+                    Ok(crash) => {
+                        if !self.handle_crash(crash).await {
+                            break;
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("Supervisor {} missed {} crash notifications", self.id, skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+            info!("Supervisor {} stopped", self.id);
+        })
+    }
+
+    /// Apply the restart strategy; returns false when the supervisor gives up
+    async fn handle_crash(&self, crash: AgentCrash) -> bool {
+        let crashed_index = {
+            let children = self.children.read().await;
+            match children.iter().position(|c| c.agent_id == crash.agent_id) {
+                Some(index) => index,
+                None => return true,
+            }
+        };
+
+        if !self.record_restart().await {
+            error!(
+                "Supervisor {} exceeded {} restarts in {:?}, shutting down children",
+                self.id, self.config.max_restarts, self.config.restart_window
+            );
+            for agent_id in self.children().await {
+                let _ = self.manager.terminate_agent(&agent_id).await;
+            }
+            self.children.write().await.clear();
+            return false;
+        }
+
+        let mut children = self.children.write().await;
+        let targets: Vec<usize> = match self.config.strategy {
+            RestartStrategy::OneForOne => vec![crashed_index],
+            RestartStrategy::OneForAll => (0 .. children.len()).collect(),
+            RestartStrategy::RestForOne => (crashed_index .. children.len()).collect(),
+        };
+
+        // Stop affected children in reverse start order
+        for &index in targets.iter().rev() {
+            let agent_id = &children[index].agent_id;
+            if let Err(e) = self.manager.terminate_agent(agent_id).await {
+                warn!("Supervisor {} could not terminate {}: {:?}", self.id, agent_id, e);
+            }
+        }
+
+        // Restart them in start order
+        for &index in &targets {
+            let child = &mut children[index];
+            let previous_id = child.agent_id.clone();
+            match self.manager.spawn_agent((child.factory)()).await {
+                Ok(agent_id) => {
+                    info!("Supervisor {} restarted {} as {}", self.id, previous_id, agent_id);
+                    child.agent_id = agent_id.clone();
+                    self.notify_restart(&previous_id, &agent_id, &crash).await;
+                }
+                Err(e) => {
+                    error!("Supervisor {} failed to restart {}: {:?}", self.id, previous_id, e);
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Record a restart; false if the restart intensity would be exceeded
+    async fn record_restart(&self) -> bool {
+        let now = Instant::now();
+        let mut restarts = self.restarts.write().await;
+
+        while let Some(&oldest) = restarts.front() {
+            if now.duration_since(oldest) > self.config.restart_window {
+                restarts.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        if restarts.len() >= self.config.max_restarts {
+            return false;
+        }
+
+        restarts.push_back(now);
+        true
+    }
+
+    /// Broadcast a restart notification on the bus
+    async fn notify_restart(&self, previous_id: &str, agent_id: &str, crash: &AgentCrash) {
+        let notice = Message::broadcast(
+            self.id.clone(),
+            MessageType::Custom("AgentRestarted".to_string()),
+            serde_json::json!({
+                "agent_id": agent_id,
+                "previous_id": previous_id,
+                "crashed_agent_id": crash.agent_id,
+                "strategy": format!("{:?}", self.config.strategy),
+            }),
+        );
+        let _ = self.manager.send_message(notice).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::{
+        agent::{
+            core::base_agent::BaseAgent,
+            types::{AgentCapability, AgentLifecycleState, AgentType},
+        },
+        error::{Error, agent_error::AgentError},
+        multi_agent::manager::AgentManagerConfig,
+        shared::GlobalContext,
+    };
+
+    /// Agent whose `run()` fails while the shared budget of failures lasts
+    struct FlakyAgent {
+        base: BaseAgent,
+        failures_left: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl AgentBehavior for FlakyAgent {
+        fn get_id(&self) -> &str {
+            &self.base.id
+        }
+
+        fn get_type(&self) -> AgentType {
+            self.base.agent_type
+        }
+
+        fn get_capabilities(&self) -> &[AgentCapability] {
+            &self.base.capabilities
+        }
+
+        async fn initialize(&mut self, context: Arc<GlobalContext>) -> Result<()> {
+            self.base.context = Some(context);
+            Ok(())
+        }
+
+        async fn process_message(&mut self, _message: Message) -> Result<Option<Message>> {
+            Ok(None)
+        }
+
+        fn tick_interval(&self) -> Option<Duration> {
+            Some(Duration::from_millis(10))
+        }
+
+        async fn tick(&mut self) -> Result<()> {
+            let remaining = self.failures_left.load(Ordering::SeqCst);
+            if remaining > 0 {
+                self.failures_left.store(remaining - 1, Ordering::SeqCst);
+                return Err(Error::AgentError(AgentError::ExecutionError("boom".into())));
+            }
+            Ok(())
+        }
+
+        async fn shutdown(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn flaky_factory(id: &'static str, failures_left: Arc<AtomicUsize>) -> AgentFactory {
+        Arc::new(move || {
+            Box::new(FlakyAgent {
+                base: BaseAgent::new(id.to_string(), AgentType::Custom("flaky"), vec![]),
+                failures_left: failures_left.clone(),
+            }) as Box<dyn AgentBehavior>
+        })
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_one_for_one_restart() {
+        let manager = Arc::new(AgentManager::new(
+            Arc::new(GlobalContext::default()),
+            AgentManagerConfig::default(),
+        ));
+        let supervisor = Arc::new(Supervisor::new(manager.clone(), SupervisorConfig::default()));
+        let _handle = supervisor.clone().start();
+
+        let failures = Arc::new(AtomicUsize::new(1));
+        let agent_id = supervisor
+            .start_child(flaky_factory("flaky-1", failures.clone()))
+            .await
+            .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        assert_eq!(failures.load(Ordering::SeqCst), 0);
+        assert_eq!(supervisor.children().await, vec![agent_id.clone()]);
+        assert_eq!(
+            manager.get_agent_state(&agent_id).await.unwrap(),
+            AgentLifecycleState::Running
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restart_intensity_exceeded() {
+        let manager = Arc::new(AgentManager::new(
+            Arc::new(GlobalContext::default()),
+            AgentManagerConfig::default(),
+        ));
+        let config = SupervisorConfig {
+            max_restarts: 2,
+            ..Default::default()
+        };
+        let supervisor = Arc::new(Supervisor::new(manager.clone(), config));
+        let handle = supervisor.clone().start();
+
+        let failures = Arc::new(AtomicUsize::new(usize::MAX));
+        supervisor
+            .start_child(flaky_factory("flaky-2", failures))
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), handle)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(supervisor.children().await.is_empty());
+        assert!(manager.get_agent_state("flaky-2").await.is_err());
+    }
+}
@@ -2,8 +2,9 @@ pub mod communication;
 pub mod coordination;
 pub mod manager;
 pub mod registry;
+pub mod transport;
 
 // Re-export commonly used types
 pub use communication::{Message, MessageBus, MessageBusConfig, MessageReceiver, MessageType};
-pub use manager::{AgentManager, AgentManagerConfig};
+pub use manager::{AgentManager, AgentManagerConfig, RestartStrategy, Supervisor, SupervisorConfig};
 pub use registry::{AgentInfo, AgentRegistry, RegistryConfig};
\ No newline at end of file
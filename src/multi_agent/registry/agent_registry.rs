@@ -7,6 +7,10 @@ use tracing::{info, warn};
 use crate::agent::types::{AgentCapability, AgentStatus, AgentType};
 use crate::error::{Result, Error};
 use crate::error::agent_error::AgentError;
+use crate::multi_agent::registry::selection::{SelectionState, SelectionStrategy};
+
+/// Metadata field holding how many tasks an agent takes before it counts as busy
+pub const CAPACITY_METADATA_KEY: &str = "max_concurrent_tasks";
 
 /// Agent information
 #[derive(Debug, Clone)]
@@ -17,6 +21,8 @@ pub struct AgentInfo {
     pub status: AgentStatus,
     pub last_heartbeat: DateTime<Utc>,
     pub metadata: serde_json::Value,
+    /// Tasks dispatched to the agent that have not finished yet
+    pub outstanding_tasks: usize,
 }
 
 impl AgentInfo {
@@ -28,9 +34,24 @@ impl AgentInfo {
             status: AgentStatus::Active,
             last_heartbeat: Utc::now(),
             metadata: serde_json::json!({}),
+            outstanding_tasks: 0,
         }
     }
 
+    pub fn with_metadata(mut self, metadata: serde_json::Value) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Number of concurrent tasks before the agent is considered busy (default 1)
+    pub fn capacity(&self) -> usize {
+        self.metadata
+            .get(CAPACITY_METADATA_KEY)
+            .and_then(|v| v.as_u64())
+            .map(|v| v.max(1) as usize)
+            .unwrap_or(1)
+    }
+
     pub fn update_heartbeat(&mut self) {
         self.last_heartbeat = Utc::now();
     }
@@ -59,6 +80,7 @@ pub struct AgentRegistry {
     agents: Arc<RwLock<HashMap<String, AgentInfo>>>,
     capability_index: Arc<RwLock<HashMap<AgentCapability, Vec<String>>>>,
     type_index: Arc<RwLock<HashMap<AgentType, Vec<String>>>>,
+    selection: Arc<RwLock<SelectionState>>,
     config: RegistryConfig,
 }
 
@@ -68,6 +90,7 @@ impl AgentRegistry {
             agents: Arc::new(RwLock::new(HashMap::new())),
             capability_index: Arc::new(RwLock::new(HashMap::new())),
             type_index: Arc::new(RwLock::new(HashMap::new())),
+            selection: Arc::new(RwLock::new(SelectionState::default())),
             config,
         }
     }
@@ -184,6 +207,79 @@ impl AgentRegistry {
         }
     }
 
+    /// Select one available agent with `capability` using `strategy`
+    ///
+    /// Busy, failed, offline and dead agents are skipped.
+    pub async fn select(
+        &self,
+        capability: &AgentCapability,
+        strategy: &SelectionStrategy,
+    ) -> Option<AgentInfo> {
+        self.select_matching(std::slice::from_ref(capability), strategy, &[])
+            .await
+    }
+
+    /// Select one available agent holding every capability in `capabilities`
+    pub async fn select_matching(
+        &self,
+        capabilities: &[AgentCapability],
+        strategy: &SelectionStrategy,
+        exclude: &[&str],
+    ) -> Option<AgentInfo> {
+        let first = capabilities.first()?;
+        let timeout = Duration::seconds(self.config.heartbeat_timeout_secs);
+
+        let mut candidates: Vec<AgentInfo> = self
+            .find_by_capability(first)
+            .await
+            .into_iter()
+            .filter(|agent| !exclude.contains(&agent.id.as_str()))
+            .filter(|agent| capabilities.iter().all(|c| agent.capabilities.contains(c)))
+            .filter(|agent| {
+                matches!(agent.status, AgentStatus::Active | AgentStatus::Idle)
+                    && agent.is_alive(timeout)
+            })
+            .collect();
+        candidates.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let group = format!("{capabilities:?}");
+        self.selection
+            .write()
+            .await
+            .select(&group, &candidates, strategy)
+            .cloned()
+    }
+
+    /// Record a task dispatched to the agent; it becomes busy once its capacity is reached
+    pub async fn begin_task(&self, agent_id: &str) -> Result<()> {
+        let mut agents = self.agents.write().await;
+        let agent = agents
+            .get_mut(agent_id)
+            .ok_or_else(|| Error::AgentError(AgentError::AgentNotFound(agent_id.to_string())))?;
+
+        agent.outstanding_tasks += 1;
+        if agent.outstanding_tasks >= agent.capacity()
+            && matches!(agent.status, AgentStatus::Active | AgentStatus::Idle)
+        {
+            agent.status = AgentStatus::Busy;
+        }
+        Ok(())
+    }
+
+    /// Record a finished task; a busy agent becomes idle again once below capacity
+    pub async fn end_task(&self, agent_id: &str) -> Result<()> {
+        let mut agents = self.agents.write().await;
+        let agent = agents
+            .get_mut(agent_id)
+            .ok_or_else(|| Error::AgentError(AgentError::AgentNotFound(agent_id.to_string())))?;
+
+        agent.outstanding_tasks = agent.outstanding_tasks.saturating_sub(1);
+        if agent.outstanding_tasks < agent.capacity() && agent.status == AgentStatus::Busy {
+            agent.status = AgentStatus::Idle;
+        }
+        Ok(())
+    }
+
     pub async fn find_alive_agents(&self) -> Vec<AgentInfo> {
         let timeout = Duration::seconds(self.config.heartbeat_timeout_secs);
         self.agents
@@ -320,4 +416,118 @@ mod tests {
         assert_eq!(executors.len(), 1);
         assert_eq!(executors[0].id, "agent1");
     }
-}
\ No newline at end of file
+
+    async fn register_executors(registry: &AgentRegistry, ids: &[&str]) {
+        for id in ids {
+            registry
+                .register(AgentInfo::new(
+                    id.to_string(),
+                    AgentType::Executor,
+                    vec![AgentCapability::TaskExecution],
+                ))
+                .await
+                .unwrap();
+        }
+    }
+
+    async fn pick(registry: &AgentRegistry, strategy: &SelectionStrategy) -> String {
+        registry
+            .select(&AgentCapability::TaskExecution, strategy)
+            .await
+            .unwrap()
+            .id
+    }
+
+    #[tokio::test]
+    async fn test_select_round_robin() {
+        let registry = AgentRegistry::new(RegistryConfig::default());
+        register_executors(&registry, &["a", "b", "c"]).await;
+
+        let mut picked = Vec::new();
+        for _ in 0 .. 4 {
+            picked.push(pick(&registry, &SelectionStrategy::RoundRobin).await);
+        }
+        assert_eq!(picked, vec!["a", "b", "c", "a"]);
+    }
+
+    #[tokio::test]
+    async fn test_select_least_outstanding_skips_busy() {
+        let registry = AgentRegistry::new(RegistryConfig::default());
+        register_executors(&registry, &["a", "b"]).await;
+        registry
+            .register(
+                AgentInfo::new(
+                    "c".to_string(),
+                    AgentType::Executor,
+                    vec![AgentCapability::TaskExecution],
+                )
+                .with_metadata(serde_json::json!({"max_concurrent_tasks": 3})),
+            )
+            .await
+            .unwrap();
+        let strategy = SelectionStrategy::LeastOutstanding;
+
+        // a reaches its capacity of 1 and is skipped
+        registry.begin_task("a").await.unwrap();
+        registry.begin_task("c").await.unwrap();
+        assert_eq!(registry.get_agent("a").await.unwrap().status, AgentStatus::Busy);
+        assert_eq!(pick(&registry, &strategy).await, "b");
+
+        registry.begin_task("b").await.unwrap();
+        assert_eq!(pick(&registry, &strategy).await, "c");
+
+        registry.end_task("a").await.unwrap();
+        assert_eq!(registry.get_agent("a").await.unwrap().status, AgentStatus::Idle);
+        assert_eq!(pick(&registry, &strategy).await, "a");
+    }
+
+    #[tokio::test]
+    async fn test_select_weighted() {
+        let registry = AgentRegistry::new(RegistryConfig::default());
+        registry
+            .register(
+                AgentInfo::new(
+                    "heavy".to_string(),
+                    AgentType::Executor,
+                    vec![AgentCapability::TaskExecution],
+                )
+                .with_metadata(serde_json::json!({"weight": 3})),
+            )
+            .await
+            .unwrap();
+        register_executors(&registry, &["light"]).await;
+
+        let mut counts = HashMap::new();
+        for _ in 0 .. 8 {
+            *counts
+                .entry(pick(&registry, &SelectionStrategy::Weighted).await)
+                .or_insert(0) += 1;
+        }
+        assert_eq!(counts["heavy"], 6);
+        assert_eq!(counts["light"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_select_consistent_hash() {
+        let registry = AgentRegistry::new(RegistryConfig::default());
+        register_executors(&registry, &["a", "b", "c", "d"]).await;
+
+        let keys: Vec<String> = (0 .. 32).map(|i| format!("task-{i}")).collect();
+        let mut before = HashMap::new();
+        for key in &keys {
+            let strategy = SelectionStrategy::ConsistentHash(key.clone());
+            let first = pick(&registry, &strategy).await;
+            assert_eq!(pick(&registry, &strategy).await, first);
+            before.insert(key.clone(), first);
+        }
+
+        // Removing one agent only remaps the keys that were on it
+        registry.unregister("d").await.unwrap();
+        for key in &keys {
+            let after = pick(&registry, &SelectionStrategy::ConsistentHash(key.clone())).await;
+            if before[key] != "d" {
+                assert_eq!(after, before[key]);
+            }
+        }
+    }
+}
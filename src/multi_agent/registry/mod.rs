@@ -1,3 +1,5 @@
 pub mod agent_registry;
+pub mod selection;
 
-pub use agent_registry::{AgentInfo, AgentRegistry, RegistryConfig, RegistryStats};
\ No newline at end of file
+pub use agent_registry::{AgentInfo, AgentRegistry, RegistryConfig, RegistryStats};
+pub use selection::SelectionStrategy;
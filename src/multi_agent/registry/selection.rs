@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::multi_agent::registry::agent_registry::AgentInfo;
+
+/// Virtual nodes per agent on the consistent hash ring
+const VIRTUAL_NODES: u32 = 64;
+
+/// Metadata field holding an agent's weight for `SelectionStrategy::Weighted`
+pub const WEIGHT_METADATA_KEY: &str = "weight";
+
+/// How one agent is picked among several capable candidates
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum SelectionStrategy {
+    /// Cycle through the candidates in turn
+    RoundRobin,
+    /// Pick the candidate with the fewest outstanding tasks
+    #[default]
+    LeastOutstanding,
+    /// Smooth weighted round robin using the numeric `weight` metadata field (default 1)
+    Weighted,
+    /// Map the key to the same agent for as long as that agent is available
+    ConsistentHash(String),
+}
+
+/// Per-group state kept between selections
+#[derive(Debug, Default)]
+pub(crate) struct SelectionState {
+    cursors: HashMap<String, usize>,
+    current_weights: HashMap<String, HashMap<String, i64>>,
+}
+
+impl SelectionState {
+    /// Pick one of `candidates`, which must be sorted by id.
+    ///
+    /// `group` identifies the candidate set so round robin and weighted state is kept
+    /// separately per capability.
+    pub(crate) fn select<'a>(
+        &mut self,
+        group: &str,
+        candidates: &'a [AgentInfo],
+        strategy: &SelectionStrategy,
+    ) -> Option<&'a AgentInfo> {
+        if candidates.is_empty() {
+            return None;
+        }
+
+        match strategy {
+            SelectionStrategy::RoundRobin => {
+                let cursor = self.cursors.entry(group.to_string()).or_insert(0);
+                let selected = &candidates[*cursor % candidates.len()];
+                *cursor = cursor.wrapping_add(1);
+                Some(selected)
+            }
+            SelectionStrategy::LeastOutstanding => candidates
+                .iter()
+                .min_by_key(|agent| agent.outstanding_tasks),
+            SelectionStrategy::Weighted => {
+                let current = self.current_weights.entry(group.to_string()).or_default();
+                current.retain(|id, _| candidates.iter().any(|agent| &agent.id == id));
+
+                let mut total = 0;
+                let mut selected: Option<&AgentInfo> = None;
+                let mut best = i64::MIN;
+                for agent in candidates {
+                    let weight = weight_of(agent);
+                    total += weight;
+                    let value = current.entry(agent.id.clone()).or_insert(0);
+                    *value += weight;
+                    if *value > best {
+                        best = *value;
+                        selected = Some(agent);
+                    }
+                }
+
+                let selected = selected?;
+                if let Some(value) = current.get_mut(&selected.id) {
+                    *value -= total;
+                }
+                Some(selected)
+            }
+            SelectionStrategy::ConsistentHash(key) => {
+                let key_hash = hash_of(key);
+                candidates
+                    .iter()
+                    .flat_map(|agent| {
+                        (0 .. VIRTUAL_NODES).map(move |node| (hash_of(&(&agent.id, node)), agent))
+                    })
+                    .min_by_key(|(node_hash, _)| node_hash.wrapping_sub(key_hash))
+                    .map(|(_, agent)| agent)
+            }
+        }
+    }
+}
+
+fn weight_of(agent: &AgentInfo) -> i64 {
+    agent
+        .metadata
+        .get(WEIGHT_METADATA_KEY)
+        .and_then(|w| w.as_i64())
+        .filter(|w| *w > 0)
+        .unwrap_or(1)
+}
+
+fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
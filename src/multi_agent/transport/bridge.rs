@@ -0,0 +1,446 @@
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::{broadcast, mpsc};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
+
+use crate::error::agent_error::AgentError;
+use crate::error::{Error, Result};
+use crate::multi_agent::communication::message::Message;
+use crate::multi_agent::communication::message_bus::MessageBus;
+use crate::multi_agent::transport::nodes::NodeRegistry;
+use crate::multi_agent::transport::{Connection, Frame, Transport};
+use crate::utils::retry::RetryPolicy;
+
+/// Messages waiting to be written to one connection
+const ROUTE_CAPACITY: usize = 1000;
+
+/// Broadcast ids remembered to keep forwarded broadcasts from bouncing back
+const RECENT_BROADCASTS: usize = 10_000;
+
+/// Bridge configuration
+#[derive(Debug, Clone)]
+pub struct BridgeConfig {
+    /// Name of this node, unique among the connected nodes
+    pub node_id: String,
+    /// How often the local agent list is sent to peers
+    pub heartbeat_interval: Duration,
+    /// Delay between reconnection attempts; `max_retries` consecutive failures end them
+    pub reconnect: RetryPolicy,
+}
+
+impl BridgeConfig {
+    pub fn new(node_id: impl Into<String>) -> Self {
+        Self {
+            node_id: node_id.into(),
+            heartbeat_interval: Duration::from_secs(5),
+            reconnect: RetryPolicy {
+                max_retries: u32::MAX,
+                initial_backoff_ms: 500,
+                max_backoff_ms: 30_000,
+                multiplier: 2.0,
+            },
+        }
+    }
+
+    pub fn with_heartbeat_interval(mut self, interval: Duration) -> Self {
+        self.heartbeat_interval = interval;
+        self
+    }
+
+    pub fn with_reconnect(mut self, reconnect: RetryPolicy) -> Self {
+        self.reconnect = reconnect;
+        self
+    }
+}
+
+/// Connects a `MessageBus` to the buses of other processes
+///
+/// Agents of a connected node are added as routes to the local bus, so point-to-point messages
+/// for them are forwarded by agent id, and broadcasts sent on either side reach both. Nodes are
+/// expected to be connected pairwise: broadcasts are forwarded one hop only. When a connection
+/// drops, messages for its agents become dead letters, which are re-driven once the node is
+/// back. Topic messages stay on the bus they were published on.
+#[derive(Clone)]
+pub struct BusBridge {
+    bus: Arc<MessageBus>,
+    transport: Arc<dyn Transport>,
+    config: Arc<BridgeConfig>,
+    nodes: Arc<NodeRegistry>,
+    /// Ids of broadcasts received from peers
+    received: Arc<Mutex<RecentIds>>,
+    shutdown: CancellationToken,
+}
+
+impl BusBridge {
+    pub fn new(bus: Arc<MessageBus>, transport: Arc<dyn Transport>, config: BridgeConfig) -> Self {
+        Self {
+            bus,
+            transport,
+            config: Arc::new(config),
+            nodes: Arc::new(NodeRegistry::new()),
+            received: Arc::new(Mutex::new(RecentIds::default())),
+            shutdown: CancellationToken::new(),
+        }
+    }
+
+    pub fn node_id(&self) -> &str {
+        &self.config.node_id
+    }
+
+    /// Remote nodes and their agents
+    pub fn nodes(&self) -> &NodeRegistry {
+        &self.nodes
+    }
+
+    /// Accept connections from other nodes and return the bound address
+    pub async fn listen(&self, addr: &str) -> Result<String> {
+        let mut listener = self.transport.bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        info!("Node {} listening on {}", self.config.node_id, local_addr);
+
+        let bridge = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let connection = tokio::select! {
+                    result = listener.accept() => result,
+                    _ = bridge.shutdown.cancelled() => break,
+                };
+                match connection {
+                    Ok(connection) => {
+                        let bridge = bridge.clone();
+                        tokio::spawn(async move {
+                            let peer_addr = connection.peer_addr.clone();
+                            if let Err(e) = bridge.serve(connection).await {
+                                warn!("Connection from {} closed: {:?}", peer_addr, e);
+                            }
+                        });
+                    }
+                    Err(e) => warn!("Failed to accept connection: {:?}", e),
+                }
+            }
+        });
+        Ok(local_addr)
+    }
+
+    /// Connect to the node at `addr` in the background, reconnecting whenever it drops
+    pub fn connect(&self, addr: impl Into<String>) {
+        let bridge = self.clone();
+        let addr = addr.into();
+        tokio::spawn(async move { bridge.maintain(addr).await });
+    }
+
+    /// Close all connections and stop accepting new ones
+    pub fn shutdown(&self) {
+        self.shutdown.cancel();
+    }
+
+    async fn maintain(self, addr: String) {
+        let policy = &self.config.reconnect;
+        let mut failures = 0;
+
+        while !self.shutdown.is_cancelled() {
+            match self.transport.connect(&addr).await {
+                Ok(connection) => {
+                    failures = 0;
+                    info!("Node {} connected to {}", self.config.node_id, addr);
+                    if let Err(e) = self.serve(connection).await {
+                        warn!("Connection to {} closed: {:?}", addr, e);
+                    }
+                }
+                Err(e) => {
+                    failures += 1;
+                    if failures > policy.max_retries {
+                        error!("Giving up connecting to {} after {} attempts", addr, failures);
+                        return;
+                    }
+                    warn!("Failed to connect to {}: {:?}", addr, e);
+                }
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(policy.backoff(failures.max(1))) => {}
+                _ = self.shutdown.cancelled() => break,
+            }
+        }
+    }
+
+    /// Exchange frames with a peer until the connection closes
+    async fn serve(&self, connection: Connection) -> Result<()> {
+        let Connection {
+            peer_addr,
+            mut reader,
+            mut writer,
+        } = connection;
+        let mut broadcasts = self.bus.subscribe_broadcasts();
+
+        let hello = Frame::Hello {
+            node_id: self.config.node_id.clone(),
+            agents: self.local_agents().await,
+        };
+        writer.write_frame(&hello).await?;
+        let (peer, agents) = match reader.read_frame().await? {
+            Some(Frame::Hello { node_id, agents }) => (node_id, agents),
+            other => {
+                return Err(Error::AgentError(AgentError::MessageDeliveryError(format!(
+                    "Expected hello from {peer_addr}, got {other:?}"
+                ))));
+            }
+        };
+        info!("Node {} at {} joined with {} agents", peer, peer_addr, agents.len());
+
+        let (route_tx, mut route_rx) = mpsc::channel(ROUTE_CAPACITY);
+        self.update_peer(&peer, &peer_addr, agents, &route_tx).await;
+
+        let read_loop = async {
+            while let Some(frame) = reader.read_frame().await? {
+                match frame {
+                    Frame::Agents { agents } => {
+                        self.update_peer(&peer, &peer_addr, agents, &route_tx).await;
+                    }
+                    Frame::Message { message } => self.receive(message).await,
+                    Frame::Hello { .. } => warn!("Ignoring repeated hello from {}", peer),
+                }
+            }
+            Ok::<(), Error>(())
+        };
+
+        let write_loop = async {
+            let mut heartbeat = tokio::time::interval(self.config.heartbeat_interval);
+            heartbeat.tick().await;
+            loop {
+                let frame = tokio::select! {
+                    Some(message) = route_rx.recv() => self.forward(&message),
+                    result = broadcasts.recv() => match result {
+                        Ok(message) if self.is_local_broadcast(&message) => self.forward(&message),
+                        Ok(_) => continue,
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            warn!("{} broadcasts were not forwarded to {}", skipped, peer);
+                            continue;
+                        }
+                        Err(broadcast::error::RecvError::Closed) => return Ok(()),
+                    },
+                    _ = heartbeat.tick() => Frame::Agents {
+                        agents: self.local_agents().await,
+                    },
+                };
+                writer.write_frame(&frame).await?;
+            }
+        };
+
+        let result = tokio::select! {
+            result = read_loop => result,
+            result = write_loop => result,
+            _ = self.shutdown.cancelled() => Ok(()),
+        };
+
+        for agent in self.nodes.disconnect(&peer).await {
+            self.bus.remove_route(&agent, &route_tx).await;
+        }
+        info!("Node {} disconnected", peer);
+        result
+    }
+
+    /// Route the peer's agents through this connection
+    async fn update_peer(
+        &self,
+        peer: &str,
+        peer_addr: &str,
+        agents: Vec<String>,
+        route_tx: &mpsc::Sender<Arc<Message>>,
+    ) {
+        let (added, removed) = self.nodes.update(peer, peer_addr, agents).await;
+        for agent in removed {
+            self.bus.remove_route(&agent, route_tx).await;
+        }
+        for agent in added {
+            // An agent of this node keeps its messages, whatever the peer claims
+            if self.bus.add_route(agent.clone(), route_tx.clone()).await.is_err() {
+                continue;
+            }
+            // Messages that could not be delivered while the node was away
+            self.bus.redrive(&agent).await;
+        }
+    }
+
+    /// Frame a message for a peer, recording this node as its origin unless it already has one
+    fn forward(&self, message: &Message) -> Frame {
+        let mut message = message.clone();
+        message
+            .origin_node
+            .get_or_insert_with(|| self.config.node_id.clone());
+        Frame::Message { message }
+    }
+
+    /// Deliver a message from a peer to the local bus
+    async fn receive(&self, message: Message) {
+        if message.is_broadcast() {
+            self.received.lock().unwrap().insert(message.id.clone());
+        }
+        debug!("Node {} received message {} from a peer", self.config.node_id, message.id);
+        if let Err(e) = self.bus.send(message).await {
+            warn!("Failed to deliver forwarded message: {:?}", e);
+        }
+    }
+
+    /// Whether a broadcast was sent on this node rather than forwarded from a peer
+    fn is_local_broadcast(&self, message: &Message) -> bool {
+        !self.received.lock().unwrap().contains(&message.id)
+    }
+
+    async fn local_agents(&self) -> Vec<String> {
+        self.bus.get_local_agents().await
+    }
+}
+
+/// Bounded set of message ids, forgetting the oldest first
+#[derive(Default)]
+struct RecentIds {
+    ids: HashSet<String>,
+    order: VecDeque<String>,
+}
+
+impl RecentIds {
+    fn insert(&mut self, id: String) {
+        if self.ids.insert(id.clone()) {
+            self.order.push_back(id);
+        }
+        while self.order.len() > RECENT_BROADCASTS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+    }
+
+    fn contains(&self, id: &str) -> bool {
+        self.ids.contains(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multi_agent::communication::message::MessageType;
+    use crate::multi_agent::communication::message_bus::MessageBusConfig;
+    use crate::multi_agent::transport::TcpTransport;
+
+    fn node(node_id: &str) -> (Arc<MessageBus>, BusBridge) {
+        let bus = Arc::new(MessageBus::new(MessageBusConfig::default()));
+        let reconnect = RetryPolicy {
+            max_retries: u32::MAX,
+            initial_backoff_ms: 20,
+            max_backoff_ms: 20,
+            multiplier: 1.0,
+        };
+        let config = BridgeConfig::new(node_id)
+            .with_heartbeat_interval(Duration::from_millis(20))
+            .with_reconnect(reconnect);
+        let bridge = BusBridge::new(bus.clone(), Arc::new(TcpTransport::new()), config);
+        (bus, bridge)
+    }
+
+    async fn wait_for_agent(bridge: &BusBridge, agent_id: &str) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !bridge.nodes().is_remote(agent_id).await {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("agent did not show up");
+    }
+
+    #[tokio::test]
+    async fn test_bridge_forwards_messages_between_buses() {
+        let (bus_a, bridge_a) = node("a");
+        let (bus_b, bridge_b) = node("b");
+        let mut master = bus_a.register_agent("master".to_string()).await.unwrap();
+        let mut worker = bus_b.register_agent("worker".to_string()).await.unwrap();
+
+        let addr = bridge_a.listen("127.0.0.1:0").await.unwrap();
+        bridge_b.connect(addr);
+        wait_for_agent(&bridge_a, "worker").await;
+        wait_for_agent(&bridge_b, "master").await;
+        assert_eq!(bridge_a.nodes().node_of("worker").await.as_deref(), Some("b"));
+
+        let task = Message::new(
+            "master".to_string(),
+            Some("worker".to_string()),
+            MessageType::TaskAssignment,
+            serde_json::json!({ "task": 1 }),
+        );
+        let responder = tokio::spawn(async move {
+            let received = worker.recv().await.unwrap();
+            let response = Message::response(
+                "worker".to_string(),
+                received.sender_id.clone(),
+                MessageType::ResultNotification,
+                serde_json::json!({ "done": received.payload["task"] }),
+                received.id.clone(),
+            );
+            bus_b.send(response).await.unwrap();
+            (bus_b, worker)
+        });
+        let response = bus_a.request(task, Duration::from_secs(5)).await.unwrap();
+        assert_eq!(response.payload["done"], 1);
+        let (bus_b, mut worker) = responder.await.unwrap();
+
+        // A broadcast reaches the other node once and is not sent back
+        let heartbeat =
+            Message::broadcast("worker".to_string(), MessageType::Heartbeat, serde_json::json!({}));
+        bus_b.send(heartbeat).await.unwrap();
+        let received = master.recv().await.unwrap();
+        assert_eq!(received.message_type, MessageType::Heartbeat);
+        assert_eq!(received.origin_node.as_deref(), Some("b"));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(worker.try_recv().is_none());
+        assert!(master.try_recv().is_none());
+
+        // Each message is counted once, on the node it was sent on
+        assert_eq!(bus_a.get_stats().await.total_messages, 1);
+        assert_eq!(bus_b.get_stats().await.total_messages, 2);
+
+        bridge_a.shutdown();
+        bridge_b.shutdown();
+    }
+
+    #[tokio::test]
+    async fn test_bridge_reconnects_and_redrives() {
+        let (bus_a, bridge_a) = node("a");
+        let (bus_b, bridge_b) = node("b");
+        let mut worker = bus_b.register_agent("worker".to_string()).await.unwrap();
+
+        // Nothing listens yet: the message is dead-lettered and b keeps retrying
+        let probe = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = probe.local_addr().unwrap().to_string();
+        drop(probe);
+        bridge_b.connect(addr.clone());
+        let task = Message::new(
+            "master".to_string(),
+            Some("worker".to_string()),
+            MessageType::TaskAssignment,
+            serde_json::json!({}),
+        );
+        assert!(bus_a.send(task).await.is_err());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        bridge_a.listen(&addr).await.unwrap();
+        wait_for_agent(&bridge_a, "worker").await;
+        assert_eq!(worker.recv().await.unwrap().message_type, MessageType::TaskAssignment);
+        assert!(bus_a.get_dead_letters(None).await.is_empty());
+
+        let node = bridge_a.nodes().get("b").await.unwrap();
+        assert!(node.connected);
+        bridge_b.shutdown();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while bridge_a.nodes().get("b").await.unwrap().connected {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert!(!bus_a.get_registered_agents().await.contains(&"worker".to_string()));
+        bridge_a.shutdown();
+    }
+}
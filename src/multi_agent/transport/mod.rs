@@ -0,0 +1,63 @@
+pub mod bridge;
+pub mod nodes;
+pub mod tcp;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::multi_agent::communication::message::Message;
+
+pub use bridge::{BridgeConfig, BusBridge};
+pub use nodes::{NodeInfo, NodeRegistry};
+pub use tcp::TcpTransport;
+
+/// Unit of data exchanged between two nodes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "frame", rename_all = "snake_case")]
+pub enum Frame {
+    /// First frame on every connection, sent by both sides
+    Hello { node_id: String, agents: Vec<String> },
+    /// The agents registered on the sending node; also serves as heartbeat
+    Agents { agents: Vec<String> },
+    /// A message for an agent of the receiving node, or a broadcast
+    Message { message: Message },
+}
+
+/// Reading half of a connection
+#[async_trait]
+pub trait FrameReader: Send {
+    /// Read the next frame; `None` once the peer closed the connection
+    async fn read_frame(&mut self) -> Result<Option<Frame>>;
+}
+
+/// Writing half of a connection
+#[async_trait]
+pub trait FrameWriter: Send {
+    async fn write_frame(&mut self, frame: &Frame) -> Result<()>;
+}
+
+/// An established connection to another node
+pub struct Connection {
+    /// Address of the peer, for logging and the node registry
+    pub peer_addr: String,
+    pub reader: Box<dyn FrameReader>,
+    pub writer: Box<dyn FrameWriter>,
+}
+
+/// Accepts connections from other nodes
+#[async_trait]
+pub trait Listener: Send {
+    /// The address the listener is bound to
+    fn local_addr(&self) -> Result<String>;
+
+    async fn accept(&mut self) -> Result<Connection>;
+}
+
+/// Way of connecting nodes, e.g. TCP
+#[async_trait]
+pub trait Transport: Send + Sync {
+    async fn bind(&self, addr: &str) -> Result<Box<dyn Listener>>;
+
+    async fn connect(&self, addr: &str) -> Result<Connection>;
+}
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use tokio::sync::RwLock;
+
+/// A remote node known to a bridge
+#[derive(Debug, Clone)]
+pub struct NodeInfo {
+    pub node_id: String,
+    /// Address of the connection to the node
+    pub address: String,
+    /// Agents registered on the node
+    pub agents: Vec<String>,
+    pub connected: bool,
+    /// When the last frame from the node arrived
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Remote nodes and the agents they host
+///
+/// Disconnected nodes are kept so their agents can be told apart from unknown ones; an agent
+/// belongs to the node that most recently reported it.
+#[derive(Debug, Default)]
+pub struct NodeRegistry {
+    nodes: RwLock<HashMap<String, NodeInfo>>,
+}
+
+impl NodeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a connected node and its agents
+    ///
+    /// Returns the agents that were not on the node before and the ones it no longer has.
+    pub async fn update(
+        &self,
+        node_id: &str,
+        address: &str,
+        agents: Vec<String>,
+    ) -> (Vec<String>, Vec<String>) {
+        let mut nodes = self.nodes.write().await;
+        let previous = nodes
+            .get(node_id)
+            .filter(|node| node.connected)
+            .map(|node| node.agents.clone())
+            .unwrap_or_default();
+
+        // Agents that moved here are no longer hosted by their old node
+        for node in nodes.values_mut().filter(|node| node.node_id != node_id) {
+            node.agents.retain(|agent| !agents.contains(agent));
+        }
+
+        let added = agents.iter().filter(|a| !previous.contains(a)).cloned().collect();
+        let removed = previous.into_iter().filter(|a| !agents.contains(a)).collect();
+        nodes.insert(
+            node_id.to_string(),
+            NodeInfo {
+                node_id: node_id.to_string(),
+                address: address.to_string(),
+                agents,
+                connected: true,
+                last_seen: Utc::now(),
+            },
+        );
+        (added, removed)
+    }
+
+    /// Mark a node as disconnected and return its agents
+    pub async fn disconnect(&self, node_id: &str) -> Vec<String> {
+        let mut nodes = self.nodes.write().await;
+        match nodes.get_mut(node_id) {
+            Some(node) => {
+                node.connected = false;
+                node.agents.clone()
+            }
+            None => Vec::new(),
+        }
+    }
+
+    pub async fn get(&self, node_id: &str) -> Option<NodeInfo> {
+        self.nodes.read().await.get(node_id).cloned()
+    }
+
+    pub async fn list(&self) -> Vec<NodeInfo> {
+        self.nodes.read().await.values().cloned().collect()
+    }
+
+    /// Node hosting an agent
+    pub async fn node_of(&self, agent_id: &str) -> Option<String> {
+        self.nodes
+            .read()
+            .await
+            .values()
+            .find(|node| node.agents.iter().any(|agent| agent == agent_id))
+            .map(|node| node.node_id.clone())
+    }
+
+    /// Whether an agent is hosted by a remote node
+    pub async fn is_remote(&self, agent_id: &str) -> bool {
+        self.node_of(agent_id).await.is_some()
+    }
+}
@@ -0,0 +1,140 @@
+use async_trait::async_trait;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::error::agent_error::AgentError;
+use crate::error::{Error, Result};
+use crate::multi_agent::transport::{
+    Connection, Frame, FrameReader, FrameWriter, Listener, Transport,
+};
+
+/// Largest frame accepted from a peer
+const MAX_FRAME_BYTES: usize = 16 * 1024 * 1024;
+
+/// TCP transport
+///
+/// Every frame is a JSON document preceded by its length as a big-endian `u32`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TcpTransport;
+
+impl TcpTransport {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn connection(stream: TcpStream) -> Result<Connection> {
+        stream.set_nodelay(true)?;
+        let peer_addr = stream.peer_addr()?.to_string();
+        let (reader, writer) = stream.into_split();
+        Ok(Connection {
+            peer_addr,
+            reader: Box::new(TcpFrameReader(reader)),
+            writer: Box::new(TcpFrameWriter(writer)),
+        })
+    }
+}
+
+#[async_trait]
+impl Transport for TcpTransport {
+    async fn bind(&self, addr: &str) -> Result<Box<dyn Listener>> {
+        Ok(Box::new(TcpFrameListener(TcpListener::bind(addr).await?)))
+    }
+
+    async fn connect(&self, addr: &str) -> Result<Connection> {
+        Self::connection(TcpStream::connect(addr).await?)
+    }
+}
+
+struct TcpFrameListener(TcpListener);
+
+#[async_trait]
+impl Listener for TcpFrameListener {
+    fn local_addr(&self) -> Result<String> {
+        Ok(self.0.local_addr()?.to_string())
+    }
+
+    async fn accept(&mut self) -> Result<Connection> {
+        let (stream, _) = self.0.accept().await?;
+        TcpTransport::connection(stream)
+    }
+}
+
+struct TcpFrameReader(OwnedReadHalf);
+
+#[async_trait]
+impl FrameReader for TcpFrameReader {
+    async fn read_frame(&mut self) -> Result<Option<Frame>> {
+        let mut header = [0u8; 4];
+        match self.0.read_exact(&mut header).await {
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+
+        let len = u32::from_be_bytes(header) as usize;
+        if len > MAX_FRAME_BYTES {
+            return Err(Error::AgentError(AgentError::MessageDeliveryError(format!(
+                "Frame of {len} bytes exceeds the limit of {MAX_FRAME_BYTES}"
+            ))));
+        }
+        let mut body = vec![0u8; len];
+        self.0.read_exact(&mut body).await?;
+        Ok(Some(serde_json::from_slice(&body)?))
+    }
+}
+
+struct TcpFrameWriter(OwnedWriteHalf);
+
+#[async_trait]
+impl FrameWriter for TcpFrameWriter {
+    async fn write_frame(&mut self, frame: &Frame) -> Result<()> {
+        let body = serde_json::to_vec(frame)?;
+        if body.len() > MAX_FRAME_BYTES {
+            return Err(Error::AgentError(AgentError::MessageDeliveryError(format!(
+                "Frame of {} bytes exceeds the limit of {MAX_FRAME_BYTES}",
+                body.len()
+            ))));
+        }
+
+        let mut buf = Vec::with_capacity(4 + body.len());
+        buf.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        buf.extend_from_slice(&body);
+        self.0.write_all(&buf).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_frames_round_trip() {
+        let transport = TcpTransport::new();
+        let mut listener = transport.bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let mut client = transport.connect(&addr).await.unwrap();
+        let mut server = listener.accept().await.unwrap();
+
+        let hello = Frame::Hello {
+            node_id: "a".to_string(),
+            agents: vec!["worker".to_string()],
+        };
+        client.writer.write_frame(&hello).await.unwrap();
+        client.writer.write_frame(&Frame::Agents { agents: vec![] }).await.unwrap();
+
+        match server.reader.read_frame().await.unwrap() {
+            Some(Frame::Hello { node_id, agents }) => {
+                assert_eq!(node_id, "a");
+                assert_eq!(agents, vec!["worker"]);
+            }
+            other => panic!("unexpected frame: {other:?}"),
+        }
+        assert!(matches!(server.reader.read_frame().await.unwrap(), Some(Frame::Agents { .. })));
+
+        drop(client);
+        assert!(server.reader.read_frame().await.unwrap().is_none());
+    }
+}
@@ -1,4 +1,8 @@
-use crate::{input::UserTaskInput, tools::ToolInfo};
+use crate::{
+    agent::{planning::AgentPlan, state::AgentState},
+    input::UserTaskInput,
+    tools::ToolInfo,
+};
 
 pub fn build_task_prompt(input: &UserTaskInput) -> String {
     let references = input
@@ -30,7 +34,10 @@ pub fn build_tools_prompt(tools: &[ToolInfo]) -> String {
         .map(|tool| {
             format!(
                 "\n - name: {}\n - description: {}\n - params_schema: {} \n - mcp_server: {}",
-                tool.name, tool.description, tool.params_schema, tool.mcp_server
+                tool.name,
+                tool.description,
+                tool.params_schema,
+                if tool.is_native() { "native" } else { tool.mcp_server.as_str() }
             )
         })
         .collect::<Vec<_>>()
@@ -40,3 +47,44 @@ pub fn build_tools_prompt(tools: &[ToolInfo]) -> String {
         "Available tools:\n{tools_text}\n\nIMPORTANT: These are the ONLY tools available. Do not use or reference any other tools."
     )
 }
+
+pub fn build_replan_prompt(plan: &AgentPlan, state: &AgentState, reason: &str) -> String {
+    let failed_step = plan
+        .error_step_id
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let trace = plan
+        .steps
+        .iter()
+        .map(|step| {
+            let result = state
+                .step_results
+                .get(&step.step_id.to_string())
+                .map(|r| format!("success={}, output={}", r.success, r.output))
+                .unwrap_or_else(|| "not executed".to_string());
+            format!(
+                " - step {} [{}{}]: {}",
+                step.step_id,
+                step.action,
+                step.tool.as_deref().map(|t| format!(" {t}")).unwrap_or_default(),
+                result
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        r#"
+The previous plan "{}" failed at step {}.
+Failure reason: {}
+
+Execution trace:
+{}
+
+Please generate a revised plan that avoids this failure. Steps that already succeeded may be
+repeated if their output is needed. Output only the JSON plan.
+"#,
+        plan.plan_id, failed_step, reason, trace
+    )
+}
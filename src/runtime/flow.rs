@@ -0,0 +1,218 @@
+use async_trait::async_trait;
+use serde_json::{Value, json};
+
+use crate::{
+    agent::{
+        core::base_agent::AgentBehavior,
+        planning::{AgentPlan, AgentStep},
+        runner::{RunReport, StepHandler, agent_runner::DEFAULT_MAX_REPLANS, scheduler},
+        state::AgentState,
+        types::{AgentType, StepResult},
+    },
+    agents::{ExecutorAgent, PlannerAgent, VerifierAgent},
+    error::{Error, Result, agent_error::AgentError},
+    input::model::UserTaskInput,
+    multi_agent::communication::{Message, MessageType},
+};
+
+/// Sender id used for requests issued by the runtime itself
+pub const RUNTIME_SENDER_ID: &str = "runtime";
+
+/// The planner, executor and verifier a runtime drives
+pub struct RuntimeAgents {
+    pub planner: Box<dyn AgentBehavior>,
+    pub executor: Box<dyn AgentBehavior>,
+    pub verifier: Box<dyn AgentBehavior>,
+}
+
+impl Default for RuntimeAgents {
+    fn default() -> Self {
+        Self {
+            planner: Box::new(PlannerAgent::new(None)),
+            executor: Box::new(ExecutorAgent::new(None, vec![])),
+            verifier: Box::new(VerifierAgent::new(None)),
+        }
+    }
+}
+
+/// Delivers a request to the agent playing `role` and returns its reply
+///
+/// Requests for different steps may be in flight at the same time.
+#[async_trait]
+pub trait AgentTransport: Send + Sync {
+    async fn request(&self, role: AgentType, message: Message) -> Result<Message>;
+}
+
+/// Plan, execute and verify a task by exchanging the agents' regular messages.
+///
+/// The same message shapes are used whether the agents run in-process or behind the bus, and
+/// steps are scheduled and replanned like `AgentRunner` does.
+pub async fn drive_task<T: AgentTransport>(
+    transport: &T,
+    input: &UserTaskInput,
+    max_concurrency: usize,
+) -> Result<RunReport> {
+    let plan = request_plan(transport, input, None).await?;
+    let mut handler = AgentSteps { transport };
+    scheduler::run_plan(
+        &mut handler,
+        input,
+        plan,
+        DEFAULT_MAX_REPLANS,
+        max_concurrency,
+    )
+    .await
+}
+
+/// Runs steps by sending requests to the planner, executor and verifier agents
+struct AgentSteps<'a, T> {
+    transport: &'a T,
+}
+
+#[async_trait]
+impl<T: AgentTransport> StepHandler for AgentSteps<'_, T> {
+    async fn execute_step(
+        &self,
+        plan_id: &str,
+        step: &AgentStep,
+        state: &AgentState,
+    ) -> StepResult {
+        request_execution(self.transport, plan_id, step, state)
+            .await
+            .unwrap_or_else(|e| StepResult {
+                output: json!({ "error": e.to_string() }).to_string(),
+                success: false,
+                ..Default::default()
+            })
+    }
+
+    async fn verify_step(
+        &self,
+        plan_id: &str,
+        step: &AgentStep,
+        result: &StepResult,
+    ) -> std::result::Result<(), String> {
+        request_verification(self.transport, plan_id, step, result)
+            .await
+            .unwrap_or_else(|e| Err(e.to_string()))
+    }
+
+    async fn replan(
+        &self,
+        input: &UserTaskInput,
+        plan: &AgentPlan,
+        state: &AgentState,
+        reason: &str,
+    ) -> Result<AgentPlan> {
+        let failed = json!({ "plan": plan, "state": state, "reason": reason });
+        request_plan(self.transport, input, Some(failed)).await
+    }
+}
+
+/// Ask the planner for a plan, or for a revision of the failed plan in `replan`
+async fn request_plan<T: AgentTransport>(
+    transport: &T,
+    input: &UserTaskInput,
+    replan: Option<Value>,
+) -> Result<AgentPlan> {
+    let mut payload = serde_json::to_value(input)?;
+    payload["requester_id"] = json!(RUNTIME_SENDER_ID);
+    if let Some(replan) = replan {
+        payload["replan"] = replan;
+    }
+
+    let request = Message::new(
+        RUNTIME_SENDER_ID.to_string(),
+        None,
+        MessageType::Custom("PlanningRequest".to_string()),
+        payload,
+    );
+
+    let reply = expect_result(transport.request(AgentType::Planner, request).await?)?;
+    let plan = reply.payload.get("plan").cloned().ok_or_else(|| {
+        AgentError::InvalidPlan("规划 Agent 的回复中缺少 plan 字段".into())
+    })?;
+    Ok(serde_json::from_value(plan)?)
+}
+
+async fn request_execution<T: AgentTransport>(
+    transport: &T,
+    plan_id: &str,
+    step: &AgentStep,
+    state: &AgentState,
+) -> Result<StepResult> {
+    let request = Message::new(
+        RUNTIME_SENDER_ID.to_string(),
+        None,
+        MessageType::TaskAssignment,
+        json!({
+            "id": format!("{plan_id}-{}", step.step_id),
+            "task_type": "execution",
+            "step": step,
+            "step_results": state.step_results,
+        }),
+    );
+
+    let reply = transport.request(AgentType::Executor, request).await?;
+    let result = &reply.payload["result"];
+    if reply.message_type == MessageType::Error && result.is_null() {
+        return Ok(StepResult {
+            output: json!({ "error": reply.payload.get("error") }).to_string(),
+            success: false,
+            ..Default::default()
+        });
+    }
+
+    // Failed steps are sent as `Error` but still carry their output and approval trace
+    Ok(StepResult {
+        output: result["output"].as_str().unwrap_or_default().to_string(),
+        success: reply.message_type != MessageType::Error
+            && result["success"].as_bool().unwrap_or(false),
+        trace: serde_json::from_value(result["trace"].clone()).unwrap_or_default(),
+    })
+}
+
+async fn request_verification<T: AgentTransport>(
+    transport: &T,
+    plan_id: &str,
+    step: &AgentStep,
+    result: &StepResult,
+) -> Result<std::result::Result<(), String>> {
+    let request = Message::new(
+        RUNTIME_SENDER_ID.to_string(),
+        None,
+        MessageType::Custom("VerificationRequest".to_string()),
+        json!({
+            "task_id": format!("{plan_id}-{}", step.step_id),
+            "step": step,
+            "result": result,
+        }),
+    );
+
+    let reply = expect_result(transport.request(AgentType::Verifier, request).await?)?;
+    let verification = &reply.payload["verification_result"];
+    if verification["valid"].as_bool().unwrap_or(false) {
+        Ok(Ok(()))
+    } else {
+        Ok(Err(verification["message"]
+            .as_str()
+            .unwrap_or("Verification failed")
+            .to_string()))
+    }
+}
+
+/// Turn an `Error` reply into an error
+fn expect_result(reply: Message) -> Result<Message> {
+    if reply.message_type == MessageType::Error {
+        let error = reply
+            .payload
+            .get("error")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown error");
+        return Err(Error::AgentError(AgentError::ExecutionError(format!(
+            "Agent {} 返回错误: {error}",
+            reply.sender_id
+        ))));
+    }
+    Ok(reply)
+}
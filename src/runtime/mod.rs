@@ -0,0 +1,43 @@
+pub mod flow;
+pub mod multi_agent;
+pub mod single_agent;
+
+use std::sync::Arc;
+
+pub use flow::{AgentTransport, RuntimeAgents};
+pub use multi_agent::MultiAgentRuntime;
+pub use single_agent::SingleAgentRuntime;
+
+use crate::{
+    agent::{runner::RunReport, types::RuntimeMode},
+    error::Result,
+    input::model::UserTaskInput,
+    shared::GlobalContext,
+};
+
+/// Run a task with the default agents, in the mode selected by `GlobalConfig.runtime_mode`
+pub async fn run_task(context: Arc<GlobalContext>, input: &UserTaskInput) -> Result<RunReport> {
+    run_task_with_agents(context, RuntimeAgents::default(), input).await
+}
+
+/// Run a task with the given agents, in the mode selected by `GlobalConfig.runtime_mode`
+pub async fn run_task_with_agents(
+    context: Arc<GlobalContext>,
+    agents: RuntimeAgents,
+    input: &UserTaskInput,
+) -> Result<RunReport> {
+    match context.get_runtime_mode().await {
+        RuntimeMode::SingleAgent => {
+            let mut runtime = SingleAgentRuntime::new(context, agents).await?;
+            let report = runtime.run_task(input).await;
+            runtime.shutdown().await?;
+            report
+        }
+        RuntimeMode::MultiAgent => {
+            let mut runtime = MultiAgentRuntime::new(context, agents).await?;
+            let report = runtime.run_task(input).await;
+            runtime.shutdown().await?;
+            report
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use tracing::info;
+
+use crate::{
+    agent::{runner::RunReport, types::AgentType},
+    error::{Error, Result, agent_error::AgentError},
+    input::model::UserTaskInput,
+    multi_agent::{
+        communication::Message,
+        manager::{AgentManager, AgentManagerConfig},
+    },
+    runtime::flow::{AgentTransport, RuntimeAgents, drive_task},
+    shared::GlobalContext,
+};
+
+/// Runs planner, executor and verifier as managed agents talking over the message bus
+pub struct MultiAgentRuntime {
+    manager: Arc<AgentManager>,
+    planner_id: String,
+    executor_id: String,
+    verifier_id: String,
+    request_timeout: Duration,
+    max_concurrency: usize,
+}
+
+impl MultiAgentRuntime {
+    /// Spawn the agents under a new `AgentManager`
+    pub async fn new(context: Arc<GlobalContext>, agents: RuntimeAgents) -> Result<Self> {
+        let config = context.get_config().await;
+        let request_timeout = Duration::from_secs(config.task_timeout_secs);
+        let manager = Arc::new(AgentManager::new(context, AgentManagerConfig::default()));
+
+        let planner_id = manager.spawn_agent(agents.planner).await?;
+        let executor_id = manager.spawn_agent(agents.executor).await?;
+        let verifier_id = manager.spawn_agent(agents.verifier).await?;
+
+        info!("Multi-agent runtime started");
+        Ok(Self {
+            manager,
+            planner_id,
+            executor_id,
+            verifier_id,
+            request_timeout,
+            max_concurrency: config.max_concurrent_tasks,
+        })
+    }
+
+    /// The manager owning the runtime's agents
+    pub fn manager(&self) -> Arc<AgentManager> {
+        self.manager.clone()
+    }
+
+    /// Plan, execute and verify a task
+    pub async fn run_task(&mut self, input: &UserTaskInput) -> Result<RunReport> {
+        drive_task(&*self, input, self.max_concurrency).await
+    }
+
+    /// Stop all agents
+    pub async fn shutdown(self) -> Result<()> {
+        self.manager.shutdown_all().await
+    }
+}
+
+#[async_trait]
+impl AgentTransport for MultiAgentRuntime {
+    async fn request(&self, role: AgentType, mut message: Message) -> Result<Message> {
+        let agent_id = match role {
+            AgentType::Planner => &self.planner_id,
+            AgentType::Executor => &self.executor_id,
+            AgentType::Verifier => &self.verifier_id,
+            other => {
+                return Err(Error::AgentError(AgentError::AgentNotFound(other.to_string())));
+            }
+        };
+        message.receiver_id = Some(agent_id.clone());
+
+        self.manager
+            .send_request(message, self.request_timeout)
+            .await
+    }
+}
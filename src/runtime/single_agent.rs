@@ -0,0 +1,220 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+use tracing::info;
+
+use crate::{
+    agent::{core::base_agent::AgentBehavior, runner::RunReport, types::AgentType},
+    error::{Error, Result, agent_error::AgentError},
+    input::model::UserTaskInput,
+    multi_agent::communication::Message,
+    runtime::flow::{AgentTransport, RuntimeAgents, drive_task},
+    shared::GlobalContext,
+};
+
+/// Runs planner, executor and verifier in-process.
+///
+/// Messages are handed straight to `AgentBehavior::process_message`; no message bus,
+/// registry or heartbeat task is started. Each agent handles one message at a time.
+pub struct SingleAgentRuntime {
+    planner: Mutex<Box<dyn AgentBehavior>>,
+    executor: Mutex<Box<dyn AgentBehavior>>,
+    verifier: Mutex<Box<dyn AgentBehavior>>,
+    max_concurrency: usize,
+}
+
+impl SingleAgentRuntime {
+    /// Initialize the agents with the shared context
+    pub async fn new(context: Arc<GlobalContext>, mut agents: RuntimeAgents) -> Result<Self> {
+        agents.planner.initialize(context.clone()).await?;
+        agents.executor.initialize(context.clone()).await?;
+        agents.verifier.initialize(context.clone()).await?;
+
+        info!("Single-agent runtime started");
+        Ok(Self {
+            planner: Mutex::new(agents.planner),
+            executor: Mutex::new(agents.executor),
+            verifier: Mutex::new(agents.verifier),
+            max_concurrency: context.get_config().await.max_concurrent_tasks,
+        })
+    }
+
+    /// Plan, execute and verify a task
+    pub async fn run_task(&mut self, input: &UserTaskInput) -> Result<RunReport> {
+        drive_task(&*self, input, self.max_concurrency).await
+    }
+
+    /// Shut the agents down
+    pub async fn shutdown(self) -> Result<()> {
+        self.planner.into_inner().shutdown().await?;
+        self.executor.into_inner().shutdown().await?;
+        self.verifier.into_inner().shutdown().await?;
+        Ok(())
+    }
+
+    fn agent(&self, role: AgentType) -> Result<&Mutex<Box<dyn AgentBehavior>>> {
+        match role {
+            AgentType::Planner => Ok(&self.planner),
+            AgentType::Executor => Ok(&self.executor),
+            AgentType::Verifier => Ok(&self.verifier),
+            other => Err(Error::AgentError(AgentError::AgentNotFound(other.to_string()))),
+        }
+    }
+}
+
+#[async_trait]
+impl AgentTransport for SingleAgentRuntime {
+    async fn request(&self, role: AgentType, mut message: Message) -> Result<Message> {
+        let mut agent = self.agent(role)?.lock().await;
+        let agent_id = agent.get_id().to_string();
+        message.receiver_id = Some(agent_id.clone());
+
+        agent.process_message(message).await?.ok_or_else(|| {
+            Error::AgentError(AgentError::MessageDeliveryError(format!(
+                "Agent {agent_id} did not reply"
+            )))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::{
+        agent::{
+            core::base_agent::BaseAgent,
+            types::{AgentCapability, StepStatus},
+        },
+        agents::{ExecutorAgent, VerifierAgent},
+        multi_agent::communication::MessageType,
+        tools::native::{Tool, register_native_tool},
+    };
+
+    struct ShoutTool;
+
+    #[async_trait]
+    impl Tool for ShoutTool {
+        fn name(&self) -> &str {
+            "single_runtime_shout"
+        }
+
+        fn description(&self) -> &str {
+            "Uppercase the text argument"
+        }
+
+        fn input_schema(&self) -> Value {
+            json!({"type": "object", "required": ["text"]})
+        }
+
+        async fn call(&self, arguments: Value) -> Result<Value> {
+            let text = arguments["text"].as_str().unwrap_or_default();
+            Ok(json!({ "result": text.to_uppercase() }))
+        }
+    }
+
+    /// Planner that replies with a fixed plan in the `PlannerAgent` message shape
+    ///
+    /// For the goal "retry" the first plan misses the tool argument, and only the revised
+    /// plan works.
+    struct FixedPlanner {
+        base: BaseAgent,
+    }
+
+    #[async_trait]
+    impl AgentBehavior for FixedPlanner {
+        fn get_id(&self) -> &str {
+            &self.base.id
+        }
+
+        fn get_type(&self) -> AgentType {
+            AgentType::Planner
+        }
+
+        fn get_capabilities(&self) -> &[AgentCapability] {
+            &self.base.capabilities
+        }
+
+        async fn initialize(&mut self, _context: Arc<GlobalContext>) -> Result<()> {
+            Ok(())
+        }
+
+        async fn process_message(&mut self, message: Message) -> Result<Option<Message>> {
+            let goal = &message.payload["goal"];
+            let parameters = if goal == "retry" && message.payload.get("replan").is_none() {
+                json!({})
+            } else {
+                json!({ "text": goal })
+            };
+            let plan = json!({
+                "plan_id": "single-runtime",
+                "steps": [
+                    {
+                        "step_id": 1,
+                        "description": "shout",
+                        "action": "call_tool",
+                        "tool": "single_runtime_shout",
+                        "parameters": parameters
+                    },
+                    {
+                        "step_id": 2,
+                        "description": "shout again",
+                        "action": "call_tool",
+                        "tool": "single_runtime_shout",
+                        "parameters": {"text": "{{steps.1.output.result}}!"}
+                    }
+                ]
+            });
+            Ok(Some(Message::new(
+                self.base.id.clone(),
+                Some(message.sender_id),
+                MessageType::ResultNotification,
+                json!({"status": "success", "plan": plan}),
+            )))
+        }
+
+        async fn shutdown(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_single_agent_runtime_runs_task() {
+        register_native_tool(Arc::new(ShoutTool));
+
+        let agents = RuntimeAgents {
+            planner: Box::new(FixedPlanner {
+                base: BaseAgent::new(
+                    "fixed-planner".to_string(),
+                    AgentType::Planner,
+                    vec![AgentCapability::TaskPlanning],
+                ),
+            }),
+            executor: Box::new(ExecutorAgent::new(None, vec![])),
+            verifier: Box::new(VerifierAgent::new(None)),
+        };
+        let mut runtime = SingleAgentRuntime::new(Arc::new(GlobalContext::default()), agents)
+            .await
+            .unwrap();
+
+        let input = UserTaskInput::new("hello".into(), String::new(), None, None, None);
+        let report = runtime.run_task(&input).await.unwrap();
+
+        assert!(report.is_succeeded());
+        assert_eq!(report.state.get_step_status(2), Some(&StepStatus::Done));
+        assert_eq!(report.plan.steps[1].output, Some(json!({"result": "HELLO!"})));
+        assert_eq!(report.replans, 0);
+
+        // A failed step asks the planner for a revised plan
+        let input = UserTaskInput::new("retry".into(), String::new(), None, None, None);
+        let report = runtime.run_task(&input).await.unwrap();
+
+        assert!(report.is_succeeded());
+        assert_eq!(report.replans, 1);
+        assert_eq!(report.plan.steps[1].output, Some(json!({"result": "RETRY!"})));
+
+        runtime.shutdown().await.unwrap();
+    }
+}
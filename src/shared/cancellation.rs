@@ -0,0 +1,128 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use tokio_util::sync::CancellationToken;
+
+/// Number of cancellations kept for tasks that have not started
+const MAX_PENDING_CANCELLATIONS: usize = 1024;
+
+/// Cancellation tokens of running tasks, keyed by task id
+///
+/// The agent executing a task registers a token with `token_for`; whoever handles a
+/// `TaskCancellation` message calls `cancel`, which the executing agent observes through
+/// the token even while it is still busy with the task. Cancellations of tasks that have no
+/// token yet are remembered in a bounded set, oldest dropped first, so a task cancelled
+/// before it starts still starts cancelled.
+#[derive(Debug, Clone, Default)]
+pub struct TaskCancellations {
+    inner: Arc<Mutex<Cancellations>>,
+}
+
+#[derive(Debug, Default)]
+struct Cancellations {
+    tokens: HashMap<String, CancellationToken>,
+    pending: HashSet<String>,
+    /// Order in which `pending` was filled
+    pending_order: VecDeque<String>,
+}
+
+impl TaskCancellations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the token for a task, creating it on first use
+    ///
+    /// A task cancelled before it started gets an already cancelled token.
+    pub fn token_for(&self, task_id: &str) -> CancellationToken {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(token) = inner.tokens.get(task_id) {
+            return token.clone();
+        }
+
+        let token = CancellationToken::new();
+        if inner.pending.remove(task_id) {
+            inner.pending_order.retain(|id| id != task_id);
+            token.cancel();
+        }
+        inner.tokens.insert(task_id.to_string(), token.clone());
+        token
+    }
+
+    /// Cancel a task
+    pub fn cancel(&self, task_id: &str) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(token) = inner.tokens.get(task_id) {
+            token.cancel();
+            return;
+        }
+
+        if inner.pending.insert(task_id.to_string()) {
+            inner.pending_order.push_back(task_id.to_string());
+        }
+        while inner.pending_order.len() > MAX_PENDING_CANCELLATIONS {
+            if let Some(oldest) = inner.pending_order.pop_front() {
+                inner.pending.remove(&oldest);
+            }
+        }
+    }
+
+    /// Check whether a task has been cancelled
+    pub fn is_cancelled(&self, task_id: &str) -> bool {
+        let inner = self.inner.lock().unwrap();
+        inner.pending.contains(task_id)
+            || inner
+                .tokens
+                .get(task_id)
+                .is_some_and(|token| token.is_cancelled())
+    }
+
+    /// Forget a task that reached a terminal status, together with any cancellation of it
+    pub fn remove(&self, task_id: &str) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.tokens.remove(task_id);
+        if inner.pending.remove(task_id) {
+            inner.pending_order.retain(|id| id != task_id);
+        }
+    }
+
+    /// Number of tasks with a token or a pending cancellation
+    pub fn len(&self) -> usize {
+        let inner = self.inner.lock().unwrap();
+        inner.tokens.len() + inner.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_cancellations_are_bounded() {
+        let cancellations = TaskCancellations::new();
+
+        // Cancelling a running task does not keep anything once it is removed
+        let token = cancellations.token_for("running");
+        cancellations.cancel("running");
+        assert!(token.is_cancelled());
+        cancellations.remove("running");
+        assert!(cancellations.is_empty());
+
+        // A task cancelled before it starts starts cancelled
+        cancellations.cancel("early");
+        assert!(cancellations.is_cancelled("early"));
+        assert!(cancellations.token_for("early").is_cancelled());
+        cancellations.remove("early");
+
+        for n in 0 .. MAX_PENDING_CANCELLATIONS + 10 {
+            cancellations.cancel(&format!("elsewhere-{n}"));
+        }
+        assert_eq!(cancellations.len(), MAX_PENDING_CANCELLATIONS);
+        assert!(!cancellations.is_cancelled("elsewhere-0"));
+        assert!(!cancellations.token_for("fresh").is_cancelled());
+    }
+}
@@ -3,6 +3,7 @@ use tokio::sync::RwLock;
 use serde::{Deserialize, Serialize};
 
 use crate::agent::types::RuntimeMode;
+use crate::shared::cancellation::TaskCancellations;
 
 /// Global configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,6 +56,8 @@ pub struct GlobalContext {
     pub shared_data: Arc<RwLock<serde_json::Value>>,
     /// MCP tool registry reference (if needed)
     pub tool_registry: Option<Arc<RwLock<std::collections::HashMap<String, serde_json::Value>>>>,
+    /// Cancellation tokens of running tasks
+    pub cancellations: TaskCancellations,
 }
 
 impl GlobalContext {
@@ -65,6 +68,7 @@ impl GlobalContext {
             runtime_info: Arc::new(RuntimeInfo::default()),
             shared_data: Arc::new(RwLock::new(serde_json::json!({}))),
             tool_registry: None,
+            cancellations: TaskCancellations::new(),
         }
     }
 
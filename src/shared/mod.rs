@@ -1,5 +1,7 @@
+pub mod cancellation;
 pub mod global_context;
 pub mod memory_pool;
 
+pub use cancellation::TaskCancellations;
 pub use global_context::GlobalContext;
 pub use memory_pool::{MemoryEntry, MemoryPool, SharedMemory};
\ No newline at end of file
@@ -10,6 +10,7 @@ pub fn instantiate_tool() -> Vec<ToolInfo> {
             description: tool_info.description.clone(),
             params_schema: tool_info.params_schema.clone(),
             mcp_server: tool_info.mcp_server.clone(),
+            read_only: tool_info.read_only,
         })
         .collect()
 }
@@ -21,6 +22,7 @@ pub fn get_tool_info(tool_name: &str) -> Option<ToolInfo> {
         description: tool_info.description.clone(),
         params_schema: tool_info.params_schema.clone(),
         mcp_server: tool_info.mcp_server.clone(),
+        read_only: tool_info.read_only,
     })
 }
 
@@ -1,4 +1,7 @@
 pub mod instantiate;
 pub mod model;
+pub mod native;
+pub mod schema;
 
 pub use model::ToolInfo;
+pub use native::{Tool, register_native_tool};
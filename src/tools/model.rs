@@ -3,11 +3,15 @@ use std::collections::HashMap;
 use once_cell::sync::Lazy;
 use serde_json::Value;
 
+use crate::tools::schema::{self, SchemaError};
+
 pub struct ToolInfo {
     pub name: String,
     pub description: String,
     pub params_schema: Value,
     pub mcp_server: String,
+    /// Whether calling the tool has no side effects
+    pub read_only: bool,
 }
 
 impl ToolInfo {
@@ -22,9 +26,27 @@ impl ToolInfo {
             description,
             params_schema,
             mcp_server,
+            read_only: false,
         }
     }
 
+    /// Mark the tool as free of side effects, letting plan steps that call it run alongside
+    /// other steps
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Whether the tool runs in-process rather than on an MCP server
+    pub fn is_native(&self) -> bool {
+        self.mcp_server.is_empty()
+    }
+
+    /// Validate call arguments against the tool's input schema
+    pub fn validate_arguments(&self, arguments: &Value) -> Result<(), Vec<SchemaError>> {
+        schema::validate(&self.params_schema, arguments)
+    }
+
     pub fn new_with_server(
         name: String,
         description: String,
@@ -36,6 +58,7 @@ impl ToolInfo {
             description,
             params_schema,
             mcp_server,
+            read_only: false,
         }
     }
 }
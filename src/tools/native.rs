@@ -0,0 +1,158 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use tracing::warn;
+
+#[cfg(feature = "mcp")]
+use crate::mcp::tool::McpTool;
+use crate::{
+    error::Result,
+    tools::model::{TOOL_REGISTRY, ToolInfo},
+};
+
+/// A callable tool; implemented by in-process Rust tools and by MCP-backed tools
+#[async_trait]
+pub trait Tool: Send + Sync {
+    /// Unique tool name used in plan steps
+    fn name(&self) -> &str;
+
+    /// Description shown to the planner
+    fn description(&self) -> &str;
+
+    /// JSON Schema of the call arguments
+    fn input_schema(&self) -> Value;
+
+    /// Whether calling the tool has no side effects; plan steps using a read-only tool do
+    /// not have to wait for the step before them
+    fn read_only(&self) -> bool {
+        false
+    }
+
+    /// Invoke the tool with already validated arguments
+    async fn call(&self, arguments: Value) -> Result<Value>;
+}
+
+/// In-process tools (tool name -> implementation)
+pub static NATIVE_TOOLS: Lazy<RwLock<HashMap<String, Arc<dyn Tool>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Register a native tool so the planner can see it and the executor can call it
+///
+/// Native tools take precedence over MCP tools, so registering one under the name of an MCP
+/// tool replaces that tool and logs a warning.
+pub fn register_native_tool(tool: Arc<dyn Tool>) {
+    let name = tool.name().to_string();
+
+    let mut registry = TOOL_REGISTRY.write().unwrap();
+    if let Some(existing) = registry.get(&name)
+        && !existing.is_native()
+    {
+        warn!(
+            "Native tool '{}' replaces the tool of the same name from MCP server '{}'",
+            name, existing.mcp_server
+        );
+    }
+    registry.insert(
+        name.clone(),
+        ToolInfo::new(
+            name.clone(),
+            tool.description().to_string(),
+            tool.input_schema(),
+            String::new(),
+        )
+        .with_read_only(tool.read_only()),
+    );
+    drop(registry);
+    NATIVE_TOOLS.write().unwrap().insert(name, tool);
+}
+
+/// Remove a native tool from both registries
+pub fn unregister_native_tool(name: &str) {
+    if NATIVE_TOOLS.write().unwrap().remove(name).is_some() {
+        TOOL_REGISTRY.write().unwrap().remove(name);
+    }
+}
+
+/// Resolve a tool name to a callable implementation, native tools first
+pub fn resolve_tool(name: &str) -> Option<Arc<dyn Tool>> {
+    if let Some(tool) = NATIVE_TOOLS.read().unwrap().get(name) {
+        return Some(tool.clone());
+    }
+
+    let registry = TOOL_REGISTRY.read().unwrap();
+    let info = registry.get(name)?;
+    if info.is_native() {
+        // Registered without an implementation
+        return None;
+    }
+
+    #[cfg(feature = "mcp")]
+    return Some(Arc::new(McpTool::new(
+        info.name.clone(),
+        info.description.clone(),
+        info.params_schema.clone(),
+        info.mcp_server.clone(),
+    )));
+
+    #[cfg(not(feature = "mcp"))]
+    {
+        warn!(
+            "Tool '{}' is served by MCP server '{}', but the mcp feature is disabled",
+            name, info.mcp_server
+        );
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    struct UpperTool;
+
+    #[async_trait]
+    impl Tool for UpperTool {
+        fn name(&self) -> &str {
+            "native_test_upper"
+        }
+
+        fn description(&self) -> &str {
+            "Uppercase a string"
+        }
+
+        fn input_schema(&self) -> Value {
+            json!({
+                "type": "object",
+                "required": ["text"],
+                "properties": {"text": {"type": "string"}}
+            })
+        }
+
+        async fn call(&self, arguments: Value) -> Result<Value> {
+            let text = arguments["text"].as_str().unwrap_or_default();
+            Ok(json!({"result": text.to_uppercase()}))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_register_and_resolve_native_tool() {
+        register_native_tool(Arc::new(UpperTool));
+
+        let info = crate::tools::instantiate::get_tool_info("native_test_upper").unwrap();
+        assert!(info.is_native());
+
+        let tool = resolve_tool("native_test_upper").unwrap();
+        let output = tool.call(json!({"text": "abc"})).await.unwrap();
+        assert_eq!(output["result"], "ABC");
+
+        unregister_native_tool("native_test_upper");
+        assert!(resolve_tool("native_test_upper").is_none());
+    }
+}
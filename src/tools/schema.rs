@@ -0,0 +1,327 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// A single schema violation, annotated with the JSON path where it occurred
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaError {
+    /// Path of the offending value, e.g. `$.items[2].name`
+    pub path: String,
+    /// Human readable description of the violation
+    pub message: String,
+}
+
+impl SchemaError {
+    fn new(path: &str, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Join a list of schema errors into a single line
+pub fn format_schema_errors(errors: &[SchemaError]) -> String {
+    errors
+        .iter()
+        .map(|e| e.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Validate `instance` against a JSON Schema (draft 2020-12 subset).
+///
+/// Supported keywords: `type`, `required`, `properties`, `additionalProperties`,
+/// `enum`, `const`, `items`, `minimum`, `maximum`, `exclusiveMinimum`,
+/// `exclusiveMaximum`, `minLength`, `maxLength`, `minItems` and `maxItems`.
+/// Unknown keywords are ignored; a non-object schema accepts everything.
+pub fn validate(schema: &Value, instance: &Value) -> Result<(), Vec<SchemaError>> {
+    let mut errors = Vec::new();
+    validate_at(schema, instance, "$", &mut errors);
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+fn validate_at(schema: &Value, instance: &Value, path: &str, errors: &mut Vec<SchemaError>) {
+    let schema = match schema {
+        Value::Object(schema) => schema,
+        Value::Bool(false) => {
+            errors.push(SchemaError::new(path, "no value is allowed here"));
+            return;
+        }
+        _ => return,
+    };
+
+    if let Some(expected) = schema.get("type")
+        && !matches_type(expected, instance)
+    {
+        errors.push(SchemaError::new(
+            path,
+            format!("expected type {}, got {}", describe_type(expected), type_name(instance)),
+        ));
+        // Further keywords are meaningless against the wrong type
+        return;
+    }
+
+    if let Some(Value::Array(options)) = schema.get("enum")
+        && !options.contains(instance)
+    {
+        errors.push(SchemaError::new(
+            path,
+            format!("value {instance} is not one of {}", Value::Array(options.clone())),
+        ));
+    }
+
+    if let Some(expected) = schema.get("const")
+        && expected != instance
+    {
+        errors.push(SchemaError::new(path, format!("value must be {expected}")));
+    }
+
+    match instance {
+        Value::Object(object) => validate_object(schema, object, path, errors),
+        Value::Array(items) => validate_array(schema, items, path, errors),
+        Value::String(text) => validate_string(schema, text, path, errors),
+        Value::Number(number) => {
+            if let Some(value) = number.as_f64() {
+                validate_number(schema, value, path, errors);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn validate_object(
+    schema: &Map<String, Value>,
+    object: &Map<String, Value>,
+    path: &str,
+    errors: &mut Vec<SchemaError>,
+) {
+    if let Some(Value::Array(required)) = schema.get("required") {
+        for key in required.iter().filter_map(|k| k.as_str()) {
+            if !object.contains_key(key) {
+                errors.push(SchemaError::new(
+                    path,
+                    format!("missing required property '{key}'"),
+                ));
+            }
+        }
+    }
+
+    let properties = schema.get("properties").and_then(|p| p.as_object());
+
+    for (key, value) in object {
+        let child_path = format!("{path}.{key}");
+        match properties.and_then(|p| p.get(key)) {
+            Some(property_schema) => validate_at(property_schema, value, &child_path, errors),
+            None => match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => {
+                    errors.push(SchemaError::new(
+                        &child_path,
+                        "additional property is not allowed",
+                    ));
+                }
+                Some(additional) => validate_at(additional, value, &child_path, errors),
+                None => {}
+            },
+        }
+    }
+}
+
+fn validate_array(
+    schema: &Map<String, Value>,
+    items: &[Value],
+    path: &str,
+    errors: &mut Vec<SchemaError>,
+) {
+    if let Some(min) = schema.get("minItems").and_then(|v| v.as_u64())
+        && (items.len() as u64) < min
+    {
+        errors.push(SchemaError::new(
+            path,
+            format!("expected at least {min} items, got {}", items.len()),
+        ));
+    }
+
+    if let Some(max) = schema.get("maxItems").and_then(|v| v.as_u64())
+        && (items.len() as u64) > max
+    {
+        errors.push(SchemaError::new(
+            path,
+            format!("expected at most {max} items, got {}", items.len()),
+        ));
+    }
+
+    if let Some(item_schema) = schema.get("items") {
+        for (index, item) in items.iter().enumerate() {
+            validate_at(item_schema, item, &format!("{path}[{index}]"), errors);
+        }
+    }
+}
+
+fn validate_string(
+    schema: &Map<String, Value>,
+    text: &str,
+    path: &str,
+    errors: &mut Vec<SchemaError>,
+) {
+    let length = text.chars().count() as u64;
+
+    if let Some(min) = schema.get("minLength").and_then(|v| v.as_u64())
+        && length < min
+    {
+        errors.push(SchemaError::new(
+            path,
+            format!("expected at least {min} characters, got {length}"),
+        ));
+    }
+
+    if let Some(max) = schema.get("maxLength").and_then(|v| v.as_u64())
+        && length > max
+    {
+        errors.push(SchemaError::new(
+            path,
+            format!("expected at most {max} characters, got {length}"),
+        ));
+    }
+}
+
+fn validate_number(
+    schema: &Map<String, Value>,
+    value: f64,
+    path: &str,
+    errors: &mut Vec<SchemaError>,
+) {
+    if let Some(min) = schema.get("minimum").and_then(|v| v.as_f64())
+        && value < min
+    {
+        errors.push(SchemaError::new(path, format!("{value} is less than minimum {min}")));
+    }
+
+    if let Some(max) = schema.get("maximum").and_then(|v| v.as_f64())
+        && value > max
+    {
+        errors.push(SchemaError::new(path, format!("{value} is greater than maximum {max}")));
+    }
+
+    if let Some(min) = schema.get("exclusiveMinimum").and_then(|v| v.as_f64())
+        && value <= min
+    {
+        errors.push(SchemaError::new(path, format!("{value} must be greater than {min}")));
+    }
+
+    if let Some(max) = schema.get("exclusiveMaximum").and_then(|v| v.as_f64())
+        && value >= max
+    {
+        errors.push(SchemaError::new(path, format!("{value} must be less than {max}")));
+    }
+}
+
+fn matches_type(expected: &Value, instance: &Value) -> bool {
+    match expected {
+        Value::String(name) => matches_type_name(name, instance),
+        Value::Array(names) => names
+            .iter()
+            .filter_map(|n| n.as_str())
+            .any(|name| matches_type_name(name, instance)),
+        _ => true,
+    }
+}
+
+fn matches_type_name(name: &str, instance: &Value) -> bool {
+    match name {
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "boolean" => instance.is_boolean(),
+        "null" => instance.is_null(),
+        "number" => instance.is_number(),
+        "integer" => match instance {
+            Value::Number(n) => {
+                n.is_i64() || n.is_u64() || n.as_f64().is_some_and(|f| f.fract() == 0.0)
+            }
+            _ => false,
+        },
+        _ => true,
+    }
+}
+
+fn describe_type(expected: &Value) -> String {
+    match expected {
+        Value::String(name) => name.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn type_name(instance: &Value) -> &'static str {
+    match instance {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["query", "limit"],
+            "properties": {
+                "query": {"type": "string", "minLength": 1},
+                "limit": {"type": "integer", "minimum": 1, "maximum": 50},
+                "mode": {"enum": ["fast", "deep"]},
+                "tags": {"type": "array", "items": {"type": "string"}, "maxItems": 3}
+            },
+            "additionalProperties": false
+        })
+    }
+
+    #[test]
+    fn test_valid_instance() {
+        let instance = json!({"query": "rust", "limit": 10, "mode": "fast", "tags": ["a"]});
+        assert!(validate(&schema(), &instance).is_ok());
+    }
+
+    #[test]
+    fn test_errors_are_path_annotated() {
+        let instance = json!({
+            "query": "",
+            "limit": 100,
+            "mode": "slow",
+            "tags": ["a", 2],
+            "extra": true
+        });
+
+        let errors = validate(&schema(), &instance).unwrap_err();
+        let paths: Vec<&str> = errors.iter().map(|e| e.path.as_str()).collect();
+
+        assert!(paths.contains(&"$.query"));
+        assert!(paths.contains(&"$.limit"));
+        assert!(paths.contains(&"$.mode"));
+        assert!(paths.contains(&"$.tags[1]"));
+        assert!(paths.contains(&"$.extra"));
+    }
+
+    #[test]
+    fn test_missing_required_and_wrong_type() {
+        let errors = validate(&schema(), &json!({"query": 1})).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().any(|e| e.message.contains("'limit'")));
+        assert!(errors.iter().any(|e| e.path == "$.query" && e.message.contains("string")));
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Display;
+use std::hash::Hash;
+
+/// 依赖图校验错误
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum DagError {
+    #[error("{node} 依赖了未知的节点 {dependency}")]
+    UnknownDependency { node: String, dependency: String },
+
+    #[error("{0} 依赖了自身")]
+    SelfDependency(String),
+
+    #[error("存在循环依赖: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+
+    #[error("节点重复: {0}")]
+    DuplicateNode(String),
+
+    #[error("{node} 依赖的 {dependency} 已失败")]
+    FailedDependency { node: String, dependency: String },
+}
+
+/// 依赖图
+///
+/// 节点按加入顺序保存，边由节点指向它的依赖。外部节点可以被依赖，但本身不参与排序，
+/// 例如已经完成的任务。任务队列和带依赖的计划步骤都用它来校验与排序。
+#[derive(Debug, Clone)]
+pub struct DependencyGraph<K> {
+    order: Vec<K>,
+    dependencies: HashMap<K, Vec<K>>,
+    external: HashSet<K>,
+}
+
+impl<K> Default for DependencyGraph<K> {
+    fn default() -> Self {
+        Self {
+            order: Vec::new(),
+            dependencies: HashMap::new(),
+            external: HashSet::new(),
+        }
+    }
+}
+
+impl<K> DependencyGraph<K>
+where
+    K: Clone + Eq + Hash + Display,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加节点；同一节点再次添加时替换其依赖
+    pub fn add_node(&mut self, id: K, dependencies: Vec<K>) {
+        if !self.dependencies.contains_key(&id) {
+            self.order.push(id.clone());
+        }
+        self.dependencies.insert(id, dependencies);
+    }
+
+    /// 添加可被依赖、但已不需要排序的外部节点
+    pub fn add_external(&mut self, id: K) {
+        self.external.insert(id);
+    }
+
+    /// 是否包含该节点（含外部节点）
+    pub fn contains(&self, id: &K) -> bool {
+        self.dependencies.contains_key(id) || self.external.contains(id)
+    }
+
+    /// 节点的直接依赖
+    pub fn dependencies_of(&self, id: &K) -> &[K] {
+        self.dependencies.get(id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// 校验：无自依赖、无未知依赖、无环
+    pub fn validate(&self) -> Result<(), DagError> {
+        self.topological_order().map(|_| ())
+    }
+
+    /// 拓扑排序，依赖在前；没有先后约束的节点保持加入顺序
+    pub fn topological_order(&self) -> Result<Vec<K>, DagError> {
+        let mut remaining: HashMap<&K, usize> = HashMap::new();
+        let mut dependents: HashMap<&K, Vec<&K>> = HashMap::new();
+
+        for id in &self.order {
+            let mut count = 0;
+            for dependency in self.dependencies_of(id) {
+                if dependency == id {
+                    return Err(DagError::SelfDependency(id.to_string()));
+                }
+                if self.dependencies.contains_key(dependency) {
+                    count += 1;
+                    dependents.entry(dependency).or_default().push(id);
+                } else if !self.external.contains(dependency) {
+                    return Err(DagError::UnknownDependency {
+                        node: id.to_string(),
+                        dependency: dependency.to_string(),
+                    });
+                }
+            }
+            remaining.insert(id, count);
+        }
+
+        let mut ready: VecDeque<&K> = self
+            .order
+            .iter()
+            .filter(|id| remaining[id] == 0)
+            .collect();
+        let mut sorted = Vec::with_capacity(self.order.len());
+
+        while let Some(id) = ready.pop_front() {
+            sorted.push(id.clone());
+            for dependent in dependents.get(id).into_iter().flatten() {
+                if let Some(count) = remaining.get_mut(dependent) {
+                    *count -= 1;
+                    if *count == 0 {
+                        ready.push_back(dependent);
+                    }
+                }
+            }
+        }
+
+        if sorted.len() < self.order.len() {
+            let blocked: HashSet<&K> = remaining
+                .iter()
+                .filter(|(_, count)| **count > 0)
+                .map(|(id, _)| *id)
+                .collect();
+            return Err(DagError::Cycle(self.find_cycle(&blocked)));
+        }
+        Ok(sorted)
+    }
+
+    /// 在仍有未满足依赖的节点中找出一个环
+    fn find_cycle(&self, blocked: &HashSet<&K>) -> Vec<String> {
+        let Some(start) = self.order.iter().find(|id| blocked.contains(id)) else {
+            return Vec::new();
+        };
+
+        // 每个受阻节点至少有一个受阻的依赖，沿依赖前进必然回到走过的节点
+        let mut path: Vec<&K> = vec![start];
+        let mut current = start;
+        loop {
+            let Some(next) = self
+                .dependencies_of(current)
+                .iter()
+                .find(|dependency| blocked.contains(dependency))
+            else {
+                return Vec::new();
+            };
+            if let Some(index) = path.iter().position(|id| *id == next) {
+                let mut cycle: Vec<String> = path[index ..].iter().map(|id| id.to_string()).collect();
+                cycle.push(next.to_string());
+                return cycle;
+            }
+            path.push(next);
+            current = next;
+        }
+    }
+
+    /// 直接或间接依赖 `id` 的全部节点，按加入顺序排列
+    pub fn dependents(&self, id: &K) -> Vec<K> {
+        let mut affected: HashSet<&K> = HashSet::from([id]);
+        loop {
+            let before = affected.len();
+            for node in &self.order {
+                if !affected.contains(node)
+                    && self.dependencies_of(node).iter().any(|dep| affected.contains(dep))
+                {
+                    affected.insert(node);
+                }
+            }
+            if affected.len() == before {
+                break;
+            }
+        }
+
+        self.order
+            .iter()
+            .filter(|node| *node != id && affected.contains(node))
+            .cloned()
+            .collect()
+    }
+
+    /// 关键路径：总耗时最长的依赖链，依赖在前
+    ///
+    /// 返回路径及其总耗时；图为空时返回空路径。
+    pub fn critical_path(&self, cost: impl Fn(&K) -> u64) -> Result<(Vec<K>, u64), DagError> {
+        let order = self.topological_order()?;
+
+        let mut total: HashMap<&K, u64> = HashMap::new();
+        let mut previous: HashMap<&K, &K> = HashMap::new();
+        for id in &order {
+            let longest = self
+                .dependencies_of(id)
+                .iter()
+                .filter_map(|dependency| total.get(dependency).map(|t| (dependency, *t)))
+                .max_by_key(|(_, t)| *t);
+            let mut sum = cost(id);
+            if let Some((dependency, t)) = longest {
+                sum += t;
+                previous.insert(id, dependency);
+            }
+            total.insert(id, sum);
+        }
+
+        let Some((end, length)) = order
+            .iter()
+            .map(|id| (id, total[id]))
+            .max_by_key(|(_, t)| *t)
+        else {
+            return Ok((Vec::new(), 0));
+        };
+
+        let mut path = vec![end.clone()];
+        let mut current = end;
+        while let Some(dependency) = previous.get(current) {
+            path.push((*dependency).clone());
+            current = dependency;
+        }
+        path.reverse();
+        Ok((path, length))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(&'static str, &[&'static str])]) -> DependencyGraph<&'static str> {
+        let mut graph = DependencyGraph::new();
+        for (id, dependencies) in edges {
+            graph.add_node(*id, dependencies.to_vec());
+        }
+        graph
+    }
+
+    #[test]
+    fn test_topological_order_and_critical_path() {
+        let mut graph = graph(&[("d", &["b", "c"]), ("b", &["a"]), ("c", &["done"]), ("a", &[])]);
+        graph.add_external("done");
+
+        assert_eq!(graph.topological_order().unwrap(), vec!["c", "a", "b", "d"]);
+        assert_eq!(graph.dependents(&"a"), vec!["d", "b"]);
+
+        let (path, length) = graph.critical_path(|id| if *id == "c" { 5 } else { 1 }).unwrap();
+        assert_eq!(path, vec!["c", "d"]);
+        assert_eq!(length, 6);
+    }
+
+    #[test]
+    fn test_invalid_graphs() {
+        assert_eq!(
+            graph(&[("a", &["a"])]).validate(),
+            Err(DagError::SelfDependency("a".into()))
+        );
+        assert!(matches!(
+            graph(&[("a", &["missing"])]).validate(),
+            Err(DagError::UnknownDependency { .. })
+        ));
+        assert_eq!(
+            graph(&[("x", &[]), ("a", &["c"]), ("b", &["a"]), ("c", &["b"])]).validate(),
+            Err(DagError::Cycle(vec!["a".into(), "c".into(), "b".into(), "a".into()]))
+        );
+    }
+}
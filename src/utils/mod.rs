@@ -1 +1,3 @@
+pub mod dag;
+pub mod retry;
 pub mod string_util;
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// 失败后的重试策略，任务重试、消息投递重试和节点重连共用
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// 最多重试次数，用尽后放弃（任务进入死信列表）
+    pub max_retries: u32,
+    /// 第一次重试前的等待时间（毫秒）
+    pub initial_backoff_ms: u64,
+    /// 等待时间上限（毫秒）
+    pub max_backoff_ms: u64,
+    /// 每次重试等待时间的增长倍数
+    pub multiplier: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff_ms: 1_000,
+            max_backoff_ms: 60_000,
+            multiplier: 2.0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// 不重试
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// 第 `attempt` 次重试（从 1 开始）前的等待时间
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let millis = self.initial_backoff_ms as f64 * self.multiplier.max(1.0).powi(exponent);
+        Duration::from_millis(millis.min(self.max_backoff_ms as f64) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_backoff() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            initial_backoff_ms: 100,
+            max_backoff_ms: 1_000,
+            multiplier: 3.0,
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(300));
+        assert_eq!(policy.backoff(3), Duration::from_millis(900));
+        assert_eq!(policy.backoff(4), Duration::from_millis(1_000));
+    }
+}
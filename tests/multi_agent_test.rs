@@ -179,4 +179,602 @@ mod multi_agent_tests {
         let dequeued = queue.dequeue().await.unwrap();
         assert_eq!(dequeued.priority, Priority::High);
     }
+
+    #[tokio::test]
+    async fn test_manager_routes_messages_to_agent() {
+        use rusagent::multi_agent::{AgentManager, AgentManagerConfig};
+
+        let context = Arc::new(GlobalContext::default());
+        let manager = AgentManager::new(context, AgentManagerConfig::default());
+
+        let executor = Box::new(ExecutorAgent::new(Some("executor-001".to_string()), vec![]));
+        let executor_id = manager.spawn_agent(executor).await.unwrap();
+
+        // 注册一个测试端用于接收回复
+        let bus = manager.get_message_bus();
+        let mut tester = bus.register_agent("tester".to_string()).await.unwrap();
+
+        let msg = Message::new(
+            "tester".to_string(),
+            Some(executor_id.clone()),
+            MessageType::TaskAssignment,
+            serde_json::json!({"id": "task-001"}),
+        );
+        manager.send_message(msg).await.unwrap();
+
+        // Executor应该处理消息并回复结果
+        let reply = tokio::time::timeout(std::time::Duration::from_secs(2), tester.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reply.sender_id, executor_id);
+        assert_eq!(reply.message_type, MessageType::ResultNotification);
+        assert_eq!(reply.payload["task_id"], "task-001");
+
+        manager.shutdown_all().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_pause_resume_and_stop_agent() {
+        use std::time::Duration;
+
+        use rusagent::{
+            agent::types::AgentLifecycleState,
+            multi_agent::{AgentManager, AgentManagerConfig, communication::message::ControlCommand},
+        };
+
+        let context = Arc::new(GlobalContext::default());
+        let manager = AgentManager::new(context.clone(), AgentManagerConfig::default());
+
+        let executor = Box::new(ExecutorAgent::new(Some("executor-002".to_string()), vec![]));
+        let executor_id = manager.spawn_agent(executor).await.unwrap();
+
+        let bus = manager.get_message_bus();
+        let mut tester = bus.register_agent("tester".to_string()).await.unwrap();
+
+        // 暂停后消息应被缓存
+        manager.pause_agent(&executor_id).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(
+            manager.get_agent_state(&executor_id).await.unwrap(),
+            AgentLifecycleState::Paused
+        );
+
+        let msg = Message::new(
+            "tester".to_string(),
+            Some(executor_id.clone()),
+            MessageType::TaskAssignment,
+            serde_json::json!({"id": "task-002"}),
+        );
+        manager.send_message(msg).await.unwrap();
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), tester.recv())
+                .await
+                .is_err()
+        );
+
+        // 暂停时的取消消息立即生效
+        let cancel = Message::new(
+            "tester".to_string(),
+            Some(executor_id.clone()),
+            MessageType::TaskCancellation,
+            serde_json::json!({"task_id": "task-003"}),
+        );
+        manager.send_message(cancel).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(context.cancellations.is_cancelled("task-003"));
+
+        // 恢复后缓存的消息被处理
+        manager.resume_agent(&executor_id).await.unwrap();
+        let reply = tokio::time::timeout(Duration::from_secs(2), tester.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reply.payload["task_id"], "task-002");
+        assert_eq!(
+            manager.get_agent_state(&executor_id).await.unwrap(),
+            AgentLifecycleState::Running
+        );
+
+        // Stop命令会调用shutdown，并把Agent从管理器、注册表和消息总线中移除
+        manager
+            .send_control(&executor_id, ControlCommand::Stop)
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(manager.get_agent_state(&executor_id).await.is_err());
+        assert!(manager.get_registry().get_agent(&executor_id).await.is_none());
+        assert!(!bus.get_local_agents().await.contains(&executor_id));
+
+        manager.shutdown_all().await.unwrap();
+    }
+
+    /// 返回固定计划的规划Agent，消息格式与 PlannerAgent 相同
+    struct FixedPlanner;
+
+    #[async_trait::async_trait]
+    impl AgentBehavior for FixedPlanner {
+        fn get_id(&self) -> &str {
+            "fixed-planner"
+        }
+
+        fn get_type(&self) -> rusagent::agent::types::AgentType {
+            rusagent::agent::types::AgentType::Planner
+        }
+
+        fn get_capabilities(&self) -> &[AgentCapability] {
+            &[]
+        }
+
+        async fn initialize(
+            &mut self,
+            _context: Arc<GlobalContext>,
+        ) -> rusagent::error::Result<()> {
+            Ok(())
+        }
+
+        async fn process_message(
+            &mut self,
+            message: Message,
+        ) -> rusagent::error::Result<Option<Message>> {
+            let plan = serde_json::json!({
+                "plan_id": "runtime-mode",
+                "steps": [{
+                    "step_id": 1,
+                    "description": "echo",
+                    "action": "call_tool",
+                    "tool": "runtime_mode_echo",
+                    "parameters": {"text": message.payload["goal"]}
+                }]
+            });
+            Ok(Some(Message::new(
+                "fixed-planner".to_string(),
+                Some(message.sender_id),
+                MessageType::ResultNotification,
+                serde_json::json!({"status": "success", "plan": plan}),
+            )))
+        }
+
+        async fn shutdown(&mut self) -> rusagent::error::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct EchoTool;
+
+    #[async_trait::async_trait]
+    impl rusagent::tools::Tool for EchoTool {
+        fn name(&self) -> &str {
+            "runtime_mode_echo"
+        }
+
+        fn description(&self) -> &str {
+            "Echo the text argument"
+        }
+
+        fn input_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object", "required": ["text"]})
+        }
+
+        async fn call(
+            &self,
+            arguments: serde_json::Value,
+        ) -> rusagent::error::Result<serde_json::Value> {
+            Ok(serde_json::json!({"result": arguments["text"]}))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_task_in_both_runtime_modes() {
+        use rusagent::{
+            agent::types::RuntimeMode,
+            agents::VerifierAgent,
+            input::UserTaskInput,
+            runtime::{RuntimeAgents, run_task_with_agents},
+            shared::global_context::GlobalConfig,
+            tools::register_native_tool,
+        };
+
+        register_native_tool(Arc::new(EchoTool));
+        let input = UserTaskInput::new("ping".into(), String::new(), None, None, None);
+
+        // 仅通过配置切换运行模式
+        for mode in [RuntimeMode::SingleAgent, RuntimeMode::MultiAgent] {
+            let config = GlobalConfig {
+                runtime_mode: mode,
+                ..Default::default()
+            };
+            let agents = RuntimeAgents {
+                planner: Box::new(FixedPlanner),
+                executor: Box::new(ExecutorAgent::new(None, vec![])),
+                verifier: Box::new(VerifierAgent::new(None)),
+            };
+
+            let context = Arc::new(GlobalContext::new(config));
+            let report = run_task_with_agents(context, agents, &input).await.unwrap();
+            assert!(report.is_succeeded(), "{mode:?} run should succeed");
+            assert_eq!(
+                report.plan.steps[0].output,
+                Some(serde_json::json!({"result": "ping"}))
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_master_dispatches_by_capability() {
+        use std::time::Duration;
+
+        use rusagent::{
+            agent::types::{Priority, TaskStatus, TaskType},
+            multi_agent::{
+                AgentManager, AgentManagerConfig, communication::MessageFilter,
+                coordination::Task,
+            },
+        };
+
+        let context = Arc::new(GlobalContext::default());
+        let manager = AgentManager::new(context, AgentManagerConfig::default());
+        let bus = manager.get_message_bus();
+
+        manager
+            .spawn_agent(Box::new(ExecutorAgent::new(Some("executor-plain".to_string()), vec![])))
+            .await
+            .unwrap();
+        manager
+            .spawn_agent(Box::new(ExecutorAgent::with_tool_capability(
+                Some("executor-calc".to_string()),
+                "calculator".to_string(),
+            )))
+            .await
+            .unwrap();
+
+        let master = MasterAgent::new(Some("master-dispatch".to_string()))
+            .with_dispatch(manager.get_registry(), bus.clone());
+        let queue = master.task_queue();
+        manager.spawn_agent(Box::new(master)).await.unwrap();
+        let _tester = bus.register_agent("tester".to_string()).await.unwrap();
+
+        // 需要 calculator 工具能力的任务只能分配给 executor-calc
+        let task = Task::new(
+            TaskType::Execution,
+            Priority::Normal,
+            serde_json::json!({"description": "2 + 2"}),
+            "tester".to_string(),
+        )
+        .with_capabilities(vec![AgentCapability::ToolCalling("calculator".to_string())]);
+        let task_id = task.id.clone();
+
+        manager
+            .send_message(Message::new(
+                "tester".to_string(),
+                Some("master-dispatch".to_string()),
+                MessageType::TaskAssignment,
+                serde_json::to_value(&task).unwrap(),
+            ))
+            .await
+            .unwrap();
+
+        let completed = tokio::time::timeout(Duration::from_secs(2), async {
+            while queue.get_task_status(&task_id).await != Some(TaskStatus::Completed) {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await;
+        assert!(completed.is_ok(), "task should be completed");
+
+        // 只有 executor-calc 回复了执行结果
+        let filter = MessageFilter::new().with_types(vec![MessageType::ResultNotification]);
+        let executors: Vec<String> = bus
+            .get_history(Some(filter))
+            .await
+            .iter()
+            .filter(|m| m.payload["task_id"] == task_id.as_str() && m.payload["result"].is_object())
+            .map(|m| m.sender_id.clone())
+            .collect();
+        assert_eq!(executors, vec!["executor-calc".to_string()]);
+
+        manager.shutdown_all().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_master_fails_task_on_unsuccessful_result() {
+        use std::time::Duration;
+
+        use rusagent::{
+            agent::types::{AgentType, Priority, TaskStatus, TaskType},
+            multi_agent::{
+                AgentInfo, AgentManager, AgentManagerConfig,
+                coordination::{RetryPolicy, Task},
+            },
+        };
+
+        let context = Arc::new(GlobalContext::default());
+        let manager = AgentManager::new(context, AgentManagerConfig::default());
+        let bus = manager.get_message_bus();
+        let registry = manager.get_registry();
+
+        // 手动应答的执行者：执行了步骤，但结果标记为失败
+        let mut worker = bus.register_agent("worker".to_string()).await.unwrap();
+        registry
+            .register(AgentInfo::new(
+                "worker".to_string(),
+                AgentType::Executor,
+                vec![AgentCapability::TaskExecution],
+            ))
+            .await
+            .unwrap();
+
+        let master = MasterAgent::new(Some("master-failing".to_string()))
+            .with_dispatch(registry.clone(), bus.clone());
+        let queue = master.task_queue();
+        manager.spawn_agent(Box::new(master)).await.unwrap();
+
+        let task = Task::new(
+            TaskType::Execution,
+            Priority::Normal,
+            serde_json::json!({"description": "call a failing tool"}),
+            "tester".to_string(),
+        )
+        .with_retry_policy(RetryPolicy::none());
+        let task_id = task.id.clone();
+        manager
+            .send_message(Message::new(
+                "tester".to_string(),
+                Some("master-failing".to_string()),
+                MessageType::TaskAssignment,
+                serde_json::to_value(&task).unwrap(),
+            ))
+            .await
+            .unwrap();
+
+        let assignment = tokio::time::timeout(Duration::from_secs(2), worker.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(assignment.message_type, MessageType::TaskAssignment);
+        bus.send(Message::response(
+            "worker".to_string(),
+            assignment.sender_id.clone(),
+            MessageType::ResultNotification,
+            serde_json::json!({
+                "task_id": task_id,
+                "status": "completed",
+                "result": {"output": "tool failed", "success": false},
+            }),
+            assignment.id.clone(),
+        ))
+        .await
+        .unwrap();
+
+        let failed = tokio::time::timeout(Duration::from_secs(2), async {
+            while !matches!(queue.get_task_status(&task_id).await, Some(TaskStatus::Failed(_))) {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await;
+        assert!(failed.is_ok(), "unsuccessful result should fail the task");
+
+        manager.shutdown_all().await.unwrap();
+    }
+
+    /// 固定的两级分解：根任务 -> [s1, s2(复合)]，s2 -> [s3, s4]
+    struct FixedDecomposer;
+
+    #[async_trait::async_trait]
+    impl rusagent::multi_agent::coordination::TaskDecomposer for FixedDecomposer {
+        async fn decompose(
+            &self,
+            task: &rusagent::multi_agent::coordination::Task,
+            _allow_composite: bool,
+        ) -> rusagent::error::Result<Vec<rusagent::multi_agent::coordination::SubtaskSpec>> {
+            let text = if task.payload["description"] == "report" {
+                r#"{"subtasks": [
+                    {"id": "s1", "description": "collect", "task_type": "Execution"},
+                    {"id": "s2", "description": "write", "task_type": "Composite",
+                     "depends_on": ["s1"]}
+                ]}"#
+            } else {
+                r#"{"subtasks": [
+                    {"id": "s3", "description": "draft", "task_type": "Execution"},
+                    {"id": "s4", "description": "polish", "task_type": "Execution",
+                     "depends_on": ["s3"]}
+                ]}"#
+            };
+            rusagent::multi_agent::coordination::decomposer::parse_decomposition(text, true)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_master_completes_decomposed_task() {
+        use std::time::Duration;
+
+        use rusagent::{
+            agent::types::{Priority, TaskStatus, TaskType},
+            multi_agent::{AgentManager, AgentManagerConfig, coordination::Task},
+        };
+
+        let context = Arc::new(GlobalContext::default());
+        let manager = AgentManager::new(context, AgentManagerConfig::default());
+        let bus = manager.get_message_bus();
+
+        manager
+            .spawn_agent(Box::new(ExecutorAgent::new(Some("executor-sub".to_string()), vec![])))
+            .await
+            .unwrap();
+
+        let master = MasterAgent::new(Some("master-decompose".to_string()))
+            .with_dispatch(manager.get_registry(), bus.clone())
+            .with_decomposer(Arc::new(FixedDecomposer));
+        let queue = master.task_queue();
+        manager.spawn_agent(Box::new(master)).await.unwrap();
+        let mut tester = bus.register_agent("tester".to_string()).await.unwrap();
+
+        let task = Task::new(
+            TaskType::Composite,
+            Priority::Normal,
+            serde_json::json!({"description": "report"}),
+            "tester".to_string(),
+        );
+        let task_id = task.id.clone();
+        manager
+            .send_message(Message::new(
+                "tester".to_string(),
+                Some("master-decompose".to_string()),
+                MessageType::TaskAssignment,
+                serde_json::to_value(&task).unwrap(),
+            ))
+            .await
+            .unwrap();
+
+        // 三个叶子子任务被入队
+        let accepted = tokio::time::timeout(Duration::from_secs(2), tester.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(accepted.payload["subtasks"], 3);
+
+        // 所有子任务完成后父任务才完成
+        let completed = tokio::time::timeout(Duration::from_secs(3), async {
+            while queue.get_task_status(&task_id).await != Some(TaskStatus::Completed) {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await;
+        assert!(completed.is_ok(), "parent task should be completed");
+        assert_eq!(queue.get_stats().await.completed, 5);
+
+        manager.shutdown_all().await.unwrap();
+    }
+
+    /// 一直等待直到被取消的工具
+    struct SlowTool;
+
+    #[async_trait::async_trait]
+    impl rusagent::tools::Tool for SlowTool {
+        fn name(&self) -> &str {
+            "cancellation_slow_tool"
+        }
+
+        fn description(&self) -> &str {
+            "Wait for a long time"
+        }
+
+        fn input_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object"})
+        }
+
+        async fn call(
+            &self,
+            _arguments: serde_json::Value,
+        ) -> rusagent::error::Result<serde_json::Value> {
+            tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+            Ok(serde_json::json!({"result": "done"}))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_running_task() {
+        use std::time::Duration;
+
+        use rusagent::{
+            agent::types::{Priority, TaskStatus, TaskType},
+            multi_agent::{AgentManager, AgentManagerConfig, coordination::Task},
+            tools::register_native_tool,
+        };
+
+        register_native_tool(Arc::new(SlowTool));
+
+        let context = Arc::new(GlobalContext::default());
+        let manager = AgentManager::new(context, AgentManagerConfig::default());
+        let bus = manager.get_message_bus();
+
+        manager
+            .spawn_agent(Box::new(ExecutorAgent::new(Some("executor-slow".to_string()), vec![])))
+            .await
+            .unwrap();
+        let master = MasterAgent::new(Some("master-cancel".to_string()))
+            .with_dispatch(manager.get_registry(), bus.clone());
+        let queue = master.task_queue();
+        manager.spawn_agent(Box::new(master)).await.unwrap();
+        let mut tester = bus.register_agent("tester".to_string()).await.unwrap();
+
+        let slow = Task::new(
+            TaskType::Execution,
+            Priority::Normal,
+            serde_json::json!({"step": {
+                "step_id": 1,
+                "description": "wait",
+                "action": "call_tool",
+                "tool": "cancellation_slow_tool",
+                "parameters": {}
+            }}),
+            "tester".to_string(),
+        );
+        let mut dependent = Task::new(
+            TaskType::Execution,
+            Priority::Normal,
+            serde_json::json!({}),
+            "tester".to_string(),
+        );
+        dependent.add_dependency(slow.id.clone());
+
+        for task in [&slow, &dependent] {
+            manager
+                .send_message(Message::new(
+                    "tester".to_string(),
+                    Some("master-cancel".to_string()),
+                    MessageType::TaskAssignment,
+                    serde_json::to_value(task).unwrap(),
+                ))
+                .await
+                .unwrap();
+        }
+
+        let started = tokio::time::timeout(Duration::from_secs(2), async {
+            while queue.get_task_status(&slow.id).await != Some(TaskStatus::InProgress) {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await;
+        assert!(started.is_ok(), "task should be running");
+
+        manager
+            .send_message(Message::new(
+                "tester".to_string(),
+                Some("master-cancel".to_string()),
+                MessageType::TaskCancellation,
+                serde_json::json!({"task_id": slow.id}),
+            ))
+            .await
+            .unwrap();
+
+        // 正在执行的工具调用被中止，依赖它的任务也被取消
+        let waited = tokio::time::timeout(Duration::from_secs(2), async {
+            let reply = loop {
+                let message = tester.recv().await.unwrap();
+                if message.payload["status"] == "cancelled" {
+                    break message;
+                }
+            };
+            loop {
+                let history = bus.get_history(None).await;
+                if history
+                    .iter()
+                    .any(|m| m.sender_id == "executor-slow" && m.payload["status"] == "cancelled")
+                {
+                    break reply;
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await;
+        assert!(waited.is_ok(), "cancellation should finish before the tool does");
+
+        let cancelled = waited.unwrap().payload["cancelled"].clone();
+        assert_eq!(cancelled.as_array().map(|ids| ids.len()), Some(2));
+        assert_eq!(queue.get_task_status(&slow.id).await, Some(TaskStatus::Cancelled));
+        assert_eq!(queue.get_task_status(&dependent.id).await, Some(TaskStatus::Cancelled));
+
+        manager.shutdown_all().await.unwrap();
+    }
 }